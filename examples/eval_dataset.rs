@@ -0,0 +1,195 @@
+//! Batch accuracy report for the recognizer.
+//!
+//! Reads a CSV manifest of `path,expected` lines, where `expected` is a
+//! `GestureKind` variant name (e.g. `Stop`, `Like`) or `None` for images
+//! without a hand, runs every image through the recognizer and prints a
+//! confusion matrix with the overall accuracy. Relative paths are resolved
+//! against the manifest's directory; blank lines and `#` comments are skipped.
+
+#[allow(dead_code)]
+#[path = "../src/gesture.rs"]
+mod gesture;
+#[allow(dead_code)]
+#[path = "../src/model_download.rs"]
+mod model_download;
+#[allow(dead_code)]
+#[path = "../src/pipeline"]
+mod pipeline {
+    pub mod channel;
+    pub mod gamma;
+    pub mod recognizer;
+}
+#[allow(dead_code)]
+#[path = "../src/types.rs"]
+mod types;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use pipeline::recognizer::{Recognizer, RecognizerBackend};
+use types::Frame;
+
+/// Bucket for images where the recognizer found no hand or no gesture.
+const NO_DETECTION: &str = "None";
+/// Synthetic spacing between images, well beyond the vote window and the
+/// tracker's lifetime so one image never influences the next.
+const IMAGE_SPACING: Duration = Duration::from_secs(2);
+
+struct Sample {
+    path: PathBuf,
+    expected: String,
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let manifest_path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("usage: eval_dataset <manifest.csv>"))?;
+    let samples = read_manifest(&manifest_path)?;
+    if samples.is_empty() {
+        bail!("manifest {} lists no images", manifest_path.display());
+    }
+
+    let mut recognizer = Recognizer::new(&RecognizerBackend::default())?;
+
+    let base = Instant::now();
+    // (expected, predicted) -> count
+    let mut confusion: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for (idx, sample) in samples.iter().enumerate() {
+        let mut frame = load_frame(&sample.path)?;
+        frame.timestamp = base + IMAGE_SPACING * idx as u32;
+        let result = recognizer
+            .process(&frame)
+            .with_context(|| format!("recognition failed for {}", sample.path.display()))?;
+
+        let predicted = result
+            .detail
+            .as_ref()
+            .map(|detail| format!("{:?}", detail.primary))
+            .unwrap_or_else(|| NO_DETECTION.to_string());
+        let marker = if predicted.eq_ignore_ascii_case(&sample.expected) {
+            "ok "
+        } else {
+            "ERR"
+        };
+        println!(
+            "{marker} {}: expected {}, got {predicted} ({:.2})",
+            sample.path.display(),
+            sample.expected,
+            result.confidence
+        );
+        *confusion
+            .entry((sample.expected.clone(), predicted))
+            .or_default() += 1;
+    }
+
+    print_report(&confusion, samples.len());
+    Ok(())
+}
+
+fn read_manifest(path: &Path) -> Result<Vec<Sample>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("无法读取清单 {}", path.display()))?;
+    let root = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut samples = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (image, expected) = line
+            .rsplit_once(',')
+            .ok_or_else(|| anyhow!("line {}: expected `path,gesture`", line_no + 1))?;
+        let (image, expected) = (image.trim(), expected.trim());
+        if line_no == 0 && image.eq_ignore_ascii_case("path") {
+            continue;
+        }
+        samples.push(Sample {
+            path: root.join(image),
+            expected: expected.to_string(),
+        });
+    }
+    Ok(samples)
+}
+
+fn load_frame(path: &Path) -> Result<Frame> {
+    let rgba = image::open(path)
+        .with_context(|| format!("无法打开图片 {}", path.display()))?
+        .to_rgba8();
+    let (width, height) = rgba.dimensions();
+    Frame::from_rgba(width, height, rgba.into_raw()).context("图片尺寸与像素数据不符")
+}
+
+fn print_report(confusion: &BTreeMap<(String, String), usize>, total: usize) {
+    // Labels are compared case-insensitively but printed as first seen.
+    let mut labels: Vec<&str> = Vec::new();
+    for (expected, predicted) in confusion.keys() {
+        for label in [expected.as_str(), predicted.as_str()] {
+            if !labels.iter().any(|seen| seen.eq_ignore_ascii_case(label)) {
+                labels.push(label);
+            }
+        }
+    }
+    labels.sort_by_key(|label| {
+        (
+            label.eq_ignore_ascii_case(NO_DETECTION),
+            label.to_lowercase(),
+        )
+    });
+
+    let count = |expected: &str, predicted: &str| -> usize {
+        confusion
+            .iter()
+            .filter(|((e, p), _)| {
+                e.eq_ignore_ascii_case(expected) && p.eq_ignore_ascii_case(predicted)
+            })
+            .map(|(_, n)| *n)
+            .sum()
+    };
+
+    let width = labels
+        .iter()
+        .map(|label| label.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    println!();
+    println!("Confusion matrix (rows: expected, columns: predicted)");
+    print!("{:width$}", "");
+    for label in &labels {
+        print!(" {label:>width$}");
+    }
+    println!();
+    let mut correct = 0;
+    for expected in &labels {
+        let row: Vec<usize> = labels
+            .iter()
+            .map(|predicted| count(expected, predicted))
+            .collect();
+        if row.iter().all(|n| *n == 0) {
+            continue;
+        }
+        print!("{expected:width$}");
+        for (predicted, n) in labels.iter().zip(&row) {
+            if predicted == expected {
+                correct += n;
+            }
+            print!(" {n:>width$}");
+        }
+        println!();
+    }
+
+    println!();
+    println!(
+        "Accuracy: {correct}/{total} ({:.1}%)",
+        correct as f32 / total.max(1) as f32 * 100.0
+    );
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 #[path = "../src/gesture.rs"]
 mod gesture;
 #[allow(dead_code)]
@@ -10,7 +11,7 @@ mod recognizer_common;
 #[path = "../src/types.rs"]
 mod types;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result};
 use gesture::GestureClassifier;
 use std::path::PathBuf;
 use types::Frame;
@@ -39,7 +40,10 @@ fn main() -> Result<()> {
     let model_path = model_download::default_handpose_estimator_model_path();
     model_download::ensure_handpose_estimator_model_ready(&model_path, |_evt| {})?;
     let mut model = HandposeModel::new(&model_path)?;
-    let mut classifier = GestureClassifier::new();
+    let mut classifier = GestureClassifier::new(
+        model_download::SessionThreads::default(),
+        cfg!(feature = "embed-models"),
+    );
 
     println!(
         "使用模型 {} 对 {} 张图片进行手势分类",
@@ -105,10 +109,11 @@ impl HandposeModel {
     fn new(model_path: &PathBuf) -> Result<Self> {
         model_download::ensure_handpose_estimator_model_ready(model_path, |_evt| {})?;
 
-        let model = Session::builder()?
+        let builder = Session::builder()?
             .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_intra_threads(2)?
-            .commit_from_file(model_path)
+            .with_intra_threads(2)?;
+        let model = model_download::ModelSource::File(model_path.clone())
+            .commit(builder)
             .with_context(|| format!("failed to load model from {}", model_path.display()))?;
 
         Ok(Self { model })
@@ -124,8 +129,11 @@ impl HandposeModel {
             raw_landmarks: inference.landmarks,
             projected_landmarks: projected,
             confidence: inference.confidence.clamp(0.0, 1.0),
+            palm_score: None,
+            handpose_confidence: inference.confidence.clamp(0.0, 1.0),
             handedness: inference.handedness,
             palm_regions: Vec::new(),
+            secondary_hands: Vec::new(),
         })
     }
 }
@@ -145,32 +153,15 @@ fn run_model(model: &mut Model, input: ndarray::Array4<f32>) -> Result<Inference
 }
 
 fn decode_ort_outputs(outputs: &ort::session::SessionOutputs<'_>) -> Result<InferenceResult> {
-    if outputs.len() == 0 {
-        return Err(anyhow!("model returned no outputs"));
-    }
-
-    let coords = outputs[0].try_extract_array::<f32>()?;
-    let flattened: Vec<f32> = coords.iter().copied().collect();
-    let landmarks = recognizer_common::decode_landmarks(&flattened)?;
-
-    let confidence = if outputs.len() > 1 {
-        outputs[1]
-            .try_extract_array::<f32>()
-            .ok()
-            .and_then(|v| v.iter().next().copied())
-            .unwrap_or(0.0)
-    } else {
-        0.0
-    };
-    let handedness = if outputs.len() > 2 {
-        outputs[2]
-            .try_extract_array::<f32>()
-            .ok()
-            .and_then(|v| v.iter().next().copied())
-            .unwrap_or(0.0)
-    } else {
-        0.0
-    };
+    let values = (0..outputs.len())
+        .map(|idx| {
+            outputs[idx]
+                .try_extract_array::<f32>()
+                .map(|array| array.iter().copied().collect())
+                .with_context(|| format!("handpose output {idx} is not an f32 tensor"))
+        })
+        .collect::<Result<Vec<Vec<f32>>>>()?;
+    let (landmarks, confidence, handedness) = recognizer_common::decode_handpose_outputs(&values)?;
 
     Ok(InferenceResult {
         landmarks,
@@ -184,14 +175,7 @@ fn load_frame(path: &PathBuf) -> Result<Frame> {
         .with_context(|| format!("无法打开图片 {}", path.display()))?
         .to_rgba8();
     let (width, height) = image.dimensions();
-    let rgba = image.into_raw();
-
-    Ok(Frame {
-        rgba,
-        width,
-        height,
-        timestamp: std::time::Instant::now(),
-    })
+    Frame::from_rgba(width, height, image.into_raw()).context("图片尺寸与像素数据不符")
 }
 
 fn demo_images() -> Result<Vec<PathBuf>> {
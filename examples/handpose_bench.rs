@@ -5,7 +5,7 @@ mod model_download;
 use anyhow::{Context, Result};
 use image::{RgbaImage, imageops::FilterType};
 use model_download::{
-    default_handpose_estimator_model_path, ensure_handpose_estimator_model_ready,
+    SessionThreads, default_handpose_estimator_model_path, ensure_handpose_estimator_model_ready,
 };
 use std::{
     path::PathBuf,
@@ -27,6 +27,8 @@ struct InferenceResult {
 }
 
 const INPUT_SIZE: u32 = 224;
+/// Intra-op thread counts compared by the benchmark.
+const THREAD_COUNTS: &[usize] = &[1, 2, 4];
 
 fn main() -> Result<()> {
     env_logger::init();
@@ -44,26 +46,42 @@ fn main() -> Result<()> {
 
     let input_tensor = prepare_tensor(&input_image).context("failed to read input image")?;
     ensure_handpose_estimator_model_ready(&model_path, |_evt| {})?;
-    let mut model = load_model(&model_path)?;
-
     println!(
-        "Benchmarking model {} on {} for {}s",
+        "Benchmarking model {} on {} for {}s per thread count (default intra threads: {})",
         model_path.display(),
         input_image.display(),
-        duration_secs
+        duration_secs,
+        SessionThreads::default().intra
     );
 
+    for &intra in THREAD_COUNTS {
+        let threads = SessionThreads { intra, inter: 1 };
+        let mut model = load_model(&model_path, threads)?;
+        bench(
+            &mut model,
+            &input_tensor,
+            intra,
+            Duration::from_secs(duration_secs.max(1)),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn bench(
+    model: &mut Model,
+    input_tensor: &InputTensor,
+    intra: usize,
+    duration: Duration,
+) -> Result<()> {
     // Warm-up once to trigger any lazy initialisation.
-    let warmup = infer(&mut model, input_tensor.clone())?;
-    let warmup_conf = warmup.confidence;
-    println!("Warm-up done (conf {:.3})", warmup_conf);
+    let warmup = infer(model, input_tensor.clone())?;
+    let mut last_conf = warmup.confidence;
 
-    let duration = Duration::from_secs(duration_secs.max(1));
     let start = Instant::now();
     let mut iterations: u64 = 0;
-    let mut last_conf = warmup_conf;
     while start.elapsed() < duration {
-        let outputs = infer(&mut model, input_tensor.clone())?;
+        let outputs = infer(model, input_tensor.clone())?;
         last_conf = outputs.confidence;
         iterations += 1;
     }
@@ -71,20 +89,19 @@ fn main() -> Result<()> {
     let fps = iterations as f64 / elapsed.as_secs_f64();
 
     println!(
-        "Ran {} inferences in {:.3}s -> {:.1} fps (last conf {:.3})",
+        "{intra} thread(s): {} inferences in {:.3}s -> {:.1} fps (last conf {:.3})",
         iterations,
         elapsed.as_secs_f64(),
         fps,
         last_conf
     );
-
     Ok(())
 }
 
-fn load_model(model_path: &PathBuf) -> Result<Model> {
-    let session = Session::builder()?
-        .with_optimization_level(GraphOptimizationLevel::Level3)?
-        .with_intra_threads(2)?
+fn load_model(model_path: &PathBuf, threads: SessionThreads) -> Result<Model> {
+    let builder = Session::builder()?.with_optimization_level(GraphOptimizationLevel::Level3)?;
+    let session = threads
+        .apply(builder)?
         .commit_from_file(model_path)
         .with_context(|| format!("failed to load model from {}", model_path.display()))?;
     Ok(session)
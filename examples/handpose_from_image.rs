@@ -1,12 +1,26 @@
 #[allow(dead_code)]
 #[path = "../src/model_download.rs"]
 mod model_download;
+#[allow(dead_code)]
+#[path = "../src/pipeline/recognizer"]
+mod recognizer {
+    pub mod common;
+    pub mod palm;
+    pub mod providers;
+}
+#[allow(dead_code)]
+#[path = "../src/pipeline/skeleton.rs"]
+mod skeleton;
+#[allow(dead_code)]
+#[path = "../src/types.rs"]
+mod types;
 
 use anyhow::{Context, Result, anyhow};
 use image::{Rgba, RgbaImage, imageops::FilterType};
 use model_download::{
     default_handpose_estimator_model_path, ensure_handpose_estimator_model_ready,
 };
+use skeleton::{SkeletonStyle, draw_landmarks_on_image};
 use std::path::PathBuf;
 
 use ort::{
@@ -26,31 +40,13 @@ struct InferenceResult {
 
 const INPUT_SIZE: u32 = 224;
 const NUM_LANDMARKS: usize = 21;
-const CONNECTIONS: &[(usize, usize)] = &[
-    (0, 1),
-    (1, 2),
-    (2, 3),
-    (3, 4),
-    (0, 5),
-    (5, 6),
-    (6, 7),
-    (7, 8),
-    (0, 9),
-    (9, 10),
-    (10, 11),
-    (11, 12),
-    (0, 13),
-    (13, 14),
-    (14, 15),
-    (15, 16),
-    (0, 17),
-    (17, 18),
-    (18, 19),
-    (19, 20),
-    (5, 9),
-    (9, 13),
-    (13, 17),
-];
+const EXAMPLE_STYLE: SkeletonStyle = SkeletonStyle {
+    line_color: [255, 142, 82, 255],
+    point_color: [56, 163, 255, 255],
+    line_thickness: Some(1),
+    point_radius: Some(3),
+    point_outline: false,
+};
 
 struct LetterboxInfo {
     scale: f32,
@@ -98,7 +94,7 @@ fn main() -> Result<()> {
     );
 
     let projected = project_landmarks(&landmarks, &letterbox);
-    draw_skeleton(&mut canvas, &projected);
+    draw_landmarks_on_image(&mut canvas, &projected, &EXAMPLE_STYLE);
     canvas
         .save(&output_image)
         .with_context(|| format!("failed to save {}", output_image.display()))?;
@@ -227,64 +223,3 @@ fn project_landmarks(landmarks: &[[f32; 3]], letterbox: &LetterboxInfo) -> Vec<(
         })
         .collect()
 }
-
-fn draw_skeleton(image: &mut RgbaImage, points: &[(f32, f32)]) {
-    let line_color = Rgba([255, 142, 82, 255]);
-    for &(a, b) in CONNECTIONS {
-        if let (Some(pa), Some(pb)) = (points.get(a), points.get(b)) {
-            draw_line(image, pa, pb, line_color);
-        }
-    }
-
-    let point_color = Rgba([56, 163, 255, 255]);
-    for &(x, y) in points {
-        draw_circle(image, (x as i32, y as i32), 3, point_color);
-    }
-}
-
-fn draw_line(image: &mut RgbaImage, p0: &(f32, f32), p1: &(f32, f32), color: Rgba<u8>) {
-    let (mut x0, mut y0) = (p0.0 as i32, p0.1 as i32);
-    let (x1, y1) = (p1.0 as i32, p1.1 as i32);
-    let dx = (x1 - x0).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let dy = -(y1 - y0).abs();
-    let sy = if y0 < y1 { 1 } else { -1 };
-    let mut err = dx + dy;
-
-    loop {
-        put_pixel_safe(image, x0, y0, color);
-        if x0 == x1 && y0 == y1 {
-            break;
-        }
-        let e2 = 2 * err;
-        if e2 >= dy {
-            err += dy;
-            x0 += sx;
-        }
-        if e2 <= dx {
-            err += dx;
-            y0 += sy;
-        }
-    }
-}
-
-fn draw_circle(image: &mut RgbaImage, center: (i32, i32), radius: i32, color: Rgba<u8>) {
-    let (cx, cy) = center;
-    for dy in -radius..=radius {
-        for dx in -radius..=radius {
-            if dx * dx + dy * dy <= radius * radius {
-                put_pixel_safe(image, cx + dx, cy + dy, color);
-            }
-        }
-    }
-}
-
-fn put_pixel_safe(image: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
-    if x < 0 || y < 0 {
-        return;
-    }
-    let (ux, uy) = (x as u32, y as u32);
-    if ux < image.width() && uy < image.height() {
-        image.put_pixel(ux, uy, color);
-    }
-}
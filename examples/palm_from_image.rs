@@ -1,12 +1,13 @@
 #[allow(dead_code)]
-#[path = "../src/pipeline/recognizer/common.rs"]
-mod common;
-#[allow(dead_code)]
 #[path = "../src/model_download.rs"]
 mod model_download;
 #[allow(dead_code)]
-#[path = "../src/pipeline/recognizer/palm/mod.rs"]
-mod palm;
+#[path = "../src/pipeline/recognizer"]
+mod recognizer {
+    pub mod common;
+    pub mod palm;
+    pub mod providers;
+}
 #[allow(dead_code)]
 #[path = "../src/pipeline/skeleton.rs"]
 mod skeleton;
@@ -15,12 +16,11 @@ mod skeleton;
 mod types;
 
 use anyhow::{Context, Result, anyhow};
-use image::RgbaImage;
 use model_download::{default_palm_detector_model_path, ensure_palm_detector_model_ready};
 use std::path::PathBuf;
 use types::{Frame, PalmRegion};
 
-use crate::palm::{PalmDetector, PalmDetectorConfig};
+use recognizer::palm::{CropConfig, PalmDetector, PalmDetectorConfig};
 
 fn main() -> Result<()> {
     env_logger::init();
@@ -42,8 +42,25 @@ fn main() -> Result<()> {
 
     let mut palm_detector =
         PalmDetector::new(&palm_detector_model_path, PalmDetectorConfig::default())?;
+    let mut gray_detector = PalmDetector::new(
+        &palm_detector_model_path,
+        PalmDetectorConfig {
+            grayscale: true,
+            ..PalmDetectorConfig::default()
+        },
+    )?;
 
     let palms = palm_detector.detect(&frame)?;
+    // Grayscale input is an option for speed; show whether it finds the same
+    // palms on this image.
+    let gray_palms = gray_detector.detect(&frame)?;
+    println!(
+        "Grayscale input: {} palms (color: {}), best score {:.2} (color: {:.2})",
+        gray_palms.len(),
+        palms.len(),
+        gray_palms.first().map_or(0.0, |palm| palm.score),
+        palms.first().map_or(0.0, |palm| palm.score)
+    );
     if palms.is_empty() {
         println!("No palms detected in {}", input_image.display());
         return Ok(());
@@ -54,10 +71,18 @@ fn main() -> Result<()> {
         palms.len(),
         input_image.display()
     );
+    for (idx, palm) in palms.iter().enumerate() {
+        let [x1, y1, x2, y2] = palm.bbox;
+        println!(
+            "  palm {idx}: score={:.2} box=({x1:.0}, {y1:.0})-({x2:.0}, {y2:.0}) keypoints={:?}",
+            palm.score, palm.landmarks
+        );
+    }
 
     overlay(&mut frame, &palms);
 
-    let output = RgbaImage::from_raw(frame.width, frame.height, frame.rgba)
+    let output = frame
+        .to_rgba_image()
         .ok_or_else(|| anyhow!("failed to build image buffer"))?;
     output
         .save(&output_image)
@@ -72,15 +97,16 @@ fn load_frame(path: &PathBuf) -> Result<Frame> {
         .with_context(|| format!("failed to open image {}", path.display()))?
         .to_rgba8();
     let (width, height) = image.dimensions();
-    let rgba = image.into_raw();
-    Ok(Frame {
-        rgba,
-        width,
-        height,
-        timestamp: std::time::Instant::now(),
-    })
+    Frame::from_rgba(width, height, image.into_raw())
+        .context("image size does not match its pixels")
 }
 
 fn overlay(frame: &mut Frame, palms: &[PalmRegion]) {
-    skeleton::draw_palm_regions(&mut frame.rgba, frame.width, frame.height, palms);
+    skeleton::draw_palm_regions(
+        &mut frame.rgba,
+        frame.width,
+        frame.height,
+        palms,
+        &CropConfig::default(),
+    );
 }
@@ -0,0 +1,296 @@
+//! Golden-file regression harness for the recognizer.
+//!
+//! `record <session> <image>...` downsamples each image, stores it as JPEG
+//! together with what the recognizer reports for it, and `replay <session>`
+//! re-runs the recognizer on the stored frames and diffs the results. Dump a
+//! video with e.g. `ffmpeg -i clip.mp4 -vf fps=15 frames/%04d.png` to record
+//! a moving sequence.
+
+#[allow(dead_code)]
+#[path = "../src/gesture.rs"]
+mod gesture;
+#[allow(dead_code)]
+#[path = "../src/model_download.rs"]
+mod model_download;
+#[allow(dead_code)]
+#[path = "../src/pipeline"]
+mod pipeline {
+    pub mod channel;
+    pub mod gamma;
+    pub mod recognizer;
+}
+#[allow(dead_code)]
+#[path = "../src/types.rs"]
+mod types;
+
+use std::{
+    fs,
+    io::{Cursor, Read},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use image::{ImageFormat, codecs::jpeg::JpegEncoder, imageops::FilterType};
+use pipeline::recognizer::{Recognizer, RecognizerBackend};
+use types::{Frame, GestureResult};
+
+const MAGIC: &[u8; 4] = b"GUSN";
+const VERSION: u32 = 1;
+/// Recorded frames are capped at this long side to keep sessions small.
+const MAX_SIDE: u32 = 320;
+const JPEG_QUALITY: u8 = 80;
+/// Spacing of synthetic timestamps, matching a 30fps camera.
+const FRAME_INTERVAL_MS: u64 = 33;
+/// Mean landmark drift in pixels tolerated before a frame counts as changed.
+const LANDMARK_TOLERANCE_PX: f32 = 2.0;
+
+struct RecordedFrame {
+    offset_ms: u64,
+    jpeg: Vec<u8>,
+    expected: FrameOutcome,
+}
+
+struct FrameOutcome {
+    gesture: String,
+    confidence: f32,
+    landmarks: Vec<(f32, f32)>,
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let mut args = std::env::args().skip(1);
+    let usage = "usage: session_replay record <session> <image>... | replay <session>";
+    let mode = args.next().ok_or_else(|| anyhow!(usage))?;
+    let session_path = args
+        .next()
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!(usage))?;
+
+    match mode.as_str() {
+        "record" => {
+            let images: Vec<PathBuf> = args.map(PathBuf::from).collect();
+            if images.is_empty() {
+                bail!(usage);
+            }
+            record(&session_path, &images)
+        }
+        "replay" => replay(&session_path),
+        _ => bail!(usage),
+    }
+}
+
+fn record(session_path: &PathBuf, images: &[PathBuf]) -> Result<()> {
+    let mut frames = Vec::with_capacity(images.len());
+    for (idx, path) in images.iter().enumerate() {
+        let image =
+            image::open(path).with_context(|| format!("无法打开图片 {}", path.display()))?;
+        let image = if image.width().max(image.height()) > MAX_SIDE {
+            image.resize(MAX_SIDE, MAX_SIDE, FilterType::Triangle)
+        } else {
+            image
+        };
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+            .encode_image(&image.to_rgb8())
+            .with_context(|| format!("failed to encode {}", path.display()))?;
+        frames.push(RecordedFrame {
+            offset_ms: idx as u64 * FRAME_INTERVAL_MS,
+            jpeg,
+            expected: FrameOutcome {
+                gesture: String::new(),
+                confidence: 0.0,
+                landmarks: Vec::new(),
+            },
+        });
+    }
+
+    // Run on the decoded JPEGs rather than the originals so a replay sees
+    // exactly the pixels that produced the stored results.
+    let outcomes = run_session(&frames)?;
+    for (frame, outcome) in frames.iter_mut().zip(outcomes) {
+        frame.expected = outcome;
+    }
+
+    let bytes = encode_session(&frames);
+    fs::write(session_path, &bytes)
+        .with_context(|| format!("failed to write {}", session_path.display()))?;
+    println!(
+        "Recorded {} frames ({} KiB) to {}",
+        frames.len(),
+        bytes.len() / 1024,
+        session_path.display()
+    );
+    Ok(())
+}
+
+fn replay(session_path: &PathBuf) -> Result<()> {
+    let bytes = fs::read(session_path)
+        .with_context(|| format!("failed to read {}", session_path.display()))?;
+    let frames = decode_session(&bytes)?;
+    let outcomes = run_session(&frames)?;
+
+    let mut mismatches = 0;
+    for (idx, (frame, actual)) in frames.iter().zip(&outcomes).enumerate() {
+        let expected = &frame.expected;
+        let drift = landmark_drift(&expected.landmarks, &actual.landmarks);
+        if expected.gesture != actual.gesture || drift > LANDMARK_TOLERANCE_PX {
+            mismatches += 1;
+            println!(
+                "frame {idx}: expected {} ({:.2}), got {} ({:.2}), landmark drift {drift:.1}px",
+                expected.gesture, expected.confidence, actual.gesture, actual.confidence
+            );
+        }
+    }
+
+    println!(
+        "Replayed {} frames from {}: {} mismatches",
+        frames.len(),
+        session_path.display(),
+        mismatches
+    );
+    if mismatches > 0 {
+        bail!("{mismatches} frames differ from the recording");
+    }
+    Ok(())
+}
+
+fn run_session(frames: &[RecordedFrame]) -> Result<Vec<FrameOutcome>> {
+    let mut recognizer = Recognizer::new(&RecognizerBackend::default())?;
+
+    let base = Instant::now();
+    let mut outcomes = Vec::with_capacity(frames.len());
+    for (idx, recorded) in frames.iter().enumerate() {
+        let mut frame = decode_frame(&recorded.jpeg)?;
+        frame.timestamp = base + Duration::from_millis(recorded.offset_ms);
+        let result = recognizer
+            .process(&frame)
+            .with_context(|| format!("frame {idx} produced no result"))?;
+        outcomes.push(outcome_from(&result));
+    }
+    Ok(outcomes)
+}
+
+fn outcome_from(result: &GestureResult) -> FrameOutcome {
+    FrameOutcome {
+        gesture: result
+            .detail
+            .as_ref()
+            .map(|detail| format!("{:?}", detail.primary))
+            .unwrap_or_else(|| "None".to_string()),
+        confidence: result.confidence,
+        landmarks: result.landmarks.clone().unwrap_or_default(),
+    }
+}
+
+fn landmark_drift(expected: &[(f32, f32)], actual: &[(f32, f32)]) -> f32 {
+    if expected.len() != actual.len() {
+        return f32::INFINITY;
+    }
+    if expected.is_empty() {
+        return 0.0;
+    }
+    let total: f32 = expected
+        .iter()
+        .zip(actual)
+        .map(|(a, b)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt())
+        .sum();
+    total / expected.len() as f32
+}
+
+fn decode_frame(jpeg: &[u8]) -> Result<Frame> {
+    let rgba = image::load_from_memory_with_format(jpeg, ImageFormat::Jpeg)
+        .context("failed to decode recorded frame")?
+        .to_rgba8();
+    let (width, height) = rgba.dimensions();
+    Frame::from_rgba(width, height, rgba.into_raw())
+        .context("recorded frame size does not match its pixels")
+}
+
+// Session layout, all integers little-endian:
+//   magic "GUSN", u32 version, u32 frame count, then per frame
+//   u64 offset_ms, u32 jpeg len, jpeg bytes,
+//   u32 gesture len, gesture utf8, f32 confidence,
+//   u32 landmark count, (f32 x, f32 y) per landmark.
+
+fn encode_session(frames: &[RecordedFrame]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+    for frame in frames {
+        out.extend_from_slice(&frame.offset_ms.to_le_bytes());
+        out.extend_from_slice(&(frame.jpeg.len() as u32).to_le_bytes());
+        out.extend_from_slice(&frame.jpeg);
+        let expected = &frame.expected;
+        out.extend_from_slice(&(expected.gesture.len() as u32).to_le_bytes());
+        out.extend_from_slice(expected.gesture.as_bytes());
+        out.extend_from_slice(&expected.confidence.to_le_bytes());
+        out.extend_from_slice(&(expected.landmarks.len() as u32).to_le_bytes());
+        for (x, y) in &expected.landmarks {
+            out.extend_from_slice(&x.to_le_bytes());
+            out.extend_from_slice(&y.to_le_bytes());
+        }
+    }
+    out
+}
+
+fn decode_session(bytes: &[u8]) -> Result<Vec<RecordedFrame>> {
+    let mut reader = Cursor::new(bytes);
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).context("truncated session")?;
+    if &magic != MAGIC {
+        bail!("not a gesture session file");
+    }
+    let version = read_u32(&mut reader)?;
+    if version != VERSION {
+        bail!("unsupported session version {version}");
+    }
+
+    let count = read_u32(&mut reader)? as usize;
+    let mut frames = Vec::with_capacity(count);
+    for _ in 0..count {
+        let offset_ms = read_u64(&mut reader)?;
+        let jpeg = read_bytes(&mut reader)?;
+        let gesture =
+            String::from_utf8(read_bytes(&mut reader)?).context("invalid gesture name")?;
+        let confidence = f32::from_bits(read_u32(&mut reader)?);
+        let landmark_count = read_u32(&mut reader)? as usize;
+        let mut landmarks = Vec::with_capacity(landmark_count);
+        for _ in 0..landmark_count {
+            let x = f32::from_bits(read_u32(&mut reader)?);
+            let y = f32::from_bits(read_u32(&mut reader)?);
+            landmarks.push((x, y));
+        }
+        frames.push(RecordedFrame {
+            offset_ms,
+            jpeg,
+            expected: FrameOutcome {
+                gesture,
+                confidence,
+                landmarks,
+            },
+        });
+    }
+    Ok(frames)
+}
+
+fn read_u32(reader: &mut Cursor<&[u8]>) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).context("truncated session")?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut Cursor<&[u8]>) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf).context("truncated session")?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_bytes(reader: &mut Cursor<&[u8]>) -> Result<Vec<u8>> {
+    let len = read_u32(reader)? as usize;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).context("truncated session")?;
+    Ok(buf)
+}
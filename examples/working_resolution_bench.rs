@@ -0,0 +1,82 @@
+#[allow(dead_code)]
+#[path = "../src/pipeline/recognizer/common.rs"]
+mod recognizer_common;
+#[allow(dead_code)]
+#[path = "../src/types.rs"]
+mod types;
+
+use anyhow::Result;
+use recognizer_common::{
+    INPUT_SIZE, PALM_INPUT_SIZE, PreprocessMode, downscale_frame, prepare_frame_with_size,
+    prepare_rotated_crop,
+};
+use std::time::{Duration, Instant};
+use types::Frame;
+
+/// Working resolutions compared by the benchmark; `None` runs on the full
+/// frame.
+const WORKING_RESOLUTIONS: &[Option<u32>] = &[None, Some(1920), Some(1280), Some(960), Some(640)];
+
+/// Times the per-frame preprocessing `OrtEngine::infer` does outside the
+/// models — the optional working-resolution downscale, the palm detector
+/// letterbox and the handpose crop — on a synthetic frame, by default 1080p.
+///
+/// `cargo run --release --example working_resolution_bench [width height]`
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let width = args.next().and_then(|s| s.parse().ok()).unwrap_or(1920);
+    let height = args.next().and_then(|s| s.parse().ok()).unwrap_or(1080);
+    let frame = synthetic_frame(width, height);
+    println!("Preprocessing a {width}x{height} frame");
+
+    for &max_side in WORKING_RESOLUTIONS {
+        let per_frame = bench(&frame, max_side, Duration::from_secs(2))?;
+        let label = max_side.map_or("full frame".to_string(), |side| format!("{side}px"));
+        println!(
+            "{label:>10}: {:.2} ms per frame",
+            per_frame.as_secs_f64() * 1000.0
+        );
+    }
+    Ok(())
+}
+
+fn bench(frame: &Frame, max_side: Option<u32>, duration: Duration) -> Result<Duration> {
+    // Warm up once so the first allocation isn't counted.
+    preprocess(frame, max_side)?;
+
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while start.elapsed() < duration {
+        preprocess(frame, max_side)?;
+        iterations += 1;
+    }
+    Ok(start.elapsed() / iterations.max(1))
+}
+
+/// The palm letterbox and a crop around a hand a third of the frame tall,
+/// in working-resolution space like the engine does.
+fn preprocess(frame: &Frame, max_side: Option<u32>) -> Result<()> {
+    let working = match max_side {
+        Some(max_side) => downscale_frame(frame, max_side)?.map(|(working, _)| working),
+        None => None,
+    };
+    let frame = working.as_ref().unwrap_or(frame);
+
+    let (palm_input, _) =
+        prepare_frame_with_size(frame, PALM_INPUT_SIZE, PreprocessMode::Letterbox, false)?;
+    let center = (frame.width as f32 / 2.0, frame.height as f32 / 2.0);
+    let side = frame.height as f32 / 3.0;
+    let (crop, _) = prepare_rotated_crop(frame, center, side, 0.3, INPUT_SIZE, INPUT_SIZE)?;
+    std::hint::black_box((palm_input, crop));
+    Ok(())
+}
+
+fn synthetic_frame(width: u32, height: u32) -> Frame {
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            rgba.extend_from_slice(&[(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8, 255]);
+        }
+    }
+    Frame::from_rgba(width, height, rgba).expect("buffer matches the frame size")
+}
@@ -0,0 +1,43 @@
+use std::{fs, path::PathBuf, thread};
+
+use crossbeam_channel::{Sender, unbounded};
+
+use crate::types::GestureKind;
+
+/// Mirrors triggered gestures into a file as a single emoji, for chat bots
+/// and stream overlays that poll or tail it.
+///
+/// Every write truncates and rewrites the file, so readers always see just
+/// the latest gesture. Writes happen on a background thread because a named
+/// pipe blocks until a reader opens it, and the UI thread must not wait on
+/// that.
+pub struct EmojiFileOutput {
+    tx: Sender<GestureKind>,
+}
+
+impl EmojiFileOutput {
+    pub fn spawn(path: PathBuf) -> Self {
+        let (tx, rx) = unbounded::<GestureKind>();
+        thread::spawn(move || {
+            let mut last_written = None;
+            while let Ok(kind) = rx.recv() {
+                // Only the newest gesture matters after a blocked pipe write.
+                let kind = rx.try_iter().last().unwrap_or(kind);
+                if last_written == Some(kind) {
+                    continue;
+                }
+                match fs::write(&path, kind.emoji().trim()) {
+                    Ok(()) => last_written = Some(kind),
+                    Err(err) => log::warn!("failed to write emoji to {}: {err}", path.display()),
+                }
+            }
+        });
+        Self { tx }
+    }
+
+    /// Queues `kind` for writing; repeats of the last written gesture are
+    /// skipped so the file only changes when the gesture does.
+    pub fn send(&self, kind: GestureKind) {
+        let _ = self.tx.send(kind);
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 
 use crate::{
     model_download::{
-        default_gesture_classifier_model_path, ensure_gesture_classifier_model_ready,
+        ModelKind, SessionThreads, default_gesture_classifier_model_path, prepare_model_source,
     },
     types::{FingerState, GestureDetail, GestureKind, GestureMotion, Handedness},
 };
@@ -15,15 +15,371 @@ use ort::session::Session;
 const MIN_CONFIDENCE: f32 = 0.2;
 const MOTION_WINDOW: Duration = Duration::from_millis(1_200);
 
+struct HandFeatures {
+    finger_states: [FingerState; 5],
+    finger_spreads: [f32; 4],
+    roll_degrees: f32,
+    hand_scale_px: f32,
+    handedness: Handedness,
+}
+
+impl HandFeatures {
+    fn into_detail(
+        self,
+        primary: GestureKind,
+        motion: GestureMotion,
+        probabilities: Vec<(GestureKind, f32)>,
+    ) -> GestureDetail {
+        GestureDetail {
+            primary,
+            secondary: None, // No longer using secondary detection
+            handedness: self.handedness,
+            finger_states: self.finger_states,
+            motion,
+            finger_spreads: self.finger_spreads,
+            roll_degrees: self.roll_degrees,
+            hand_scale_px: self.hand_scale_px,
+            probabilities,
+        }
+    }
+}
+
 pub struct GestureClassifier {
     motion_tracker: MotionTracker,
     model_session: Option<Session>,
     class_to_gesture: HashMap<usize, GestureKind>,
+    confidence_floors: ConfidenceFloors,
+    voter: GestureVoter,
+    finger_config: FingerClassifierConfig,
+    /// When false, motion history is never kept and every result reports
+    /// [`GestureMotion::Steady`].
+    enable_motion: bool,
+}
+
+/// Minimum softmax probability a class needs before it is reported, so
+/// gestures that would be embarrassing as false positives can demand more
+/// certainty than easy ones.
+#[derive(Clone, Debug)]
+pub struct ConfidenceFloors {
+    pub default: f32,
+    pub overrides: HashMap<GestureKind, f32>,
+}
+
+impl ConfidenceFloors {
+    pub fn floor_for(&self, kind: GestureKind) -> f32 {
+        self.overrides.get(&kind).copied().unwrap_or(self.default)
+    }
+
+    /// The reported gesture from probabilities sorted most likely first. A
+    /// winner below its floor yields to the runner-up if that one clears
+    /// its own floor, otherwise the gesture is reported as unknown.
+    pub fn pick(&self, probabilities: &[(GestureKind, f32)]) -> GestureKind {
+        probabilities
+            .iter()
+            .take(2)
+            .find(|(kind, prob)| *prob >= self.floor_for(*kind))
+            .map(|(kind, _)| *kind)
+            .unwrap_or(GestureKind::Unknown)
+    }
+
+    /// Parses the command-line spelling, comma-separated `<gesture>=<floor>`
+    /// pairs on top of the defaults, e.g. `MiddleFinger=0.8,default=0.2`.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        let mut floors = Self::default();
+        for pair in value.split(',') {
+            let (name, floor) = pair.split_once('=')?;
+            let floor = floor
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|floor| (0.0..=1.0).contains(floor))?;
+            if name.trim() == "default" {
+                floors.default = floor;
+            } else {
+                floors.overrides.insert(name.parse().ok()?, floor);
+            }
+        }
+        Some(floors)
+    }
+}
+
+impl Default for ConfidenceFloors {
+    fn default() -> Self {
+        Self {
+            default: 0.0,
+            overrides: [(GestureKind::MiddleFinger, 0.7), (GestureKind::XSign, 0.6)]
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Thresholds for the per-finger state heuristics. Distances are in units of
+/// the normalized hand span; straightness is the mean cosine between
+/// consecutive bone directions (1.0 = perfectly straight).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FingerClassifierConfig {
+    /// Minimum tip-beyond-PIP distance from the wrist for an extended finger.
+    pub extended_extension: f32,
+    /// Minimum straightness for an extended finger.
+    pub extended_straightness: f32,
+    /// Minimum tip-beyond-MCP distance from the wrist for an extended finger.
+    pub extended_reach: f32,
+    /// A finger below this extension is folded.
+    pub folded_extension: f32,
+    /// A finger below this straightness is folded.
+    pub folded_straightness: f32,
+    /// A finger below this reach is folded.
+    pub folded_reach: f32,
+    /// Thumb tip closer than this to the index or pinky MCP counts as tucked.
+    pub thumb_folded_spread: f32,
+    /// A tucked thumb below this straightness is folded.
+    pub thumb_folded_straightness: f32,
+    /// A tucked thumb below this reach is folded.
+    pub thumb_folded_reach: f32,
+    /// Minimum thumb tip distance from the wrist to count as extended.
+    pub thumb_extended_distance: f32,
+    /// Minimum straightness for an extended thumb.
+    pub thumb_extended_straightness: f32,
+    /// Minimum tip-beyond-IP distance from the wrist for an extended thumb.
+    pub thumb_extended_extension: f32,
+}
+
+impl Default for FingerClassifierConfig {
+    fn default() -> Self {
+        // Relaxed to reduce half-bent false positives, especially for the pinky.
+        Self {
+            extended_extension: 0.15,
+            extended_straightness: 0.40,
+            extended_reach: 0.06,
+            folded_extension: 0.08,
+            folded_straightness: 0.18,
+            folded_reach: 0.05,
+            thumb_folded_spread: 0.25,
+            thumb_folded_straightness: 0.28,
+            thumb_folded_reach: 0.15,
+            thumb_extended_distance: 0.30,
+            thumb_extended_straightness: 0.28,
+            thumb_extended_extension: 0.08,
+        }
+    }
+}
+
+/// Share of the gap between a user's fist and open-palm metrics below which
+/// a finger counts as folded, and above which it counts as extended.
+const CALIBRATION_FOLDED_SPLIT: f32 = 0.35;
+const CALIBRATION_EXTENDED_SPLIT: f32 = 0.65;
+
+impl FingerClassifierConfig {
+    /// Parses the command-line spelling, comma-separated `<field>=<value>`
+    /// pairs on top of the defaults, e.g.
+    /// `extended_extension=0.12,thumb_extended_distance=0.35`.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        let mut config = Self::default();
+        for pair in value.split(',') {
+            let (name, value) = pair.split_once('=')?;
+            let value = value.trim().parse::<f32>().ok().filter(|v| v.is_finite())?;
+            let field = match name.trim() {
+                "extended_extension" => &mut config.extended_extension,
+                "extended_straightness" => &mut config.extended_straightness,
+                "extended_reach" => &mut config.extended_reach,
+                "folded_extension" => &mut config.folded_extension,
+                "folded_straightness" => &mut config.folded_straightness,
+                "folded_reach" => &mut config.folded_reach,
+                "thumb_folded_spread" => &mut config.thumb_folded_spread,
+                "thumb_folded_straightness" => &mut config.thumb_folded_straightness,
+                "thumb_folded_reach" => &mut config.thumb_folded_reach,
+                "thumb_extended_distance" => &mut config.thumb_extended_distance,
+                "thumb_extended_straightness" => &mut config.thumb_extended_straightness,
+                "thumb_extended_extension" => &mut config.thumb_extended_extension,
+                _ => return None,
+            };
+            *field = value;
+        }
+        Some(config)
+    }
+
+    /// Thresholds for the index to pinky fingers fitted to one user's hand
+    /// from samples of an open palm and a fist, keeping `self`'s thumb
+    /// thresholds. Each metric is split between the weakest finger of the
+    /// open palm and the loosest finger of the fist; a metric whose ranges
+    /// overlap keeps its current thresholds. `None` without samples of both
+    /// poses.
+    pub fn calibrated(
+        &self,
+        open: &[[FingerMetrics; 4]],
+        fist: &[[FingerMetrics; 4]],
+    ) -> Option<Self> {
+        if open.is_empty() || fist.is_empty() {
+            return None;
+        }
+        let open = mean_metrics(open);
+        let fist = mean_metrics(fist);
+        let split = |metric: fn(&FingerMetrics) -> f32, current: (f32, f32)| {
+            let open_low = open.iter().map(metric).fold(f32::MAX, f32::min);
+            let fist_high = fist.iter().map(metric).fold(f32::MIN, f32::max);
+            let gap = open_low - fist_high;
+            if gap <= 0.0 {
+                return current;
+            }
+            (
+                fist_high + gap * CALIBRATION_FOLDED_SPLIT,
+                fist_high + gap * CALIBRATION_EXTENDED_SPLIT,
+            )
+        };
+
+        let (folded_extension, extended_extension) = split(
+            |m| m.extension,
+            (self.folded_extension, self.extended_extension),
+        );
+        let (folded_straightness, extended_straightness) = split(
+            |m| m.straightness,
+            (self.folded_straightness, self.extended_straightness),
+        );
+        let (folded_reach, extended_reach) =
+            split(|m| m.reach, (self.folded_reach, self.extended_reach));
+        Some(Self {
+            extended_extension,
+            extended_straightness,
+            extended_reach,
+            folded_extension,
+            folded_straightness,
+            folded_reach,
+            ..*self
+        })
+    }
+}
+
+/// The per-finger quantities the finger-state heuristic thresholds, in the
+/// units of [`FingerClassifierConfig`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FingerMetrics {
+    /// Tip-beyond-PIP distance from the wrist.
+    pub extension: f32,
+    pub straightness: f32,
+    /// Tip-beyond-MCP distance from the wrist.
+    pub reach: f32,
+}
+
+/// Finger-state metrics of the index, middle, ring and pinky fingers, for
+/// calibration. `None` unless all 21 landmarks are present.
+pub fn finger_metrics(raw_landmarks: &[[f32; 3]]) -> Option<[FingerMetrics; 4]> {
+    if raw_landmarks.len() < 21 {
+        return None;
+    }
+    let (normalized, _hand_span) = normalize_landmarks(raw_landmarks);
+    Some(FINGER_JOINTS.map(|idx| measure_finger(&normalized, idx)))
+}
+
+fn mean_metrics(samples: &[[FingerMetrics; 4]]) -> [FingerMetrics; 4] {
+    let count = samples.len().max(1) as f32;
+    let mut mean = [FingerMetrics::default(); 4];
+    for sample in samples {
+        for (total, metrics) in mean.iter_mut().zip(sample) {
+            total.extension += metrics.extension / count;
+            total.straightness += metrics.straightness / count;
+            total.reach += metrics.reach / count;
+        }
+    }
+    mean
+}
+
+/// Time span and decay of the temporal vote over classifier outputs.
+#[derive(Clone, Copy, Debug)]
+pub struct VoterConfig {
+    /// Frames older than this no longer contribute to the vote.
+    pub window: Duration,
+    /// Age at which a frame's weight has halved.
+    pub half_life: Duration,
+}
+
+impl Default for VoterConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_millis(500),
+            half_life: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Accumulates softmax vectors over a short window, weighting each frame by
+/// its handpose confidence and an exponential age decay, so a single noisy
+/// frame cannot flip the reported class.
+pub struct GestureVoter {
+    config: VoterConfig,
+    samples: VecDeque<VoteSample>,
+}
+
+struct VoteSample {
+    time: Instant,
+    weight: f32,
+    probabilities: Vec<(GestureKind, f32)>,
+}
+
+impl GestureVoter {
+    pub fn new(config: VoterConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Add one frame and return the accumulated distribution, sorted by
+    /// descending probability and normalised to sum to one.
+    pub fn update(
+        &mut self,
+        probabilities: &[(GestureKind, f32)],
+        confidence: f32,
+        now: Instant,
+    ) -> Vec<(GestureKind, f32)> {
+        self.samples.push_back(VoteSample {
+            time: now,
+            weight: confidence.max(0.0),
+            probabilities: probabilities.to_vec(),
+        });
+        while let Some(front) = self.samples.front() {
+            if now.duration_since(front.time) > self.config.window {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let half_life = self.config.half_life.as_secs_f32().max(1e-3);
+        let mut totals: HashMap<GestureKind, f32> = HashMap::new();
+        let mut total_weight = 0.0;
+        for sample in &self.samples {
+            let age = now.duration_since(sample.time).as_secs_f32();
+            let weight = sample.weight * 0.5f32.powf(age / half_life);
+            total_weight += weight;
+            for (kind, prob) in &sample.probabilities {
+                *totals.entry(*kind).or_insert(0.0) += weight * prob;
+            }
+        }
+        if total_weight <= f32::EPSILON {
+            return probabilities.to_vec();
+        }
+
+        let mut voted: Vec<(GestureKind, f32)> = totals
+            .into_iter()
+            .map(|(kind, sum)| (kind, sum / total_weight))
+            .collect();
+        voted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        voted
+    }
+
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
 }
 
 impl GestureClassifier {
-    pub fn new() -> Self {
-        let (model_session, class_to_gesture) = Self::load_model_and_classes();
+    /// Loads the classifier model, from the copy compiled in by the
+    /// `embed-models` feature when `prefer_embedded` is set.
+    pub fn new(threads: SessionThreads, prefer_embedded: bool) -> Self {
+        let (model_session, class_to_gesture) =
+            Self::load_model_and_classes(threads, prefer_embedded);
 
         if model_session.is_none() {
             log::warn!(
@@ -35,32 +391,101 @@ impl GestureClassifier {
             motion_tracker: MotionTracker::new(),
             model_session,
             class_to_gesture,
+            confidence_floors: ConfidenceFloors::default(),
+            voter: GestureVoter::new(VoterConfig::default()),
+            finger_config: FingerClassifierConfig::default(),
+            enable_motion: true,
         }
     }
 
-    fn load_model_and_classes() -> (Option<Session>, HashMap<usize, GestureKind>) {
-        let model_path = default_gesture_classifier_model_path();
+    /// A classifier that never loads the model, for tests that only need
+    /// a recognizer around some other stage.
+    #[cfg(test)]
+    pub(crate) fn without_model() -> Self {
+        Self {
+            motion_tracker: MotionTracker::new(),
+            model_session: None,
+            class_to_gesture: HashMap::new(),
+            confidence_floors: ConfidenceFloors::default(),
+            voter: GestureVoter::new(VoterConfig::default()),
+            finger_config: FingerClassifierConfig::default(),
+            enable_motion: true,
+        }
+    }
 
-        // Ensure model is downloaded
-        if let Err(e) = ensure_gesture_classifier_model_ready(&model_path, |_evt| {}) {
-            log::error!("Failed to prepare gesture classifier model: {}", e);
-            return (None, HashMap::new());
+    /// Gestures the loaded model can report, in class index order. Empty
+    /// when the model failed to load, since every result is then
+    /// [`GestureKind::Unknown`].
+    #[allow(dead_code)]
+    pub fn supported_gestures(&self) -> Vec<GestureKind> {
+        if self.model_session.is_none() {
+            return Vec::new();
         }
+        let mut classes: Vec<_> = self.class_to_gesture.iter().collect();
+        classes.sort_by_key(|(idx, _)| **idx);
+        classes.into_iter().map(|(_, kind)| *kind).collect()
+    }
+
+    pub fn with_confidence_floors(mut self, floors: ConfidenceFloors) -> Self {
+        self.confidence_floors = floors;
+        self
+    }
+
+    pub fn with_voter(mut self, config: VoterConfig) -> Self {
+        self.voter = GestureVoter::new(config);
+        self
+    }
+
+    pub fn with_finger_config(mut self, config: FingerClassifierConfig) -> Self {
+        self.finger_config = config;
+        self
+    }
+
+    /// Swaps the finger thresholds on a running classifier, e.g. after a
+    /// calibration.
+    pub fn set_finger_config(&mut self, config: FingerClassifierConfig) {
+        self.finger_config = config;
+    }
+
+    pub fn with_motion(mut self, enabled: bool) -> Self {
+        self.enable_motion = enabled;
+        self
+    }
+
+    fn load_model_and_classes(
+        threads: SessionThreads,
+        prefer_embedded: bool,
+    ) -> (Option<Session>, HashMap<usize, GestureKind>) {
+        let model_path = default_gesture_classifier_model_path();
+
+        // Ensure model is downloaded (or use the embedded copy when built in)
+        let source = match prepare_model_source(
+            ModelKind::GestureClassifier,
+            &model_path,
+            prefer_embedded,
+            |_evt| {},
+        ) {
+            Ok(source) => source,
+            Err(e) => {
+                log::error!("Failed to prepare gesture classifier model: {}", e);
+                return (None, HashMap::new());
+            }
+        };
 
         // Load ONNX model
-        let session = match Session::builder() {
-            Ok(builder) => match builder.commit_from_file(&model_path) {
+        let session = match Session::builder().and_then(|builder| threads.apply(builder)) {
+            Ok(builder) => match source.commit(builder) {
                 Ok(session) => {
                     log::info!(
                         "Loaded gesture classification model from {}",
-                        model_path.display()
+                        source.describe()
                     );
                     Some(session)
                 }
                 Err(e) => {
                     log::error!(
                         "Failed to load gesture model from {}: {}",
-                        model_path.display(),
+                        source.describe(),
                         e
                     );
                     None
@@ -135,34 +560,83 @@ impl GestureClassifier {
             return None;
         }
 
-        // Keep the existing normalization for finger state detection
-        let (normalized, _hand_span) = normalize_landmarks(raw_landmarks);
-        let wrist_px = projected_landmarks.get(0).copied().unwrap_or((0.0, 0.0));
-        let span_px = projected_span(projected_landmarks);
-        let finger_states = [
-            classify_thumb(&normalized),
-            classify_finger(&normalized, [5, 6, 7, 8]),
-            classify_finger(&normalized, [9, 10, 11, 12]),
-            classify_finger(&normalized, [13, 14, 15, 16]),
-            classify_finger(&normalized, [17, 18, 19, 20]),
-        ];
-
-        let handedness = handedness_from_score(handedness_score);
+        let features = self.hand_features(raw_landmarks, projected_landmarks, handedness_score);
 
         // Use ONNX model for primary gesture detection
-        let primary = self.detect_gesture_with_model(raw_landmarks);
+        let probabilities = self.detect_gesture_with_model(raw_landmarks);
+        let voted = self.voter.update(&probabilities, confidence, timestamp);
+        let primary = self.confidence_floors.pick(&voted);
+
+        let motion = if self.enable_motion {
+            let wrist_px = projected_landmarks.get(0).copied().unwrap_or((0.0, 0.0));
+            let span_px = projected_span(projected_landmarks);
+            self.motion_tracker
+                .update(wrist_px, span_px, timestamp, primary)
+        } else {
+            GestureMotion::Steady
+        };
 
-        let motion = self
-            .motion_tracker
-            .update(wrist_px, span_px, timestamp, primary);
+        Some(features.into_detail(primary, motion, voted))
+    }
 
-        Some(GestureDetail {
-            primary,
-            secondary: None, // No longer using secondary detection
-            handedness,
-            finger_states,
-            motion,
-        })
+    /// Classifies a hand without touching the voter or motion history, for
+    /// the extra hands of multi-hand mode whose identity isn't tracked
+    /// between frames. Motion is always reported as steady.
+    #[allow(dead_code)]
+    pub fn classify_snapshot(
+        &mut self,
+        raw_landmarks: &[[f32; 3]],
+        projected_landmarks: &[(f32, f32)],
+        confidence: f32,
+        handedness_score: f32,
+    ) -> Option<GestureDetail> {
+        if confidence < MIN_CONFIDENCE {
+            return None;
+        }
+        if raw_landmarks.len() < 21 || projected_landmarks.len() < 21 {
+            return None;
+        }
+
+        let features = self.hand_features(raw_landmarks, projected_landmarks, handedness_score);
+        let probabilities = self.detect_gesture_with_model(raw_landmarks);
+        let primary = self.confidence_floors.pick(&probabilities);
+
+        Some(features.into_detail(primary, GestureMotion::Steady, probabilities))
+    }
+
+    /// Geometric per-frame features that don't depend on the model.
+    fn hand_features(
+        &self,
+        raw_landmarks: &[[f32; 3]],
+        projected_landmarks: &[(f32, f32)],
+        handedness_score: f32,
+    ) -> HandFeatures {
+        // Keep the existing normalization for finger state detection
+        let (normalized, _hand_span) = normalize_landmarks(raw_landmarks);
+        let cfg = &self.finger_config;
+        HandFeatures {
+            finger_states: [
+                classify_thumb(&normalized, cfg),
+                classify_finger(&normalized, FINGER_JOINTS[0], cfg),
+                classify_finger(&normalized, FINGER_JOINTS[1], cfg),
+                classify_finger(&normalized, FINGER_JOINTS[2], cfg),
+                classify_finger(&normalized, FINGER_JOINTS[3], cfg),
+            ],
+            finger_spreads: finger_spreads(&normalized),
+            roll_degrees: roll_degrees(projected_landmarks),
+            hand_scale_px: palm_width_px(projected_landmarks),
+            handedness: handedness_from_score(handedness_score),
+        }
+    }
+
+    /// One-shot classification of landmarks from an external source, in the
+    /// handpose model's 21-point order. Runs only the ONNX model: no voting,
+    /// motion tracking or finger states, so calls don't affect each other.
+    /// Confidence floors apply as in [`Self::classify`].
+    #[allow(dead_code)]
+    pub fn classify_landmarks(&mut self, raw_landmarks: &[[f32; 3]; 21]) -> GestureKind {
+        let probabilities = self.detect_gesture_with_model(raw_landmarks);
+        self.confidence_floors.pick(&probabilities)
     }
 
     /// Normalize landmarks for ONNX model input (matching training normalization)
@@ -217,29 +691,32 @@ impl GestureClassifier {
         Some(result)
     }
 
-    fn detect_gesture_with_model(&mut self, raw_landmarks: &[[f32; 3]]) -> GestureKind {
+    /// Runs the classifier and returns every mapped class with its softmax
+    /// probability, sorted from most to least likely. Empty when the model is
+    /// unavailable or inference fails.
+    fn detect_gesture_with_model(&mut self, raw_landmarks: &[[f32; 3]]) -> Vec<(GestureKind, f32)> {
         let session = match &mut self.model_session {
             Some(s) => s,
-            None => return GestureKind::Unknown,
+            None => return Vec::new(),
         };
 
         // Normalize landmarks for model input
         let input_vec = match Self::normalize_for_model(raw_landmarks) {
             Some(v) => v,
-            None => return GestureKind::Unknown,
+            None => return Vec::new(),
         };
 
         // Create ndarray input (1, 42) shape
         let input_array = match Array2::from_shape_vec((1, 42), input_vec) {
             Ok(arr) => arr,
-            Err(_) => return GestureKind::Unknown,
+            Err(_) => return Vec::new(),
         };
 
         // Create tensor from array
         use ort::value::Tensor;
         let tensor = match Tensor::from_array(input_array) {
             Ok(t) => t,
-            Err(_) => return GestureKind::Unknown,
+            Err(_) => return Vec::new(),
         };
 
         // Run model inference
@@ -247,7 +724,7 @@ impl GestureClassifier {
             Ok(outputs) => outputs,
             Err(e) => {
                 log::warn!("Model inference failed: {}", e);
-                return GestureKind::Unknown;
+                return Vec::new();
             }
         };
 
@@ -256,24 +733,29 @@ impl GestureClassifier {
             Ok(arr) => arr,
             Err(e) => {
                 log::warn!("Failed to extract logits: {}", e);
-                return GestureKind::Unknown;
+                return Vec::new();
             }
         };
 
-        // Find the class with highest logit value (argmax)
-        let predicted_class = logits_array
-            .iter()
+        let logits: Vec<f32> = logits_array.iter().copied().collect();
+        let mut probabilities: Vec<(GestureKind, f32)> = softmax(&logits)
+            .into_iter()
             .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .map(|(idx, _)| idx)
-            .unwrap_or(0);
-
-        // Map class index to GestureKind
-        self.class_to_gesture
-            .get(&predicted_class)
-            .copied()
-            .unwrap_or(GestureKind::Unknown)
+            .filter_map(|(idx, prob)| self.class_to_gesture.get(&idx).map(|kind| (*kind, prob)))
+            .collect();
+        probabilities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        probabilities
+    }
+}
+
+fn softmax(logits: &[f32]) -> Vec<f32> {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = logits.iter().map(|v| (v - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    if sum <= 0.0 || !sum.is_finite() {
+        return vec![0.0; logits.len()];
     }
+    exps.into_iter().map(|v| v / sum).collect()
 }
 
 fn handedness_from_score(score: f32) -> Handedness {
@@ -324,7 +806,15 @@ fn projected_span(points: &[(f32, f32)]) -> f32 {
     (max_x - min_x).max(max_y - min_y).max(1.0)
 }
 
-fn classify_finger(points: &[[f32; 3]], idx: [usize; 4]) -> FingerState {
+/// MCP, PIP, DIP and tip landmark indices of the index to pinky fingers.
+const FINGER_JOINTS: [[usize; 4]; 4] = [
+    [5, 6, 7, 8],
+    [9, 10, 11, 12],
+    [13, 14, 15, 16],
+    [17, 18, 19, 20],
+];
+
+fn measure_finger(points: &[[f32; 3]], idx: [usize; 4]) -> FingerMetrics {
     let wrist = points[0];
     let mcp = points[idx[0]];
     let pip = points[idx[1]];
@@ -335,22 +825,40 @@ fn classify_finger(points: &[[f32; 3]], idx: [usize; 4]) -> FingerState {
     let dist_pip = distance3(pip, wrist);
     let dist_mcp = distance3(mcp, wrist);
 
-    let straightness = average_straightness(sub(pip, mcp), sub(dip, pip), sub(tip, dip));
-
-    let extension = dist_tip - dist_pip;
-    let reach = dist_tip - dist_mcp;
+    FingerMetrics {
+        extension: dist_tip - dist_pip,
+        straightness: average_straightness(sub(pip, mcp), sub(dip, pip), sub(tip, dip)),
+        reach: dist_tip - dist_mcp,
+    }
+}
 
-    // Relaxed thresholds to reduce half-bent false positives (especially for pinky)
-    if extension > 0.15 && straightness > 0.40 && reach > 0.06 {
+fn classify_finger(
+    points: &[[f32; 3]],
+    idx: [usize; 4],
+    cfg: &FingerClassifierConfig,
+) -> FingerState {
+    let FingerMetrics {
+        extension,
+        straightness,
+        reach,
+    } = measure_finger(points, idx);
+
+    if extension > cfg.extended_extension
+        && straightness > cfg.extended_straightness
+        && reach > cfg.extended_reach
+    {
         FingerState::Extended
-    } else if extension < 0.08 || straightness < 0.18 || reach < 0.05 {
+    } else if extension < cfg.folded_extension
+        || straightness < cfg.folded_straightness
+        || reach < cfg.folded_reach
+    {
         FingerState::Folded
     } else {
         FingerState::HalfBent
     }
 }
 
-fn classify_thumb(points: &[[f32; 3]]) -> FingerState {
+fn classify_thumb(points: &[[f32; 3]], cfg: &FingerClassifierConfig) -> FingerState {
     let wrist = points[0];
     let cmc = points[1]; // Carpometacarpal joint
     let mcp = points[2]; // Metacarpophalangeal joint (corrected from points[1])
@@ -380,17 +888,50 @@ fn classify_thumb(points: &[[f32; 3]]) -> FingerState {
     // Reach metric: how far tip extends beyond MCP joint
     let reach = dist_tip_wrist - dist_mcp_wrist;
 
-    // Folded: thumb is close to palm and not straight (relaxed thresholds)
-    if spread < 0.25 && (straightness < 0.28 || reach < 0.15) {
+    // Folded: thumb is close to palm and not straight
+    if spread < cfg.thumb_folded_spread
+        && (straightness < cfg.thumb_folded_straightness || reach < cfg.thumb_folded_reach)
+    {
         FingerState::Folded
     // Extended: thumb is far from wrist, straight, and extends well beyond joints
-    } else if dist_tip_wrist > 0.30 && straightness > 0.28 && extension > 0.08 {
+    } else if dist_tip_wrist > cfg.thumb_extended_distance
+        && straightness > cfg.thumb_extended_straightness
+        && extension > cfg.thumb_extended_extension
+    {
         FingerState::Extended
     } else {
         FingerState::HalfBent
     }
 }
 
+fn finger_spreads(points: &[[f32; 3]]) -> [f32; 4] {
+    // (MCP, tip) per finger, thumb first.
+    const RAYS: [(usize, usize); 5] = [(2, 4), (5, 8), (9, 12), (13, 16), (17, 20)];
+
+    let mut spreads = [0.0; 4];
+    for (spread, pair) in spreads.iter_mut().zip(RAYS.windows(2)) {
+        let a = normalize(sub(points[pair[0].1], points[pair[0].0]));
+        let b = normalize(sub(points[pair[1].1], points[pair[1].0]));
+        *spread = dot(a, b).clamp(-1.0, 1.0).acos().to_degrees();
+    }
+    spreads
+}
+
+fn roll_degrees(projected: &[(f32, f32)]) -> f32 {
+    let (wrist, mcp) = (projected[0], projected[9]);
+    // Image y grows downward, so flip it to measure from "up".
+    let (dx, dy) = (mcp.0 - wrist.0, wrist.1 - mcp.1);
+    if dx.abs() < f32::EPSILON && dy.abs() < f32::EPSILON {
+        return 0.0;
+    }
+    dx.atan2(dy).to_degrees()
+}
+
+fn palm_width_px(projected: &[(f32, f32)]) -> f32 {
+    let (index, pinky) = (projected[5], projected[17]);
+    ((index.0 - pinky.0).powi(2) + (index.1 - pinky.1).powi(2)).sqrt()
+}
+
 fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
     [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
 }
@@ -525,3 +1066,210 @@ where
 
     changes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A hand whose fingers all start on the x axis and point along `dirs`,
+    /// thumb first.
+    fn hand_with_fingers(dirs: [[f32; 3]; 5]) -> Vec<[f32; 3]> {
+        const RAYS: [(usize, usize); 5] = [(2, 4), (5, 8), (9, 12), (13, 16), (17, 20)];
+        let mut points = vec![[0.0; 3]; 21];
+        for (i, ((mcp, tip), dir)) in RAYS.into_iter().zip(dirs).enumerate() {
+            let base = [i as f32, 0.0, 0.0];
+            points[mcp] = base;
+            points[tip] = [base[0] + dir[0], base[1] + dir[1], base[2] + dir[2]];
+        }
+        points
+    }
+
+    #[test]
+    fn parallel_fingers_have_no_spread() {
+        let points = hand_with_fingers([[0.0, 2.0, 0.0]; 5]);
+        for spread in finger_spreads(&points) {
+            assert!(spread.abs() < 1e-3, "{spread}");
+        }
+    }
+
+    #[test]
+    fn spreads_are_angles_between_neighbours() {
+        let up = [0.0, 1.0, 0.0];
+        let down = [0.0, -3.0, 0.0];
+        let points = hand_with_fingers([[2.0, 0.0, 0.0], up, up, down, [0.0, -1.0, 0.0]]);
+        let expected = [90.0, 0.0, 180.0, 0.0];
+        for (spread, expected) in finger_spreads(&points).into_iter().zip(expected) {
+            assert!((spread - expected).abs() < 1e-3, "{spread} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn confidence_floor_holds_back_an_unsure_gesture() {
+        let floors = ConfidenceFloors::default();
+        let unsure = [(GestureKind::MiddleFinger, 0.6), (GestureKind::Fist, 0.3)];
+        assert_eq!(floors.pick(&unsure), GestureKind::Fist);
+        let sure = [(GestureKind::MiddleFinger, 0.8), (GestureKind::Fist, 0.1)];
+        assert_eq!(floors.pick(&sure), GestureKind::MiddleFinger);
+    }
+
+    #[test]
+    fn confidence_floor_falls_back_to_unknown() {
+        let floors = ConfidenceFloors::from_flag("default=0.5").unwrap();
+        let probabilities = [
+            (GestureKind::MiddleFinger, 0.45),
+            (GestureKind::Fist, 0.4),
+            (GestureKind::Palm, 0.15),
+        ];
+        assert_eq!(floors.pick(&probabilities), GestureKind::Unknown);
+        assert_eq!(floors.pick(&[]), GestureKind::Unknown);
+    }
+
+    #[test]
+    fn confidence_floors_parse_from_the_flag() {
+        let floors = ConfidenceFloors::from_flag("MiddleFinger=0.9, default=0.2").unwrap();
+        assert_eq!(floors.floor_for(GestureKind::MiddleFinger), 0.9);
+        assert_eq!(floors.floor_for(GestureKind::XSign), 0.6);
+        assert_eq!(floors.floor_for(GestureKind::Palm), 0.2);
+        assert!(ConfidenceFloors::from_flag("MiddleFinger=2").is_none());
+        assert!(ConfidenceFloors::from_flag("NotAGesture=0.5").is_none());
+    }
+
+    #[test]
+    fn voter_ignores_a_single_noisy_frame() {
+        let base = Instant::now();
+        let mut voter = GestureVoter::new(VoterConfig::default());
+        let like = [(GestureKind::Like, 0.9), (GestureKind::Fist, 0.1)];
+        let fist = [(GestureKind::Fist, 0.9), (GestureKind::Like, 0.1)];
+        for i in 0..4 {
+            voter.update(&like, 1.0, base + Duration::from_millis(i * 33));
+        }
+        let voted = voter.update(&fist, 1.0, base + Duration::from_millis(132));
+        assert_eq!(voted[0].0, GestureKind::Like);
+        let sum: f32 = voted.iter().map(|(_, prob)| prob).sum();
+        assert!((sum - 1.0).abs() < 1e-4, "{sum}");
+    }
+
+    #[test]
+    fn voter_follows_a_held_change() {
+        let base = Instant::now();
+        let mut voter = GestureVoter::new(VoterConfig::default());
+        let like = [(GestureKind::Like, 0.9), (GestureKind::Fist, 0.1)];
+        let fist = [(GestureKind::Fist, 0.9), (GestureKind::Like, 0.1)];
+        voter.update(&like, 1.0, base);
+        // Past the window the old frames no longer count at all.
+        let voted = voter.update(&fist, 1.0, base + Duration::from_millis(600));
+        assert_eq!(voted, fist.to_vec());
+    }
+
+    #[test]
+    fn zero_window_reports_each_frame() {
+        let base = Instant::now();
+        let mut voter = GestureVoter::new(VoterConfig {
+            window: Duration::ZERO,
+            ..VoterConfig::default()
+        });
+        let like = [(GestureKind::Like, 0.9), (GestureKind::Fist, 0.1)];
+        let fist = [(GestureKind::Fist, 0.9), (GestureKind::Like, 0.1)];
+        voter.update(&like, 1.0, base);
+        let voted = voter.update(&fist, 1.0, base + Duration::from_millis(1));
+        assert_eq!(voted[0].0, GestureKind::Fist);
+    }
+
+    /// Wrist at (100, 100) with the middle-finger MCP `offset` away on screen.
+    fn roll_for(offset: (f32, f32)) -> f32 {
+        let mut projected = vec![(100.0, 100.0); 21];
+        projected[9] = (100.0 + offset.0, 100.0 + offset.1);
+        roll_degrees(&projected)
+    }
+
+    #[test]
+    fn roll_is_measured_clockwise_from_up() {
+        // Screen y grows downward: fingers up means the MCP is above.
+        let cases = [
+            ((0.0, -50.0), 0.0),
+            ((50.0, 0.0), 90.0),
+            ((-50.0, 0.0), -90.0),
+            ((0.0, 50.0), 180.0),
+            ((50.0, -50.0), 45.0),
+        ];
+        for (offset, expected) in cases {
+            let roll = roll_for(offset);
+            assert!((roll - expected).abs() < 1e-3, "{offset:?}: {roll}");
+        }
+    }
+
+    #[test]
+    fn roll_of_a_collapsed_hand_is_zero() {
+        assert_eq!(roll_for((0.0, 0.0)), 0.0);
+    }
+
+    /// A straight index finger whose tip reaches `extension` beyond the PIP.
+    fn index_finger(extension: f32) -> Vec<[f32; 3]> {
+        let mut points = vec![[0.0; 3]; 21];
+        points[5] = [0.0, 0.5, 0.0];
+        points[6] = [0.0, 0.6, 0.0];
+        points[7] = [0.0, 0.6 + extension / 2.0, 0.0];
+        points[8] = [0.0, 0.6 + extension, 0.0];
+        points
+    }
+
+    #[test]
+    fn finger_config_flips_a_borderline_finger() {
+        let points = index_finger(0.1);
+        let defaults = FingerClassifierConfig::default();
+        assert_eq!(
+            classify_finger(&points, [5, 6, 7, 8], &defaults),
+            FingerState::HalfBent
+        );
+
+        let relaxed = FingerClassifierConfig::from_flag("extended_extension=0.09").unwrap();
+        assert_eq!(
+            classify_finger(&points, [5, 6, 7, 8], &relaxed),
+            FingerState::Extended
+        );
+        let strict = FingerClassifierConfig::from_flag("folded_extension=0.11").unwrap();
+        assert_eq!(
+            classify_finger(&points, [5, 6, 7, 8], &strict),
+            FingerState::Folded
+        );
+    }
+
+    #[test]
+    fn finger_config_flag_rejects_unknown_fields() {
+        assert!(FingerClassifierConfig::from_flag("extended_reach=0.1").is_some());
+        assert!(FingerClassifierConfig::from_flag("extended_length=0.1").is_none());
+        assert!(FingerClassifierConfig::from_flag("extended_reach=").is_none());
+    }
+
+    /// Wrist motion reported for a hand sliding right by its own width each
+    /// frame.
+    fn motion_of_sliding_hand(classifier: &mut GestureClassifier) -> GestureMotion {
+        let raw = vec![[0.0; 3]; 21];
+        let start = Instant::now();
+        let mut motion = GestureMotion::Steady;
+        for step in 0..5 {
+            let x0 = step as f32 * 100.0;
+            let projected: Vec<(f32, f32)> = (0..21)
+                .map(|i| (x0 + i as f32 * 5.0, 200.0 + i as f32 * 5.0))
+                .collect();
+            let timestamp = start + Duration::from_millis(50 * step);
+            motion = classifier
+                .classify(&raw, &projected, 0.9, 0.5, timestamp)
+                .unwrap()
+                .motion;
+        }
+        motion
+    }
+
+    #[test]
+    fn disabling_motion_reports_a_moving_hand_as_steady() {
+        let mut tracking = GestureClassifier::without_model();
+        assert_ne!(motion_of_sliding_hand(&mut tracking), GestureMotion::Steady);
+
+        let mut static_only = GestureClassifier::without_model().with_motion(false);
+        assert_eq!(
+            motion_of_sliding_hand(&mut static_only),
+            GestureMotion::Steady
+        );
+    }
+}
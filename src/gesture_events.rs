@@ -0,0 +1,347 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::types::GestureKind;
+
+/// Turns the per-frame gesture stream into discrete trigger events.
+///
+/// A gesture fires when it first appears (edge-triggered), and the same kind
+/// cannot fire again until its cooldown has elapsed, so a flickering label
+/// does not re-run a bound action several times a second.
+pub struct GestureTrigger {
+    cooldown: Duration,
+    current: Option<GestureKind>,
+    last_fired: HashMap<GestureKind, Instant>,
+}
+
+impl GestureTrigger {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            current: None,
+            last_fired: HashMap::new(),
+        }
+    }
+
+    /// Feeds the latest recognized gesture (`None` when no hand is present) and
+    /// returns the kind that should fire, if any.
+    pub fn update(&mut self, gesture: Option<GestureKind>, now: Instant) -> Option<GestureKind> {
+        let previous = std::mem::replace(&mut self.current, gesture);
+        let kind = gesture?;
+        if previous == Some(kind) || matches!(kind, GestureKind::Unknown | GestureKind::NoGesture) {
+            return None;
+        }
+
+        let cooling_down = self
+            .last_fired
+            .get(&kind)
+            .is_some_and(|last| now.saturating_duration_since(*last) < self.cooldown);
+        if cooling_down {
+            return None;
+        }
+
+        self.last_fired.insert(kind, now);
+        Some(kind)
+    }
+}
+
+/// Synthetic events from the open → closed → open hand sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrabEvent {
+    /// An open hand closed into a fist.
+    Grab,
+    /// The fist opened again soon after a grab.
+    Release,
+}
+
+/// Watches the gesture stream for a quick close-and-reopen of the hand.
+///
+/// `Grab` fires when a closed hand follows an open one within `window`, and
+/// `Release` when the hand reopens within `window` of that grab. Classes that
+/// are neither open nor closed (transition frames, `Unknown`) are skipped so
+/// a blurry frame mid-motion doesn't break the sequence; losing the hand
+/// resets it.
+pub struct GrabDetector {
+    window: Duration,
+    last_open: Option<Instant>,
+    grabbed_at: Option<Instant>,
+}
+
+impl GrabDetector {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_open: None,
+            grabbed_at: None,
+        }
+    }
+
+    pub fn update(&mut self, gesture: Option<GestureKind>, now: Instant) -> Option<GrabEvent> {
+        let Some(kind) = gesture else {
+            self.last_open = None;
+            self.grabbed_at = None;
+            return None;
+        };
+        let within = |at: Instant| now.saturating_duration_since(at) <= self.window;
+
+        if is_open_hand(kind) {
+            self.last_open = Some(now);
+            let released = self.grabbed_at.take().is_some_and(within);
+            return released.then_some(GrabEvent::Release);
+        }
+        if is_closed_hand(kind) && self.grabbed_at.is_none() && self.last_open.is_some_and(within) {
+            self.last_open = None;
+            self.grabbed_at = Some(now);
+            return Some(GrabEvent::Grab);
+        }
+        None
+    }
+}
+
+fn is_open_hand(kind: GestureKind) -> bool {
+    matches!(
+        kind,
+        GestureKind::Palm | GestureKind::Stop | GestureKind::StopInverted | GestureKind::Four
+    )
+}
+
+fn is_closed_hand(kind: GestureKind) -> bool {
+    matches!(
+        kind,
+        GestureKind::Fist | GestureKind::Grabbing | GestureKind::Grip
+    )
+}
+
+/// Rolling record of the last few recognized gestures, used to show how
+/// steadily the current label has been held.
+pub struct GestureStability {
+    window: usize,
+    recent: VecDeque<Option<GestureKind>>,
+}
+
+impl GestureStability {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            recent: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+
+    pub fn push(&mut self, gesture: Option<GestureKind>) {
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(gesture);
+    }
+
+    /// Fraction of the window (0.0..=1.0) that matched the newest gesture.
+    /// Frames not yet seen count as mismatches, so the meter fills up as the
+    /// gesture is held.
+    pub fn ratio(&self) -> f32 {
+        let Some(Some(current)) = self.recent.back() else {
+            return 0.0;
+        };
+        let matching = self
+            .recent
+            .iter()
+            .filter(|kind| **kind == Some(*current))
+            .count();
+        matching as f32 / self.window as f32
+    }
+
+    pub fn clear(&mut self) {
+        self.recent.clear();
+    }
+}
+
+/// How often a streaming output (IPC socket, OpenPose files) receives
+/// results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Every recognized frame.
+    #[default]
+    EveryFrame,
+    /// Only frames where the classifier's voted gesture differs from the
+    /// last one sent, including a hand appearing or leaving. The voter
+    /// already debounces flicker, so this yields one result per transition.
+    OnChange,
+}
+
+impl OutputMode {
+    /// Parses the command-line spelling, `every-frame` or `on-change`.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        match value {
+            "every-frame" => Some(OutputMode::EveryFrame),
+            "on-change" => Some(OutputMode::OnChange),
+            _ => None,
+        }
+    }
+}
+
+/// Per-output gate applying an [`OutputMode`] to the result stream.
+#[derive(Clone, Debug, Default)]
+pub struct OutputFilter {
+    mode: OutputMode,
+    /// Gesture of the last result let through; `None` before the first.
+    last_sent: Option<Option<GestureKind>>,
+}
+
+impl OutputFilter {
+    pub fn new(mode: OutputMode) -> Self {
+        Self {
+            mode,
+            last_sent: None,
+        }
+    }
+
+    /// Feeds the gesture of the latest result (`None` without a classified
+    /// hand) and returns whether the result should be sent.
+    pub fn accept(&mut self, gesture: Option<GestureKind>) -> bool {
+        match self.mode {
+            OutputMode::EveryFrame => true,
+            OutputMode::OnChange => {
+                let changed = self.last_sent != Some(gesture);
+                self.last_sent = Some(gesture);
+                changed
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIKE: Option<GestureKind> = Some(GestureKind::Like);
+
+    fn ms(base: Instant, offset: u64) -> Instant {
+        base + Duration::from_millis(offset)
+    }
+
+    #[test]
+    fn trigger_fires_once_per_appearance() {
+        let base = Instant::now();
+        let mut trigger = GestureTrigger::new(Duration::from_millis(500));
+        assert_eq!(trigger.update(LIKE, ms(base, 0)), LIKE);
+        // Held: no repeat while the same gesture stays.
+        assert_eq!(trigger.update(LIKE, ms(base, 33)), None);
+        assert_eq!(trigger.update(LIKE, ms(base, 2_000)), None);
+    }
+
+    #[test]
+    fn rapid_retrigger_within_cooldown_fires_once() {
+        let base = Instant::now();
+        let mut trigger = GestureTrigger::new(Duration::from_millis(500));
+        let fired: Vec<_> = [LIKE, None, LIKE, None, LIKE]
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, gesture)| trigger.update(gesture, ms(base, i as u64 * 50)))
+            .collect();
+        assert_eq!(fired, vec![GestureKind::Like]);
+    }
+
+    #[test]
+    fn trigger_fires_again_after_cooldown() {
+        let base = Instant::now();
+        let mut trigger = GestureTrigger::new(Duration::from_millis(500));
+        assert_eq!(trigger.update(LIKE, ms(base, 0)), LIKE);
+        assert_eq!(trigger.update(None, ms(base, 100)), None);
+        assert_eq!(trigger.update(LIKE, ms(base, 600)), LIKE);
+    }
+
+    #[test]
+    fn cooldown_is_per_gesture() {
+        let base = Instant::now();
+        let mut trigger = GestureTrigger::new(Duration::from_millis(500));
+        assert_eq!(trigger.update(LIKE, ms(base, 0)), LIKE);
+        let palm = Some(GestureKind::Palm);
+        assert_eq!(trigger.update(palm, ms(base, 50)), palm);
+    }
+
+    #[test]
+    fn unknown_and_no_gesture_never_fire() {
+        let base = Instant::now();
+        let mut trigger = GestureTrigger::new(Duration::ZERO);
+        assert_eq!(
+            trigger.update(Some(GestureKind::Unknown), ms(base, 0)),
+            None
+        );
+        assert_eq!(
+            trigger.update(Some(GestureKind::NoGesture), ms(base, 10)),
+            None
+        );
+    }
+
+    #[test]
+    fn stability_fills_up_while_held() {
+        let mut stability = GestureStability::new(4);
+        assert_eq!(stability.ratio(), 0.0);
+        for expected in [0.25, 0.5, 0.75, 1.0, 1.0] {
+            stability.push(LIKE);
+            assert_eq!(stability.ratio(), expected);
+        }
+    }
+
+    #[test]
+    fn stability_counts_only_the_newest_gesture() {
+        let mut stability = GestureStability::new(4);
+        for gesture in [LIKE, LIKE, LIKE, Some(GestureKind::Palm)] {
+            stability.push(gesture);
+        }
+        assert_eq!(stability.ratio(), 0.25);
+        // Losing the hand empties the meter until it is seen again.
+        stability.push(None);
+        assert_eq!(stability.ratio(), 0.0);
+        stability.clear();
+        stability.push(LIKE);
+        assert_eq!(stability.ratio(), 0.25);
+    }
+
+    const PALM: Option<GestureKind> = Some(GestureKind::Palm);
+    const FIST: Option<GestureKind> = Some(GestureKind::Fist);
+
+    #[test]
+    fn grab_and_release_within_the_window() {
+        let base = Instant::now();
+        let mut grab = GrabDetector::new(Duration::from_millis(500));
+        assert_eq!(grab.update(PALM, ms(base, 0)), None);
+        assert_eq!(grab.update(FIST, ms(base, 200)), Some(GrabEvent::Grab));
+        // Holding the fist doesn't grab again.
+        assert_eq!(grab.update(FIST, ms(base, 300)), None);
+        assert_eq!(grab.update(PALM, ms(base, 500)), Some(GrabEvent::Release));
+        assert_eq!(grab.update(PALM, ms(base, 600)), None);
+    }
+
+    #[test]
+    fn slow_close_is_not_a_grab() {
+        let base = Instant::now();
+        let mut grab = GrabDetector::new(Duration::from_millis(500));
+        grab.update(PALM, ms(base, 0));
+        assert_eq!(grab.update(FIST, ms(base, 800)), None);
+    }
+
+    #[test]
+    fn transition_frames_do_not_break_the_grab() {
+        let base = Instant::now();
+        let mut grab = GrabDetector::new(Duration::from_millis(500));
+        grab.update(PALM, ms(base, 0));
+        assert_eq!(grab.update(Some(GestureKind::Unknown), ms(base, 100)), None);
+        assert_eq!(grab.update(FIST, ms(base, 200)), Some(GrabEvent::Grab));
+    }
+
+    #[test]
+    fn losing_the_hand_resets_the_grab() {
+        let base = Instant::now();
+        let mut grab = GrabDetector::new(Duration::from_millis(500));
+        grab.update(PALM, ms(base, 0));
+        grab.update(None, ms(base, 100));
+        assert_eq!(grab.update(FIST, ms(base, 200)), None);
+
+        grab.update(PALM, ms(base, 300));
+        assert_eq!(grab.update(FIST, ms(base, 400)), Some(GrabEvent::Grab));
+        grab.update(None, ms(base, 450));
+        assert_eq!(grab.update(PALM, ms(base, 500)), None);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    pipeline::{RecognizerBackend, recognizer::Recognizer},
+    types::Frame,
+};
+
+/// Largest accepted request body; a 4K PNG stays well below this.
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+/// Largest accepted request line plus headers.
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// Stops a stalled client from holding the single connection slot.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Virtual time between requests, far enough apart that tracking and voting
+/// never carry over from one image to the next.
+const REQUEST_SPACING: Duration = Duration::from_secs(2);
+
+/// Runs a minimal HTTP/1.1 server on localhost without the camera or the
+/// window. `POST /classify` takes a PNG or JPEG body and answers with the
+/// result as [`GestureResult::event_json`](crate::types::GestureResult::event_json).
+/// Requests are handled one at a time on the calling thread.
+pub fn serve(port: u16, backend: &RecognizerBackend) -> Result<()> {
+    let mut recognizer = Recognizer::new(backend).context("failed to load models")?;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .with_context(|| format!("failed to listen on port {port}"))?;
+    log::info!("serving POST /classify on http://127.0.0.1:{port}");
+
+    let base = Instant::now();
+    let mut requests = 0u64;
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                log::warn!("http accept failed: {err}");
+                continue;
+            }
+        };
+        let timestamp = base + REQUEST_SPACING * requests as u32;
+        requests += 1;
+        if let Err(err) = handle_connection(stream, &mut recognizer, timestamp, requests) {
+            log::warn!("http request failed: {err:#}");
+        }
+    }
+    Ok(())
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":\"{}\"}}", escape_json(message)),
+        }
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    recognizer: &mut Recognizer,
+    timestamp: Instant,
+    sequence: u64,
+) -> Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let response = match read_request(&mut stream)? {
+        Ok(request) => route(&request, recognizer, timestamp, sequence),
+        Err(response) => response,
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// The next request, or the error response for a malformed one. The outer
+/// error is an I/O failure with no one to answer.
+fn read_request(stream: &mut TcpStream) -> Result<Result<Request, Response>> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(Err(Response::error(
+            "400 Bad Request",
+            "malformed request line",
+        )));
+    };
+
+    let mut header_bytes = request_line.len();
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        header_bytes += line.len();
+        if header_bytes > MAX_HEADER_BYTES {
+            return Ok(Err(Response::error(
+                "431 Request Header Fields Too Large",
+                "headers too large",
+            )));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let body = match content_length {
+        Some(len) if len > MAX_BODY_BYTES => {
+            return Ok(Err(Response::error(
+                "413 Payload Too Large",
+                "image too large",
+            )));
+        }
+        Some(len) => {
+            let mut body = vec![0u8; len];
+            reader.read_exact(&mut body)?;
+            body
+        }
+        None => Vec::new(),
+    };
+    Ok(Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body,
+    }))
+}
+
+fn route(
+    request: &Request,
+    recognizer: &mut Recognizer,
+    timestamp: Instant,
+    sequence: u64,
+) -> Response {
+    if request.path != "/classify" {
+        return Response::error("404 Not Found", "only /classify is served");
+    }
+    if request.method != "POST" {
+        return Response::error("405 Method Not Allowed", "use POST");
+    }
+
+    let image = match image::load_from_memory(&request.body) {
+        Ok(image) => image.to_rgba8(),
+        Err(err) => {
+            return Response::error("400 Bad Request", &format!("invalid image: {err}"));
+        }
+    };
+    let (width, height) = image.dimensions();
+    let Some(mut frame) = Frame::from_rgba(width, height, image.into_raw()) else {
+        return Response::error("400 Bad Request", "invalid image: size mismatch");
+    };
+    frame.timestamp = timestamp;
+    frame.sequence = sequence;
+
+    match recognizer.process(&frame) {
+        Ok(result) => Response {
+            status: "200 OK",
+            body: result.event_json(),
+        },
+        Err(err) => Response::error(
+            "422 Unprocessable Entity",
+            &format!("recognition failed: {err:#}"),
+        ),
+    }
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            ch if (ch as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use crossbeam_channel::Sender;
+
+use crate::{
+    gesture_events::{OutputFilter, OutputMode},
+    types::GestureKind,
+};
+
+/// Lines buffered per client before new lines are dropped for it. A reader
+/// that falls this far behind only loses lines; it never holds up the
+/// others or the UI.
+#[cfg(unix)]
+const CLIENT_BACKLOG: usize = 64;
+
+/// Streams every recognition result as newline-delimited JSON (see
+/// [`GestureResult::event_json`](crate::types::GestureResult::event_json))
+/// to clients of a Unix domain socket. Clients may connect and disconnect
+/// at any time and only see lines produced while connected.
+pub struct IpcOutput {
+    tx: Sender<String>,
+    filter: OutputFilter,
+}
+
+impl IpcOutput {
+    #[cfg(unix)]
+    pub fn spawn(path: PathBuf, mode: OutputMode) -> Option<Self> {
+        use crossbeam_channel::{TrySendError, bounded, unbounded};
+        use std::{
+            io::Write,
+            os::unix::{fs::FileTypeExt, net::UnixListener},
+            thread,
+        };
+
+        // A socket left behind by an earlier run would make bind fail; never
+        // remove anything that isn't a socket.
+        if let Ok(meta) = std::fs::symlink_metadata(&path)
+            && meta.file_type().is_socket()
+        {
+            let _ = std::fs::remove_file(&path);
+        }
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::warn!("failed to listen on {}: {err}", path.display());
+                return None;
+            }
+        };
+        log::info!("streaming gesture results to {}", path.display());
+
+        let (tx, rx) = unbounded::<String>();
+        let (client_tx, client_rx) = unbounded::<Sender<String>>();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        log::warn!("ipc accept failed: {err}");
+                        continue;
+                    }
+                };
+                let (line_tx, line_rx) = bounded::<String>(CLIENT_BACKLOG);
+                if client_tx.send(line_tx).is_err() {
+                    break;
+                }
+                thread::spawn(move || {
+                    for line in line_rx {
+                        if stream.write_all(line.as_bytes()).is_err() {
+                            break;
+                        }
+                    }
+                    log::debug!("ipc client disconnected");
+                });
+            }
+        });
+
+        thread::spawn(move || {
+            let mut clients: Vec<Sender<String>> = Vec::new();
+            for line in rx {
+                clients.extend(client_rx.try_iter());
+                clients.retain(|client| match client.try_send(line.clone()) {
+                    Ok(()) | Err(TrySendError::Full(_)) => true,
+                    Err(TrySendError::Disconnected(_)) => false,
+                });
+            }
+            let _ = std::fs::remove_file(&path);
+        });
+
+        Some(Self {
+            tx,
+            filter: OutputFilter::new(mode),
+        })
+    }
+
+    /// Named pipes would need platform APIs this crate doesn't link against.
+    #[cfg(not(unix))]
+    pub fn spawn(path: PathBuf, _mode: OutputMode) -> Option<Self> {
+        log::warn!(
+            "--ipc {} ignored: only Unix domain sockets are supported",
+            path.display()
+        );
+        None
+    }
+
+    /// Whether the result with this gesture should be sent under the
+    /// configured [`OutputMode`]; call once per result.
+    pub fn accept(&mut self, gesture: Option<GestureKind>) -> bool {
+        self.filter.accept(gesture)
+    }
+
+    /// Queues one result line; `line` must not contain a newline.
+    pub fn send(&self, mut line: String) {
+        line.push('\n');
+        let _ = self.tx.send(line);
+    }
+}
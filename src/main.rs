@@ -1,23 +1,64 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod emoji_output;
 mod gesture;
+mod gesture_events;
+mod http_server;
+mod ipc_output;
 mod model_download;
+mod openpose_output;
 mod pipeline;
+mod snapshot_output;
 mod types;
 mod ui;
+mod video_recorder;
 
-use anyhow::Result;
-use crossbeam_channel::bounded;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use gesture::{ConfidenceFloors, FingerClassifierConfig, VoterConfig};
+use gesture_events::OutputMode;
 use gpui::Application;
 use gpui_component;
-use pipeline::RecognizerBackend;
+use model_download::SessionThreads;
+use pipeline::{
+    RecognizerBackend,
+    channel::ChannelConfig,
+    recognizer::{InferenceProvider, PreprocessHook, PreprocessMode, telemetry::FileMetricsSink},
+};
+use video_recorder::{RecordingConfig, VideoFormat};
 
 fn main() -> Result<()> {
     env_logger::init();
 
-    let (camera_frame_tx, camera_frame_rx) = bounded(1);
-
-    let recognizer_backend = RecognizerBackend::default();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let camera_arg = parse_flag(&args, "--camera");
+    let output_mode = parse_output_mode(&args, "--output-mode")?.unwrap_or_default();
+    let outputs = ui::OutputOptions {
+        emoji_file: parse_flag(&args, "--emoji-file").map(PathBuf::from),
+        openpose_dir: parse_flag(&args, "--openpose-dir").map(PathBuf::from),
+        openpose_mode: parse_output_mode(&args, "--openpose-mode")?.unwrap_or(output_mode),
+        ipc_socket: parse_flag(&args, "--ipc").map(PathBuf::from),
+        ipc_mode: parse_output_mode(&args, "--ipc-mode")?.unwrap_or(output_mode),
+        snapshot_dir: parse_flag(&args, "--snapshot-dir").map(PathBuf::from),
+        export_smoothed: match parse_flag(&args, "--export-landmarks").as_deref() {
+            None | Some("raw") => false,
+            Some("smoothed") => true,
+            Some(other) => bail!("invalid --export-landmarks {other}: expected raw or smoothed"),
+        },
+        recording: parse_recording(&args)?,
+    };
+    let recognizer_backend = parse_recognizer_backend(&args)?;
+    if let Some(port) = parse_flag(&args, "--serve") {
+        let port = port
+            .parse::<u16>()
+            .with_context(|| format!("invalid --serve port {port}"))?;
+        return http_server::serve(port, &recognizer_backend);
+    }
 
     Application::new()
         .with_assets(gpui_component_assets::Assets)
@@ -26,9 +67,9 @@ fn main() -> Result<()> {
 
             if let Err(err) = ui::launch_ui(
                 app,
-                camera_frame_rx,
-                camera_frame_tx,
                 recognizer_backend.clone(),
+                camera_arg.clone(),
+                outputs.clone(),
             ) {
                 eprintln!("failed to launch ui: {err:?}");
             }
@@ -36,3 +77,263 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Value of `name <value>` or `name=<value>` on the command line.
+fn parse_flag(args: &[String], name: &str) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            return iter.next().cloned();
+        }
+        if let Some(value) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Value of flag `name` run through `parse`, failing with what was
+/// `expected` when it doesn't parse; `None` when the flag is missing.
+fn parse_value<T>(
+    args: &[String],
+    name: &str,
+    expected: &str,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Result<Option<T>> {
+    parse_flag(args, name)
+        .map(|value| {
+            parse(&value).with_context(|| format!("invalid {name} {value}: expected {expected}"))
+        })
+        .transpose()
+}
+
+/// `x1,y1,x2,y2` with each value in `[0, 1]` and a non-empty area.
+fn parse_region(value: &str) -> Option<[f32; 4]> {
+    let values: Vec<f32> = value
+        .split(',')
+        .map(|part| part.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let region: [f32; 4] = values.try_into().ok()?;
+    let in_range = region.iter().all(|v| (0.0..=1.0).contains(v));
+    (in_range && region[0] < region[2] && region[1] < region[3]).then_some(region)
+}
+
+/// The recognizer configuration from the command line, defaults elsewhere.
+fn parse_recognizer_backend(args: &[String]) -> Result<RecognizerBackend> {
+    let landmark_smoothing = parse_value(args, "--smooth-landmarks", "0 < alpha <= 1", |alpha| {
+        alpha
+            .parse::<f32>()
+            .ok()
+            .filter(|alpha| *alpha > 0.0 && *alpha <= 1.0)
+    })?;
+    let seconds = |name| {
+        parse_value(args, name, "seconds above 0", |secs| {
+            secs.parse::<f32>()
+                .ok()
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .map(Duration::from_secs_f32)
+        })
+    };
+    let watchdog_timeout = seconds("--watchdog")?;
+    let mut backend = RecognizerBackend::default()
+        .with_landmark_smoothing(landmark_smoothing)
+        .with_flip_landmark_z(args.iter().any(|arg| arg == "--flip-landmark-z"))
+        .with_skip_palm_detection(args.iter().any(|arg| arg == "--skip-palm-detection"))
+        .with_enable_motion(!args.iter().any(|arg| arg == "--no-motion"))
+        .with_palm_grayscale(args.iter().any(|arg| arg == "--palm-grayscale"))
+        .with_prefer_embedded_models(
+            cfg!(feature = "embed-models") && !args.iter().any(|arg| arg == "--model-files"),
+        )
+        .with_watchdog_timeout(watchdog_timeout)
+        .with_metrics_interval(seconds("--metrics-interval")?);
+    if let Some(path) = parse_flag(args, "--metrics-log") {
+        let sink = FileMetricsSink::create(Path::new(&path))?;
+        backend = backend.with_metrics_sink(Arc::new(sink));
+    }
+    if let Some(expansion) = parse_value(
+        args,
+        "--crop-expansion",
+        "a factor of at least 1",
+        |factor| factor.parse::<f32>().ok().filter(|factor| *factor >= 1.0),
+    )? {
+        backend = backend.with_crop_expansion(expansion);
+    }
+    if let Some(threshold) =
+        parse_value(args, "--min-sharpness", "a variance above 0", |variance| {
+            variance
+                .parse::<f32>()
+                .ok()
+                .filter(|variance| variance.is_finite() && *variance > 0.0)
+        })?
+    {
+        backend = backend.with_min_sharpness(Some(threshold));
+    }
+    if let Some(threads) = parse_value(
+        args,
+        "--session-threads",
+        "<intra>[,<inter>] thread counts of at least 1",
+        SessionThreads::from_flag,
+    )? {
+        backend = backend.with_session_threads(threads);
+    }
+    if let Some(region) = parse_value(
+        args,
+        "--ignore-region",
+        "x1,y1,x2,y2 as fractions of the frame",
+        parse_region,
+    )? {
+        backend = backend.with_preprocess(Some(PreprocessHook::black_out(region)));
+    }
+    if let Some(shift) = parse_value(args, "--crop-shift", "a fraction", |fraction| {
+        fraction
+            .parse::<f32>()
+            .ok()
+            .filter(|fraction| fraction.is_finite())
+    })? {
+        backend = backend.with_crop_shift(shift);
+    }
+    if let Some(size) = parse_value(
+        args,
+        "--handpose-input-size",
+        "a size in pixels above 0",
+        |size| size.parse::<u32>().ok().filter(|size| *size > 0),
+    )? {
+        backend = backend.with_handpose_input_size(size);
+    }
+    if let Some(size) = parse_value(
+        args,
+        "--crop-sample-size",
+        "a size in pixels above 0",
+        |size| size.parse::<u32>().ok().filter(|size| *size > 0),
+    )? {
+        backend = backend.with_crop_output_size(Some(size));
+    }
+    if let Some(max_side) =
+        parse_value(
+            args,
+            "--working-resolution",
+            "pixels or off",
+            |value| match value {
+                "off" => Some(None),
+                side => side.parse::<u32>().ok().filter(|side| *side > 0).map(Some),
+            },
+        )?
+    {
+        backend = backend.with_working_resolution(max_side);
+    }
+    let millis = |name, expected| {
+        parse_value(args, name, expected, |ms| {
+            ms.parse::<u64>().ok().map(Duration::from_millis)
+        })
+    };
+    let default_voter = backend.voter();
+    let voter = VoterConfig {
+        window: millis("--vote-window", "milliseconds")?.unwrap_or(default_voter.window),
+        half_life: millis("--vote-half-life", "milliseconds")?.unwrap_or(default_voter.half_life),
+    };
+    backend = backend.with_voter(voter);
+    if let Some(hold) = millis("--skeleton-hold", "milliseconds")? {
+        backend = backend.with_skeleton_hold(hold);
+    }
+    if let Some(config) = parse_value(
+        args,
+        "--finger-thresholds",
+        "<field>=<value>[,...]",
+        FingerClassifierConfig::from_flag,
+    )? {
+        backend = backend.with_finger_config(config);
+    }
+    if let Some(floors) = parse_value(
+        args,
+        "--confidence-floors",
+        "<gesture>=<0..1>[,...]",
+        ConfidenceFloors::from_flag,
+    )? {
+        backend = backend.with_confidence_floors(floors);
+    }
+    if let Some(mode) = parse_value(
+        args,
+        "--palm-preprocess",
+        "letterbox or center-crop",
+        PreprocessMode::from_flag,
+    )? {
+        backend = backend.with_palm_preprocess(mode);
+    }
+    if let Some(separation) = parse_value(
+        args,
+        "--palm-keypoint-separation",
+        "a fraction above 0",
+        |fraction| {
+            fraction
+                .parse::<f32>()
+                .ok()
+                .filter(|fraction| *fraction > 0.0)
+        },
+    )? {
+        backend = backend.with_palm_keypoint_separation(Some(separation));
+    }
+    if let Some(providers) = parse_value(
+        args,
+        "--providers",
+        "a comma-separated list of coreml, cuda, directml and cpu",
+        |list| {
+            list.split(',')
+                .map(|name| InferenceProvider::from_flag(name.trim()))
+                .collect::<Option<Vec<_>>>()
+        },
+    )? {
+        backend = backend.with_providers(providers);
+    }
+    let channel = |name| {
+        parse_value(
+            args,
+            name,
+            "<capacity>:<drop-newest|drop-oldest|block>",
+            ChannelConfig::from_flag,
+        )
+    };
+    if let Some(config) = channel("--frame-channel")? {
+        backend = backend.with_frame_channel(config);
+    }
+    if let Some(config) = channel("--result-channel")? {
+        backend = backend.with_result_channel(config);
+    }
+    Ok(backend)
+}
+
+/// [`OutputMode`] from flag `name`, or `fallback` when it is missing or not
+/// understood.
+fn parse_output_mode(args: &[String], name: &str) -> Result<Option<OutputMode>> {
+    parse_value(
+        args,
+        name,
+        "every-frame or on-change",
+        OutputMode::from_flag,
+    )
+}
+
+fn parse_recording(args: &[String]) -> Result<RecordingConfig> {
+    let mut config = RecordingConfig::default();
+    if let Some(dir) = parse_flag(args, "--record-dir") {
+        config.dir = PathBuf::from(dir);
+    }
+    if let Some(format) = parse_value(
+        args,
+        "--record-format",
+        "mp4 or webm",
+        VideoFormat::from_flag,
+    )? {
+        config.format = format;
+    }
+    if let Some(fps) = parse_value(args, "--record-fps", "1 to 120", |fps| {
+        fps.parse::<u32>()
+            .ok()
+            .filter(|fps| (1..=120).contains(fps))
+    })? {
+        config.fps = fps;
+    }
+    Ok(config)
+}
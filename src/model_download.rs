@@ -1,12 +1,15 @@
 use std::{
+    collections::HashMap,
     fs,
     io::{Read, Write},
     path::{Path, PathBuf},
-    time::Duration,
+    sync::{Arc, LazyLock, Mutex, PoisonError, Weak},
+    time::{Duration, SystemTime},
 };
 
 use anyhow::Context;
 use indicatif::{ProgressBar, ProgressStyle};
+use ort::session::{Session, builder::SessionBuilder};
 use reqwest::blocking::Client;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -16,12 +19,24 @@ pub enum ModelKind {
     GestureClassifier,
 }
 
+// Download sources are tried in order: GitHub raw first, then the jsDelivr
+// CDN mirror of the same repository for when raw.githubusercontent.com is
+// rate-limited or unreachable.
 const HANDPOSE_ESTIMATOR_MODEL_FILENAME: &str = "handpose_estimation.onnx";
-const HANDPOSE_ESTIMATOR_MODEL_URL: &str = "https://raw.githubusercontent.com/weidix/gesture-universe/refs/heads/main/models/handpose_estimation.onnx";
+const HANDPOSE_ESTIMATOR_MODEL_URLS: &[&str] = &[
+    "https://raw.githubusercontent.com/weidix/gesture-universe/refs/heads/main/models/handpose_estimation.onnx",
+    "https://cdn.jsdelivr.net/gh/weidix/gesture-universe@main/models/handpose_estimation.onnx",
+];
 const PALM_DETECTOR_MODEL_FILENAME: &str = "palm_detection.onnx";
-const PALM_DETECTOR_MODEL_URL: &str = "https://raw.githubusercontent.com/weidix/gesture-universe/refs/heads/main/models/palm_detection.onnx";
+const PALM_DETECTOR_MODEL_URLS: &[&str] = &[
+    "https://raw.githubusercontent.com/weidix/gesture-universe/refs/heads/main/models/palm_detection.onnx",
+    "https://cdn.jsdelivr.net/gh/weidix/gesture-universe@main/models/palm_detection.onnx",
+];
 const GESTURE_CLASSIFIER_MODEL_FILENAME: &str = "gesture_mlp.onnx";
-const GESTURE_CLASSIFIER_MODEL_URL: &str = "https://raw.githubusercontent.com/weidix/gesture-universe/refs/heads/main/models/gesture_mlp.onnx";
+const GESTURE_CLASSIFIER_MODEL_URLS: &[&str] = &[
+    "https://raw.githubusercontent.com/weidix/gesture-universe/refs/heads/main/models/gesture_mlp.onnx",
+    "https://cdn.jsdelivr.net/gh/weidix/gesture-universe@main/models/gesture_mlp.onnx",
+];
 
 pub fn default_handpose_estimator_model_path() -> PathBuf {
     PathBuf::from("models").join(HANDPOSE_ESTIMATOR_MODEL_FILENAME)
@@ -35,6 +50,213 @@ pub fn default_gesture_classifier_model_path() -> PathBuf {
     PathBuf::from("models").join(GESTURE_CLASSIFIER_MODEL_FILENAME)
 }
 
+/// Which models are already on disk at their default paths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModelAvailability {
+    pub handpose: bool,
+    pub palm: bool,
+    pub classifier: bool,
+}
+
+impl ModelAvailability {
+    pub fn all_present(&self) -> bool {
+        self.handpose && self.palm && self.classifier
+    }
+}
+
+/// Checks for the model files without downloading anything.
+pub fn models_available() -> ModelAvailability {
+    ModelAvailability {
+        handpose: default_handpose_estimator_model_path().exists(),
+        palm: default_palm_detector_model_path().exists(),
+        classifier: default_gesture_classifier_model_path().exists(),
+    }
+}
+
+/// ONNX Runtime thread pool sizes for one session.
+///
+/// Frame conversion, resizing and gamma correction run on rayon's global
+/// pool, which already spans every core. The default therefore gives the
+/// session only half the cores (at most four, beyond which the small hand
+/// models stop scaling) so inference doesn't fight the camera thread's
+/// rayon work and the UI for CPU time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionThreads {
+    /// Threads used inside a single operator.
+    pub intra: usize,
+    /// Threads running independent graph nodes in parallel.
+    pub inter: usize,
+}
+
+impl SessionThreads {
+    /// Parses `<intra>` or `<intra>,<inter>`, each at least 1; `inter`
+    /// defaults to 1.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        let (intra, inter) = value.split_once(',').unwrap_or((value, "1"));
+        let parse = |count: &str| count.trim().parse::<usize>().ok().filter(|n| *n >= 1);
+        Some(Self {
+            intra: parse(intra)?,
+            inter: parse(inter)?,
+        })
+    }
+
+    pub fn apply(&self, builder: SessionBuilder) -> ort::Result<SessionBuilder> {
+        builder
+            .with_intra_threads(self.intra.max(1))?
+            .with_inter_threads(self.inter.max(1))
+    }
+}
+
+impl Default for SessionThreads {
+    fn default() -> Self {
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2);
+        Self {
+            intra: (cores / 2).clamp(1, 4),
+            inter: 1,
+        }
+    }
+}
+
+/// Where an ONNX model is loaded from.
+#[derive(Clone, Debug)]
+pub enum ModelSource {
+    File(PathBuf),
+    /// Bytes compiled into the binary by the `embed-models` feature.
+    Embedded(&'static [u8]),
+}
+
+impl ModelSource {
+    pub fn commit(&self, builder: SessionBuilder) -> ort::Result<Session> {
+        match self {
+            ModelSource::File(path) => {
+                let bytes = cached_model_bytes(path).map_err(|err| {
+                    ort::Error::new(format!("failed to read {}: {err}", path.display()))
+                })?;
+                builder.commit_from_memory(&bytes)
+            }
+            ModelSource::Embedded(bytes) => builder.commit_from_memory(bytes),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ModelSource::File(path) => path.display().to_string(),
+            ModelSource::Embedded(bytes) => format!("embedded model ({} bytes)", bytes.len()),
+        }
+    }
+}
+
+/// A model file's contents as last read, with the metadata used to notice
+/// when the file has been replaced since.
+struct CachedModel {
+    modified: Option<SystemTime>,
+    len: u64,
+    bytes: Weak<[u8]>,
+}
+
+/// Process-wide cache of model file contents, keyed by canonical path, so
+/// sessions built at the same time from the same model share one read.
+///
+/// Entries are weak: ORT copies the model while committing a session, so
+/// the bytes are dropped as soon as no build still needs them instead of
+/// staying resident next to ORT's copy for the life of the process.
+///
+/// Only the bytes are shared, never a `Session`: in ort 2.0 `Session::run`
+/// takes `&mut self`, so a shared session would serialize every inference
+/// behind a lock, and each session is built with its own execution
+/// providers and thread counts. Building from memory also means a model
+/// must be self-contained, without external `.onnx_data` files, which holds
+/// for all three bundled models.
+static MODEL_CACHE: LazyLock<Mutex<HashMap<PathBuf, CachedModel>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Reads `path` through [`MODEL_CACHE`], rereading it when the cached bytes
+/// were dropped or its size or modification time changed, e.g. after a
+/// re-download. The lock is held while reading so concurrent loads of the
+/// same model read it once; it is never held across anything that could
+/// take it again.
+fn cached_model_bytes(path: &Path) -> std::io::Result<Arc<[u8]>> {
+    let key = fs::canonicalize(path)?;
+    let metadata = fs::metadata(&key)?;
+    let modified = metadata.modified().ok();
+
+    let mut cache = MODEL_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(bytes) = cache
+        .get(&key)
+        .filter(|cached| cached.len == metadata.len() && cached.modified == modified)
+        .and_then(|cached| cached.bytes.upgrade())
+    {
+        return Ok(bytes);
+    }
+
+    let bytes: Arc<[u8]> = fs::read(&key)?.into();
+    cache.insert(
+        key,
+        CachedModel {
+            modified,
+            len: metadata.len(),
+            bytes: Arc::downgrade(&bytes),
+        },
+    );
+    Ok(bytes)
+}
+
+#[cfg(feature = "embed-models")]
+mod embedded {
+    pub const HANDPOSE_ESTIMATOR: &[u8] = include_bytes!("../models/handpose_estimation.onnx");
+    pub const PALM_DETECTOR: &[u8] = include_bytes!("../models/palm_detection.onnx");
+    pub const GESTURE_CLASSIFIER: &[u8] = include_bytes!("../models/gesture_mlp.onnx");
+}
+
+/// The copy of `model` compiled into the binary, if built with `embed-models`.
+pub fn embedded_model(model: ModelKind) -> Option<&'static [u8]> {
+    #[cfg(feature = "embed-models")]
+    {
+        Some(match model {
+            ModelKind::HandposeEstimator => embedded::HANDPOSE_ESTIMATOR,
+            ModelKind::PalmDetector => embedded::PALM_DETECTOR,
+            ModelKind::GestureClassifier => embedded::GESTURE_CLASSIFIER,
+        })
+    }
+    #[cfg(not(feature = "embed-models"))]
+    {
+        let _ = model;
+        None
+    }
+}
+
+/// Resolves where to load `model` from. With `prefer_embedded` and an embedded
+/// copy available, the file and network are never touched; otherwise the file
+/// at `model_path` is downloaded if missing.
+pub fn prepare_model_source<F>(
+    model: ModelKind,
+    model_path: &Path,
+    prefer_embedded: bool,
+    mut on_event: F,
+) -> anyhow::Result<ModelSource>
+where
+    F: FnMut(ModelDownloadEvent),
+{
+    if let Some(bytes) = embedded_model(model).filter(|_| prefer_embedded) {
+        on_event(ModelDownloadEvent::AlreadyPresent { model });
+        on_event(ModelDownloadEvent::Finished { model });
+        return Ok(ModelSource::Embedded(bytes));
+    }
+
+    match model {
+        ModelKind::HandposeEstimator => {
+            ensure_handpose_estimator_model_ready(model_path, on_event)?
+        }
+        ModelKind::PalmDetector => ensure_palm_detector_model_ready(model_path, on_event)?,
+        ModelKind::GestureClassifier => {
+            ensure_gesture_classifier_model_ready(model_path, on_event)?
+        }
+    }
+    Ok(ModelSource::File(model_path.to_path_buf()))
+}
+
 #[derive(Clone, Debug)]
 pub enum ModelDownloadEvent {
     AlreadyPresent {
@@ -79,7 +301,7 @@ where
     let mut progress: Option<ProgressBar> = None;
     download_to_path(
         ModelKind::HandposeEstimator,
-        HANDPOSE_ESTIMATOR_MODEL_URL,
+        HANDPOSE_ESTIMATOR_MODEL_URLS,
         model_path,
         &mut |event| {
             match &event {
@@ -103,7 +325,52 @@ where
     )
 }
 
+/// Downloads `model` from the first of `urls` that succeeds. A mirror that
+/// fails part-way restarts from zero on the next one, which the UI sees as a
+/// fresh `Started` event.
 fn download_to_path<F>(
+    model: ModelKind,
+    urls: &[&str],
+    dest: &Path,
+    on_event: &mut F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(ModelDownloadEvent),
+{
+    let url = try_mirrors(urls, |url| {
+        download_from_url(model, url, dest, on_event).inspect_err(|_| {
+            let _ = fs::remove_file(dest.with_extension("download"));
+        })
+    })?;
+    log::info!("downloaded {model:?} model from {url}");
+    on_event(ModelDownloadEvent::Finished { model });
+    Ok(())
+}
+
+/// Calls `attempt` on each URL in order until one succeeds, returning that
+/// URL. The error lists every mirror's failure.
+fn try_mirrors<'a>(
+    urls: &[&'a str],
+    mut attempt: impl FnMut(&str) -> anyhow::Result<()>,
+) -> anyhow::Result<&'a str> {
+    let mut failures = Vec::new();
+    for url in urls {
+        match attempt(url) {
+            Ok(()) => return Ok(url),
+            Err(err) => {
+                log::warn!("model download from {url} failed: {err:#}");
+                failures.push(format!("{url}: {err:#}"));
+            }
+        }
+    }
+    Err(anyhow::anyhow!(
+        "all {} download mirrors failed:\n{}",
+        urls.len(),
+        failures.join("\n")
+    ))
+}
+
+fn download_from_url<F>(
     model: ModelKind,
     url: &str,
     dest: &Path,
@@ -169,8 +436,6 @@ where
             dest.display()
         )
     })?;
-
-    on_event(ModelDownloadEvent::Finished { model });
     Ok(())
 }
 
@@ -215,13 +480,10 @@ where
         return Ok(());
     }
 
-    log::info!(
-        "bundled palm detector not found, downloading from {}",
-        PALM_DETECTOR_MODEL_URL
-    );
+    log::info!("bundled palm detector not found, downloading");
     download_to_path(
         ModelKind::PalmDetector,
-        PALM_DETECTOR_MODEL_URL,
+        PALM_DETECTOR_MODEL_URLS,
         model_path,
         &mut on_event,
     )
@@ -262,7 +524,7 @@ where
     let mut progress: Option<ProgressBar> = None;
     download_to_path(
         ModelKind::GestureClassifier,
-        GESTURE_CLASSIFIER_MODEL_URL,
+        GESTURE_CLASSIFIER_MODEL_URLS,
         model_path,
         &mut |event| {
             match &event {
@@ -307,3 +569,77 @@ fn create_progress_bar(total_size: Option<u64>) -> ProgressBar {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_through_to_the_next_mirror() {
+        let mut tried = Vec::new();
+        let used = try_mirrors(&["https://primary", "https://mirror"], |url| {
+            tried.push(url.to_string());
+            if url == "https://primary" {
+                anyhow::bail!("rate limited")
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(used, "https://mirror");
+        assert_eq!(tried, ["https://primary", "https://mirror"]);
+    }
+
+    #[test]
+    fn stops_at_the_first_working_mirror() {
+        let mut tried = 0;
+        let used = try_mirrors(&["https://primary", "https://mirror"], |_| {
+            tried += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(used, "https://primary");
+        assert_eq!(tried, 1);
+    }
+
+    #[test]
+    fn reports_every_failed_mirror() {
+        let err = try_mirrors(&["https://primary", "https://mirror"], |url| {
+            anyhow::bail!("{url} is down")
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("all 2 download mirrors failed"), "{err}");
+        assert!(err.contains("https://primary is down") && err.contains("https://mirror is down"));
+    }
+
+    #[test]
+    fn session_threads_parse_from_the_flag() {
+        assert_eq!(
+            SessionThreads::from_flag("4"),
+            Some(SessionThreads { intra: 4, inter: 1 })
+        );
+        assert_eq!(
+            SessionThreads::from_flag("2,2"),
+            Some(SessionThreads { intra: 2, inter: 2 })
+        );
+        assert_eq!(SessionThreads::from_flag("0"), None);
+        assert_eq!(SessionThreads::from_flag("2,"), None);
+        assert_eq!(SessionThreads::from_flag("many"), None);
+    }
+
+    #[test]
+    fn cached_bytes_are_shared_only_while_held() {
+        let path = std::env::temp_dir().join(format!("model-cache-{}.onnx", std::process::id()));
+        fs::write(&path, b"model").unwrap();
+
+        let first = cached_model_bytes(&path).unwrap();
+        let second = cached_model_bytes(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let weak = Arc::downgrade(&first);
+        drop((first, second));
+        assert!(weak.upgrade().is_none());
+        assert_eq!(&*cached_model_bytes(&path).unwrap(), b"model");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fs, path::PathBuf, thread};
+
+use crossbeam_channel::{Sender, unbounded};
+
+use crate::{
+    gesture_events::{OutputFilter, OutputMode},
+    types::GestureKind,
+};
+
+/// Writes one OpenPose JSON file per frame with a hand into a directory,
+/// named `<sequence>_keypoints.json` like OpenPose's own `--write_json`
+/// output. Files are written on a background thread so a slow disk never
+/// stalls the UI.
+pub struct OpenPoseOutput {
+    tx: Sender<(u64, String)>,
+    filter: OutputFilter,
+}
+
+impl OpenPoseOutput {
+    pub fn spawn(dir: PathBuf, mode: OutputMode) -> Self {
+        let (tx, rx) = unbounded::<(u64, String)>();
+        thread::spawn(move || {
+            if let Err(err) = fs::create_dir_all(&dir) {
+                log::warn!("failed to create {}: {err}", dir.display());
+                return;
+            }
+            for (sequence, json) in rx {
+                let path = dir.join(format!("{sequence:012}_keypoints.json"));
+                if let Err(err) = fs::write(&path, json) {
+                    log::warn!("failed to write {}: {err}", path.display());
+                }
+            }
+        });
+        Self {
+            tx,
+            filter: OutputFilter::new(mode),
+        }
+    }
+
+    /// Whether the result with this gesture should be sent under the
+    /// configured [`OutputMode`]; call once per result.
+    pub fn accept(&mut self, gesture: Option<GestureKind>) -> bool {
+        self.filter.accept(gesture)
+    }
+
+    pub fn send(&self, sequence: u64, json: String) {
+        let _ = self.tx.send((sequence, json));
+    }
+}
@@ -0,0 +1,134 @@
+use std::thread;
+
+use crossbeam_channel::Receiver;
+
+use super::{
+    CompositedFrame, FrameSender, RecognizerBackend, RenderActivity, bounded_channel,
+    channel::DropCounter, start_frame_compositor, start_recognizer,
+};
+use crate::types::{Frame, RecognizedFrame};
+
+/// A running recognizer → compositor chain.
+///
+/// Cameras (or any other source) push into [`Pipeline::frame_tx`], e.g. via
+/// [`super::start_camera_stream`], so they can be switched without
+/// rebuilding the chain. The worker threads exit once their input channels
+/// disconnect.
+pub struct Pipeline {
+    /// Feeds frames to the recognizer.
+    pub frame_tx: FrameSender<Frame>,
+    /// Recognized frames with overlays drawn, ready for display.
+    pub composited_rx: Receiver<CompositedFrame>,
+    _compositor_handle: thread::JoinHandle<()>,
+    recognizer_handle: Option<thread::JoinHandle<()>>,
+    pending_recognizer: Option<PendingRecognizer>,
+}
+
+struct PendingRecognizer {
+    backend: RecognizerBackend,
+    frame_rx: Receiver<Frame>,
+    recognized_tx: FrameSender<RecognizedFrame>,
+}
+
+impl Pipeline {
+    pub fn builder() -> PipelineBuilder {
+        PipelineBuilder::default()
+    }
+
+    /// Starts a recognizer held back by [`PipelineBuilder::defer_recognizer`].
+    /// Does nothing once it is running.
+    pub fn start_recognizer(&mut self) {
+        if let Some(pending) = self.pending_recognizer.take() {
+            self.recognizer_handle = Some(start_recognizer(
+                pending.backend,
+                pending.frame_rx,
+                pending.recognized_tx,
+            ));
+        }
+    }
+
+    pub fn recognizer_running(&self) -> bool {
+        self.recognizer_handle.is_some()
+    }
+}
+
+/// Wires the bounded channels between the stages; the app builds its
+/// pipeline through this too. Models must already be available (see
+/// `model_download`) when the recognizer starts, since it loads them then.
+#[derive(Default)]
+pub struct PipelineBuilder {
+    backend: RecognizerBackend,
+    render_activity: Option<RenderActivity>,
+    drops: DropCounter,
+    defer_recognizer: bool,
+}
+
+impl PipelineBuilder {
+    pub fn with_backend(mut self, backend: RecognizerBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Lets the compositor skip overlays while nothing marks a render.
+    pub fn with_render_activity(mut self, activity: RenderActivity) -> Self {
+        self.render_activity = Some(activity);
+        self
+    }
+
+    /// Counts composited frames dropped because the consumer hadn't taken
+    /// the previous one.
+    pub fn with_drop_counter(mut self, drops: DropCounter) -> Self {
+        self.drops = drops;
+        self
+    }
+
+    /// Leaves the recognizer stopped until [`Pipeline::start_recognizer`],
+    /// e.g. while the models are still downloading.
+    pub fn defer_recognizer(mut self) -> Self {
+        self.defer_recognizer = true;
+        self
+    }
+
+    pub fn build(self) -> Pipeline {
+        let backend = self.backend;
+        let (frame_tx, frame_rx) = bounded_channel(backend.frame_channel());
+        let (recognized_tx, recognized_rx) = bounded_channel(backend.result_channel());
+        let (composited_rx, compositor_handle) = start_frame_compositor(
+            recognized_rx,
+            backend.crop(),
+            backend.skeleton_hold(),
+            self.render_activity,
+            self.drops,
+        );
+
+        let mut pipeline = Pipeline {
+            frame_tx,
+            composited_rx,
+            _compositor_handle: compositor_handle,
+            recognizer_handle: None,
+            pending_recognizer: Some(PendingRecognizer {
+                backend,
+                frame_rx,
+                recognized_tx,
+            }),
+        };
+        if !self.defer_recognizer {
+            pipeline.start_recognizer();
+        }
+        pipeline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deferred_recognizer_waits_for_start() {
+        let pipeline = Pipeline::builder().defer_recognizer().build();
+
+        assert!(!pipeline.recognizer_running());
+        assert!(pipeline.pending_recognizer.is_some());
+        assert!(pipeline.composited_rx.try_recv().is_err());
+    }
+}
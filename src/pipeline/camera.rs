@@ -1,24 +1,29 @@
 use std::{
+    collections::HashMap,
     sync::{
         Arc,
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
     },
     thread,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Result, anyhow};
-use crossbeam_channel::Sender;
 use nokhwa::{
     Camera,
     pixel_format::RgbFormat,
     query,
     utils::{
-        ApiBackend, CameraIndex, CameraInfo, FrameFormat, RequestedFormat, RequestedFormatType,
+        ApiBackend, CameraFormat, CameraIndex, CameraInfo, FrameFormat, RequestedFormat,
+        RequestedFormatType,
     },
 };
 
-use super::rgba_converter;
+use super::{
+    channel::FrameSender,
+    recognizer::HandActivity,
+    rgba_converter::{self, JpegDecodeScale, RgbaFrame},
+};
 use crate::types::Frame;
 
 // Prefer pixel formats that are widely supported on macOS (the built-in cameras
@@ -32,6 +37,188 @@ const PREFERRED_PIXEL_FORMATS: &[FrameFormat] = &[
     FrameFormat::MJPEG,
 ];
 
+/// Next capture sequence number. Process-wide so numbers stay unique and
+/// increasing when the camera is switched or restarted.
+static NEXT_FRAME_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+/// Wraps a decoded camera frame for the pipeline, or `None` when it has to
+/// be skipped: a buffer that disagrees with the frame size, which drivers
+/// can deliver mid-stream long after the first frame checked out, or a
+/// degenerate size. Accepted frames get the next capture sequence number,
+/// so skipped ones leave no gaps.
+fn accept_frame(converted: RgbaFrame) -> Option<Frame> {
+    let (width, height, len) = (converted.width, converted.height, converted.rgba.len());
+    let Some(mut frame) = Frame::from_rgba(width, height, converted.rgba) else {
+        log::warn!("skipping camera frame of {len} bytes, expected {width}x{height} RGBA");
+        return None;
+    };
+    if frame.is_too_small() {
+        log::warn!("skipping degenerate camera frame {width}x{height}");
+        return None;
+    }
+    frame.sequence = NEXT_FRAME_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    Some(frame)
+}
+
+/// Frames read and thrown away after opening a camera, while auto-exposure
+/// settles; many webcams deliver black or badly exposed frames at first.
+pub const DEFAULT_WARMUP_FRAMES: usize = 3;
+
+/// Per-stream capture settings.
+#[derive(Clone, Copy, Debug)]
+pub struct CaptureConfig {
+    /// Frames discarded after opening; see [`DEFAULT_WARMUP_FRAMES`].
+    pub warmup_frames: usize,
+    /// Reduced output size for MJPEG streams. Other formats always arrive
+    /// at full resolution.
+    pub mjpeg_scale: JpegDecodeScale,
+    /// Cheaper format to switch to while no hand is in view. `None` keeps
+    /// the negotiated format for the whole stream.
+    pub idle: Option<IdleCapture>,
+    /// Time without a successfully read frame after which the camera is
+    /// reported unresponsive and reopened. `None` disables the watchdog.
+    pub stall_timeout: Option<Duration>,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            warmup_frames: DEFAULT_WARMUP_FRAMES,
+            mjpeg_scale: JpegDecodeScale::Full,
+            idle: None,
+            stall_timeout: Some(DEFAULT_STALL_TIMEOUT),
+        }
+    }
+}
+
+/// Default for [`CaptureConfig::stall_timeout`]; well above the frame
+/// interval of even a 5 fps camera in a dark room.
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Age of the camera's last successfully read frame, shared with the UI.
+///
+/// Drivers sometimes stop delivering frames without reporting an error, or
+/// block inside `Camera::frame` indefinitely. The capture thread reopens the
+/// camera when a read returns after the stall timeout, but a read blocked in
+/// the driver cannot be interrupted; this handle lets the UI report both
+/// cases either way.
+#[derive(Clone, Debug)]
+pub struct CameraHealth {
+    inner: Arc<HealthShared>,
+}
+
+#[derive(Debug)]
+struct HealthShared {
+    epoch: Instant,
+    last_frame_ms: AtomicU64,
+    timeout: Option<Duration>,
+}
+
+impl CameraHealth {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            inner: Arc::new(HealthShared {
+                epoch: Instant::now(),
+                last_frame_ms: AtomicU64::new(0),
+                timeout,
+            }),
+        }
+    }
+
+    fn mark_alive(&self) {
+        let now = self.inner.epoch.elapsed().as_millis() as u64;
+        self.inner.last_frame_ms.store(now, Ordering::Relaxed);
+    }
+
+    fn stalled_for(&self) -> Duration {
+        let last = Duration::from_millis(self.inner.last_frame_ms.load(Ordering::Relaxed));
+        self.inner.epoch.elapsed().saturating_sub(last)
+    }
+
+    /// False once no frame has been read for longer than the stall timeout.
+    pub fn responsive(&self) -> bool {
+        self.inner
+            .timeout
+            .is_none_or(|timeout| self.stalled_for() < timeout)
+    }
+}
+
+/// Low-power capture format used while the recognizer sees no hand.
+///
+/// Switching means stopping the stream and negotiating a new format, which
+/// takes a noticeable moment on most drivers, so both directions are
+/// debounced: the camera only goes idle after `idle_after` without a hand,
+/// and never switches more often than `min_switch_interval`.
+#[derive(Clone, Copy, Debug)]
+pub struct IdleCapture {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub idle_after: Duration,
+    pub min_switch_interval: Duration,
+}
+
+impl Default for IdleCapture {
+    fn default() -> Self {
+        Self {
+            width: 640,
+            height: 480,
+            fps: 15,
+            idle_after: Duration::from_secs(5),
+            min_switch_interval: Duration::from_secs(3),
+        }
+    }
+}
+
+/// Debounced decision of when to enter and leave the idle format.
+struct IdleSwitcher {
+    config: IdleCapture,
+    idle: bool,
+    last_hand: Instant,
+    last_switch: Instant,
+}
+
+impl IdleSwitcher {
+    fn new(config: IdleCapture) -> Self {
+        let now = Instant::now();
+        Self {
+            config,
+            idle: false,
+            last_hand: now,
+            last_switch: now,
+        }
+    }
+
+    /// The mode to switch to now, if any.
+    fn poll(&mut self, hand_present: bool, now: Instant) -> Option<bool> {
+        if hand_present {
+            self.last_hand = now;
+        }
+        let want_idle = now.duration_since(self.last_hand) >= self.config.idle_after;
+        let settled = now.duration_since(self.last_switch) >= self.config.min_switch_interval;
+        (want_idle != self.idle && settled).then_some(want_idle)
+    }
+
+    fn switched(&mut self, idle: bool, now: Instant) {
+        self.idle = idle;
+        self.last_switch = now;
+    }
+}
+
+impl IdleCapture {
+    fn requested_format(&self) -> RequestedFormat<'static> {
+        RequestedFormat::with_formats(
+            RequestedFormatType::Closest(CameraFormat::new_from(
+                self.width,
+                self.height,
+                FrameFormat::MJPEG,
+                self.fps,
+            )),
+            PREFERRED_PIXEL_FORMATS,
+        )
+    }
+}
+
 fn requested_formats() -> [RequestedFormat<'static>; 4] {
     [
         RequestedFormat::with_formats(
@@ -59,9 +246,14 @@ pub struct CameraDevice {
 pub struct CameraStream {
     stop: Arc<AtomicBool>,
     handle: Option<thread::JoinHandle<()>>,
+    health: CameraHealth,
 }
 
 impl CameraStream {
+    pub fn health(&self) -> CameraHealth {
+        self.health.clone()
+    }
+
     pub fn stop(mut self) {
         self.stop.store(true, Ordering::SeqCst);
         if let Some(handle) = self.handle.take() {
@@ -81,26 +273,70 @@ impl Drop for CameraStream {
 
 pub fn available_cameras() -> Result<Vec<CameraDevice>> {
     let cameras = query(ApiBackend::Auto)?;
-    Ok(cameras
+    let mut devices: Vec<CameraDevice> = cameras
         .into_iter()
         .map(|info| CameraDevice {
             index: info.index().clone(),
             label: format_camera_label(&info),
         })
-        .collect())
+        .collect();
+    disambiguate_labels(&mut devices);
+    Ok(devices)
+}
+
+/// Numbers cameras that share a label in enumeration order, e.g. two
+/// identical webcams become "USB Camera #1" and "USB Camera #2", so the
+/// picker and the remembered camera can tell them apart. Unique labels are
+/// left alone.
+fn disambiguate_labels(devices: &mut [CameraDevice]) {
+    let mut totals: HashMap<String, usize> = HashMap::new();
+    for device in devices.iter() {
+        *totals.entry(device.label.clone()).or_default() += 1;
+    }
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for device in devices.iter_mut() {
+        if totals[&device.label] < 2 {
+            continue;
+        }
+        let ordinal = seen.entry(device.label.clone()).or_default();
+        *ordinal += 1;
+        device.label = format!("{} #{ordinal}", device.label);
+    }
+}
+
+/// Finds a camera by its index (e.g. `0`) or a case-insensitive substring of
+/// its label, in that order.
+pub fn find_camera(cameras: &[CameraDevice], query: &str) -> Option<usize> {
+    let query = query.trim();
+    let by_index = cameras.iter().position(|camera| match &camera.index {
+        CameraIndex::Index(index) => query.parse::<u32>().is_ok_and(|wanted| wanted == *index),
+        CameraIndex::String(id) => id == query,
+    });
+    by_index.or_else(|| {
+        let needle = query.to_lowercase();
+        cameras
+            .iter()
+            .position(|camera| camera.label.to_lowercase().contains(&needle))
+    })
 }
 
 fn format_camera_label(info: &CameraInfo) -> String {
     info.human_name()
 }
 
-fn build_camera(index: CameraIndex) -> Result<Camera> {
+/// Opens `index` with the first of [`requested_formats`] from `first_attempt`
+/// on that works, returning the camera and the attempt that succeeded.
+fn build_camera(index: CameraIndex, first_attempt: usize) -> Result<(Camera, usize)> {
     let mut last_err = None;
 
-    for requested in requested_formats() {
+    for (attempt, requested) in requested_formats()
+        .into_iter()
+        .enumerate()
+        .skip(first_attempt)
+    {
         match Camera::new(index.clone(), requested) {
             Ok(mut camera) => match camera.open_stream() {
-                Ok(()) => return Ok(camera),
+                Ok(()) => return Ok((camera, attempt)),
                 Err(err) => last_err = Some(err.into()),
             },
             Err(err) => last_err = Some(err.into()),
@@ -110,26 +346,116 @@ fn build_camera(index: CameraIndex) -> Result<Camera> {
     Err(last_err.unwrap_or_else(|| anyhow!("failed to open camera with any supported format")))
 }
 
-pub fn start_camera_stream(index: CameraIndex, frame_tx: Sender<Frame>) -> Result<CameraStream> {
+/// Captures from `index` on a background thread, discarding the first
+/// `config.warmup_frames` frames before anything is sent.
+///
+/// With `config.stall_timeout` set, a camera that stops delivering frames
+/// is reopened; see [`CameraHealth`].
+///
+/// With `config.idle` set, the stream drops to the idle format once
+/// `activity` has reported no hand for a while and returns to the full format
+/// when one shows up again.
+pub fn start_camera_stream(
+    index: CameraIndex,
+    frame_tx: FrameSender<Frame>,
+    config: CaptureConfig,
+    activity: HandActivity,
+) -> Result<CameraStream> {
     // Fail fast before spawning the capture thread.
-    build_camera(index.clone())?;
+    build_camera(index.clone(), 0)?;
 
     let stop = Arc::new(AtomicBool::new(false));
     let stop_flag = stop.clone();
+    let health = CameraHealth::new(config.stall_timeout);
+    let capture_health = health.clone();
 
     let handle = thread::spawn(move || {
-        let mut camera = match build_camera(index) {
-            Ok(cam) => cam,
+        let (mut camera, mut attempt) = match build_camera(index.clone(), 0) {
+            Ok(opened) => opened,
             Err(err) => {
                 log::error!("failed to open camera: {err:?}");
                 return;
             }
         };
+        // Some drivers report one format and deliver another; the first frame
+        // shows whether the decoded buffer really matches the dimensions.
+        let mut format_checked = false;
+        let mut warmup_remaining = config.warmup_frames;
+        let mut idle_switcher = config.idle.map(IdleSwitcher::new);
+        capture_health.mark_alive();
+        let mut last_recovery = Instant::now();
 
         while !stop_flag.load(Ordering::Relaxed) {
             let frame_start = Instant::now();
+            if let Some(timeout) = config.stall_timeout
+                && capture_health.stalled_for() >= timeout
+                && last_recovery.elapsed() >= timeout
+            {
+                log::warn!(
+                    "camera delivered no frame for {:?}, reopening",
+                    capture_health.stalled_for()
+                );
+                last_recovery = frame_start;
+                let _ = camera.stop_stream();
+                match build_camera(index.clone(), attempt) {
+                    Ok((next, next_attempt)) => {
+                        camera = next;
+                        attempt = next_attempt;
+                        format_checked = false;
+                        warmup_remaining = config.warmup_frames;
+                    }
+                    // Retried after another timeout; the old camera's reads
+                    // keep failing meanwhile.
+                    Err(err) => log::warn!("failed to reopen stalled camera: {err:?}"),
+                }
+                continue;
+            }
+
+            let switch_to = idle_switcher
+                .as_mut()
+                .and_then(|switcher| switcher.poll(activity.hand_present(), frame_start));
+            if let Some(idle) = switch_to {
+                let _ = camera.stop_stream();
+                let reopened = match config.idle.filter(|_| idle) {
+                    Some(idle_config) => Camera::new(index.clone(), idle_config.requested_format())
+                        .and_then(|mut camera| camera.open_stream().map(|()| camera))
+                        .map(|camera| (camera, attempt))
+                        .or_else(|err| {
+                            // The camera can't do the idle format; stay on the
+                            // full one rather than retrying on every switch.
+                            log::warn!("failed to switch camera to idle format: {err:?}");
+                            idle_switcher = None;
+                            build_camera(index.clone(), 0)
+                        }),
+                    None => build_camera(index.clone(), 0),
+                };
+                match reopened {
+                    Ok((next, next_attempt)) => {
+                        camera = next;
+                        attempt = next_attempt;
+                        if let Some(switcher) = idle_switcher.as_mut() {
+                            switcher.switched(idle, frame_start);
+                            log::info!(
+                                "camera switched to {} format",
+                                if idle { "idle" } else { "active" }
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        log::error!("failed to reopen camera: {err:?}");
+                        return;
+                    }
+                }
+                format_checked = false;
+                warmup_remaining = config.warmup_frames;
+                continue;
+            }
+
             let frame = match camera.frame() {
-                Ok(frame) => frame,
+                Ok(frame) => {
+                    capture_health.mark_alive();
+                    frame
+                }
                 Err(err) => {
                     log::warn!(
                         "camera frame read failed (after {:?}): {err:?}",
@@ -139,7 +465,12 @@ pub fn start_camera_stream(index: CameraIndex, frame_tx: Sender<Frame>) -> Resul
                 }
             };
 
-            let converted = match rgba_converter::convert_camera_frame(&frame) {
+            if warmup_remaining > 0 {
+                warmup_remaining -= 1;
+                continue;
+            }
+
+            let converted = match rgba_converter::convert_camera_frame(&frame, config.mjpeg_scale) {
                 Ok(rgba) => rgba,
                 Err(err) => {
                     log::warn!("failed to decode camera frame {err:?}");
@@ -147,21 +478,130 @@ pub fn start_camera_stream(index: CameraIndex, frame_tx: Sender<Frame>) -> Resul
                 }
             };
 
-            let frame_timestamp = Instant::now();
-            let frame = Frame {
-                rgba: converted.rgba,
-                width: converted.width,
-                height: converted.height,
-                timestamp: frame_timestamp,
+            if !format_checked {
+                let expected = converted.width as usize * converted.height as usize * 4;
+                if converted.rgba.len() != expected {
+                    log::warn!(
+                        "camera format {:?} decoded to {} bytes, expected {expected} for {}x{}; trying the next format",
+                        frame.source_frame_format(),
+                        converted.rgba.len(),
+                        converted.width,
+                        converted.height
+                    );
+                    let _ = camera.stop_stream();
+                    let next_attempt = if idle_switcher.as_ref().is_some_and(|s| s.idle) {
+                        // The idle format is what decodes badly; go back to
+                        // the full format that worked and stop switching.
+                        idle_switcher = None;
+                        attempt
+                    } else {
+                        attempt + 1
+                    };
+                    match build_camera(index.clone(), next_attempt) {
+                        Ok((next, next_attempt)) => {
+                            camera = next;
+                            attempt = next_attempt;
+                        }
+                        Err(err) => {
+                            log::error!("no camera format produced a usable frame: {err:?}");
+                            return;
+                        }
+                    }
+                    continue;
+                }
+                log::info!(
+                    "camera streaming {:?} at {}x{}",
+                    frame.source_frame_format(),
+                    converted.width,
+                    converted.height
+                );
+                format_checked = true;
+            }
+
+            let Some(frame) = accept_frame(converted) else {
+                continue;
             };
 
-            // Drop if the worker is busy, otherwise forward every frame.
-            let _ = frame_tx.try_send(frame);
+            // Overflow handling (drop or block) is decided by the channel config.
+            frame_tx.send(frame);
         }
     });
 
     Ok(CameraStream {
         stop,
         handle: Some(handle),
+        health,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MIN_FRAME_SIDE;
+
+    fn converted(width: u32, height: u32, len: usize) -> RgbaFrame {
+        RgbaFrame {
+            rgba: vec![128; len],
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn a_short_buffer_is_skipped() {
+        assert!(accept_frame(converted(640, 480, 640 * 480 * 4 - 1)).is_none());
+        assert!(accept_frame(converted(640, 480, 0)).is_none());
+    }
+
+    #[test]
+    fn a_too_small_frame_is_skipped() {
+        let side = MIN_FRAME_SIDE - 1;
+        let len = side as usize * side as usize * 4;
+        assert!(accept_frame(converted(side, side, len)).is_none());
+    }
+
+    #[test]
+    fn accepted_frames_are_numbered_in_order() {
+        let first = accept_frame(converted(64, 48, 64 * 48 * 4)).unwrap();
+        let second = accept_frame(converted(64, 48, 64 * 48 * 4)).unwrap();
+        assert!(second.sequence > first.sequence);
+        assert_eq!((first.width, first.height), (64, 48));
+    }
+
+    fn devices(labels: &[&str]) -> Vec<CameraDevice> {
+        labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| CameraDevice {
+                index: CameraIndex::Index(index as u32),
+                label: label.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_labels_are_numbered_in_order() {
+        let mut cameras = devices(&["USB Camera", "FaceTime HD", "USB Camera", "USB Camera"]);
+        disambiguate_labels(&mut cameras);
+        let labels: Vec<&str> = cameras.iter().map(|camera| camera.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "USB Camera #1",
+                "FaceTime HD",
+                "USB Camera #2",
+                "USB Camera #3"
+            ]
+        );
+        // Each numbered label now finds exactly its own camera.
+        assert_eq!(find_camera(&cameras, "usb camera #2"), Some(2));
+    }
+
+    #[test]
+    fn unique_labels_are_left_alone() {
+        let mut cameras = devices(&["Left", "Right"]);
+        disambiguate_labels(&mut cameras);
+        assert_eq!(cameras[0].label, "Left");
+        assert_eq!(cameras[1].label, "Right");
+    }
+}
@@ -0,0 +1,204 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError, TrySendError};
+
+/// What a producer does when the consumer has not caught up and the channel
+/// is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the frame being sent and keep what is already queued.
+    #[default]
+    DropNewest,
+    /// Evict the oldest queued frame to make room, so the consumer always
+    /// sees the most recent data.
+    DropOldest,
+    /// Wait until the consumer frees a slot. Nothing is lost, but a slow
+    /// consumer stalls the producer: camera capture and recognition fall
+    /// behind real time and end-to-end latency grows by up to `capacity`
+    /// frames per blocking stage.
+    Block,
+}
+
+impl OverflowPolicy {
+    /// Parses the command-line spelling: `drop-newest`, `drop-oldest` or
+    /// `block`.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        match value {
+            "drop-newest" => Some(OverflowPolicy::DropNewest),
+            "drop-oldest" => Some(OverflowPolicy::DropOldest),
+            "block" => Some(OverflowPolicy::Block),
+            _ => None,
+        }
+    }
+}
+
+/// Capacity and overflow behaviour of a pipeline stage's output channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChannelConfig {
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl ChannelConfig {
+    /// Single-slot channel that favours freshness over completeness, which is
+    /// what the live preview wants.
+    pub const fn latest_only() -> Self {
+        Self {
+            capacity: 1,
+            overflow: OverflowPolicy::DropNewest,
+        }
+    }
+
+    /// Parses `<capacity>:<policy>` as given on the command line, e.g.
+    /// `8:block`; see [`OverflowPolicy::from_flag`] for the policies.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        let (capacity, overflow) = value.split_once(':')?;
+        Some(Self {
+            capacity: capacity.parse().ok().filter(|capacity| *capacity >= 1)?,
+            overflow: OverflowPolicy::from_flag(overflow)?,
+        })
+    }
+
+    pub fn is_lossless(&self) -> bool {
+        self.overflow == OverflowPolicy::Block
+    }
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self::latest_only()
+    }
+}
+
+/// Running count of frames a stage discarded because the next one was
+/// still busy, shared with whoever wants to show it.
+#[derive(Clone, Debug, Default)]
+pub struct DropCounter {
+    count: Arc<AtomicU64>,
+}
+
+impl DropCounter {
+    pub fn record(&self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+/// Sending half of a [`bounded_channel`] that applies its overflow policy.
+#[derive(Clone, Debug)]
+pub struct FrameSender<T> {
+    tx: Sender<T>,
+    // Only held for `DropOldest`, which evicts from the producer side. Holding
+    // it otherwise would keep a blocked sender waiting on a dead consumer.
+    evict_rx: Option<Receiver<T>>,
+    overflow: OverflowPolicy,
+    drops: DropCounter,
+}
+
+impl<T> FrameSender<T> {
+    /// Frames lost to a full channel: the rejected ones under `DropNewest`,
+    /// the evicted ones under `DropOldest`. Always zero under `Block`.
+    pub fn drops(&self) -> DropCounter {
+        self.drops.clone()
+    }
+
+    /// Sends according to the overflow policy. Returns `false` once the
+    /// consumer has gone away (never reported under `DropOldest`, which keeps
+    /// its own receiver handle).
+    pub fn send(&self, value: T) -> bool {
+        match self.overflow {
+            OverflowPolicy::Block => self.tx.send(value).is_ok(),
+            OverflowPolicy::DropNewest => match self.tx.try_send(value) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.drops.record();
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+            OverflowPolicy::DropOldest => {
+                let mut value = value;
+                loop {
+                    match self.tx.try_send(value) {
+                        Ok(()) => return true,
+                        Err(TrySendError::Disconnected(_)) => return false,
+                        Err(TrySendError::Full(rejected)) => {
+                            value = rejected;
+                            let evicted = self.evict_rx.as_ref().map(Receiver::try_recv);
+                            match evicted {
+                                Some(Ok(_)) => self.drops.record(),
+                                Some(Err(TryRecvError::Disconnected)) => return false,
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn bounded_channel<T>(config: ChannelConfig) -> (FrameSender<T>, Receiver<T>) {
+    let (tx, rx) = crossbeam_channel::bounded(config.capacity.max(1));
+    let sender = FrameSender {
+        tx,
+        evict_rx: (config.overflow == OverflowPolicy::DropOldest).then(|| rx.clone()),
+        overflow: config.overflow,
+        drops: DropCounter::default(),
+    };
+    (sender, rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(rx: &Receiver<u32>) -> Vec<u32> {
+        rx.try_iter().collect()
+    }
+
+    #[test]
+    fn drop_newest_keeps_the_queued_frames() {
+        let (tx, rx) = bounded_channel(ChannelConfig::from_flag("2:drop-newest").unwrap());
+        assert!((1..=4).all(|value| tx.send(value)));
+        assert_eq!(drain(&rx), vec![1, 2]);
+        assert_eq!(tx.drops().total(), 2);
+    }
+
+    #[test]
+    fn drop_oldest_keeps_the_latest_frames() {
+        let (tx, rx) = bounded_channel(ChannelConfig::from_flag("2:drop-oldest").unwrap());
+        assert!((1..=4).all(|value| tx.send(value)));
+        assert_eq!(drain(&rx), vec![3, 4]);
+        assert_eq!(tx.drops().total(), 2);
+    }
+
+    #[test]
+    fn block_reports_a_disconnected_consumer() {
+        let (tx, rx) = bounded_channel(ChannelConfig::from_flag("1:block").unwrap());
+        assert!(tx.send(1));
+        drop(rx);
+        assert!(!tx.send(2));
+        assert_eq!(tx.drops().total(), 0);
+    }
+
+    #[test]
+    fn parses_the_flag_spelling() {
+        assert_eq!(
+            ChannelConfig::from_flag("8:block"),
+            Some(ChannelConfig {
+                capacity: 8,
+                overflow: OverflowPolicy::Block,
+            })
+        );
+        assert_eq!(ChannelConfig::from_flag("0:block"), None);
+        assert_eq!(ChannelConfig::from_flag("8"), None);
+        assert_eq!(ChannelConfig::from_flag("8:sometimes"), None);
+    }
+}
@@ -1,4 +1,8 @@
 use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     thread,
     time::{Duration, Instant},
 };
@@ -6,7 +10,11 @@ use std::{
 use crossbeam_channel::{Receiver, Sender};
 
 use crate::{
-    pipeline::skeleton,
+    pipeline::{
+        channel::DropCounter,
+        recognizer::palm::CropConfig,
+        skeleton::{self, SkeletonStyle},
+    },
     types::{Frame, GestureResult, RecognizedFrame},
 };
 
@@ -15,6 +23,50 @@ const MIN_COMPOSITED_FPS: u64 = 12;
 const SLOWDOWN_FACTOR: f64 = 1.25;
 const RECOVERY_FACTOR: f64 = 0.85;
 const OVERLAY_CONFIDENCE_THRESHOLD: f32 = 0.2;
+/// Time without a UI render after which the window counts as hidden.
+const RENDER_IDLE_AFTER: Duration = Duration::from_millis(500);
+
+/// Whether the UI is currently drawing frames, shared with the compositor.
+///
+/// gpui reports neither minimized nor occluded windows, but the platform
+/// stops requesting frames for them, so the UI marks every render and the
+/// window counts as hidden once renders stop. The compositor then skips
+/// overlay drawing, which nobody would see. Compositors started without
+/// one always draw.
+#[derive(Clone, Debug)]
+pub struct RenderActivity {
+    inner: Arc<RenderShared>,
+}
+
+#[derive(Debug)]
+struct RenderShared {
+    epoch: Instant,
+    last_render_ms: AtomicU64,
+}
+
+impl RenderActivity {
+    /// Records a UI render.
+    pub fn mark(&self) {
+        let now = self.inner.epoch.elapsed().as_millis() as u64;
+        self.inner.last_render_ms.store(now, Ordering::Relaxed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        let last = Duration::from_millis(self.inner.last_render_ms.load(Ordering::Relaxed));
+        self.inner.epoch.elapsed().saturating_sub(last) < RENDER_IDLE_AFTER
+    }
+}
+
+impl Default for RenderActivity {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RenderShared {
+                epoch: Instant::now(),
+                last_render_ms: AtomicU64::new(0),
+            }),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct CompositedFrame {
@@ -22,21 +74,43 @@ pub struct CompositedFrame {
     pub result: GestureResult,
 }
 
+/// Draws overlays onto recognized frames for display. Frames the consumer
+/// hasn't taken by the time the next one is ready are counted in `drops`.
 pub fn start_frame_compositor(
     recognized_rx: Receiver<RecognizedFrame>,
+    crop: CropConfig,
+    skeleton_hold: Duration,
+    render_activity: Option<RenderActivity>,
+    drops: DropCounter,
 ) -> (Receiver<CompositedFrame>, thread::JoinHandle<()>) {
     let (tx, rx) = crossbeam_channel::bounded(1);
-    let handle = thread::spawn(move || compositor_loop(recognized_rx, tx));
+    let handle = thread::spawn(move || {
+        compositor_loop(
+            recognized_rx,
+            tx,
+            crop,
+            skeleton_hold,
+            render_activity,
+            drops,
+        )
+    });
     (rx, handle)
 }
 
 fn compositor_loop(
     recognized_rx: Receiver<RecognizedFrame>,
     composited_tx: Sender<CompositedFrame>,
+    crop: CropConfig,
+    skeleton_hold: Duration,
+    render_activity: Option<RenderActivity>,
+    drops: DropCounter,
 ) {
     let min_interval = Duration::from_millis(1_000 / MAX_COMPOSITED_FPS);
     let max_interval = Duration::from_millis(1_000 / MIN_COMPOSITED_FPS);
     let mut target_interval = min_interval;
+    // Last drawn landmarks and the frame time they belong to, redrawn dimmed
+    // while detection briefly drops out.
+    let mut held: Option<(Vec<(f32, f32)>, Instant)> = None;
 
     while let Ok(mut recognized) = recognized_rx.recv() {
         while let Ok(newer) = recognized_rx.try_recv() {
@@ -47,16 +121,37 @@ fn compositor_loop(
         let result = recognized.result;
 
         let compose_start = Instant::now();
-        if !result.palm_regions.is_empty() {
+        // Results still go out while hidden; only the drawing is skipped.
+        let visible = render_activity
+            .as_ref()
+            .is_none_or(RenderActivity::is_active);
+        if visible && !result.palm_regions.is_empty() {
             skeleton::draw_palm_regions(
                 &mut frame.rgba,
                 frame.width,
                 frame.height,
                 &result.palm_regions,
+                &crop,
             );
         }
-        if let Some(points) = overlay_points(&result) {
+        if !visible {
+            held = None;
+        } else if let Some(points) = overlay_points(&result) {
             skeleton::draw_skeleton(&mut frame.rgba, frame.width, frame.height, points);
+            if !skeleton_hold.is_zero() {
+                held = Some((points.to_vec(), frame.timestamp));
+            }
+        } else if let Some((points, seen_at)) = held.take().filter(|(_, seen_at)| {
+            frame.timestamp.saturating_duration_since(*seen_at) <= skeleton_hold
+        }) {
+            skeleton::draw_skeleton_with_style(
+                &mut frame.rgba,
+                frame.width,
+                frame.height,
+                &points,
+                &SkeletonStyle::default().dimmed(),
+            );
+            held = Some((points, seen_at));
         }
         let compose_time = compose_start.elapsed();
 
@@ -65,6 +160,9 @@ fn compositor_loop(
             result: result.clone(),
         };
         let dropped_frame = composited_tx.try_send(packet).is_err();
+        if dropped_frame {
+            drops.record();
+        }
 
         target_interval = adjust_interval(
             target_interval,
@@ -106,7 +204,7 @@ fn adjust_interval(
 
 fn overlay_points(result: &GestureResult) -> Option<&[(f32, f32)]> {
     if result.confidence >= OVERLAY_CONFIDENCE_THRESHOLD {
-        result.landmarks.as_deref()
+        result.overlay_landmarks()
     } else {
         None
     }
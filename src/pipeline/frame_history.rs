@@ -0,0 +1,141 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::types::Frame;
+
+/// Ring buffer of recently displayed frames, so gesture-triggered actions can
+/// reach back to the moment before the gesture was recognized.
+///
+/// Frames are `width * height * 4` bytes each, so the buffer is bounded both by
+/// age and by a total byte budget; whichever limit is hit first evicts the
+/// oldest frames.
+pub struct FrameHistory {
+    frames: VecDeque<Frame>,
+    max_age: Duration,
+    max_bytes: usize,
+    bytes: usize,
+}
+
+impl FrameHistory {
+    pub fn new(max_age: Duration, max_bytes: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            max_age,
+            max_bytes,
+            bytes: 0,
+        }
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        let now = frame.timestamp;
+        self.bytes += frame.rgba.len();
+        self.frames.push_back(frame);
+
+        while let Some(front) = self.frames.front() {
+            let too_old = now.saturating_duration_since(front.timestamp) > self.max_age;
+            let over_budget = self.bytes > self.max_bytes && self.frames.len() > 1;
+            if !too_old && !over_budget {
+                break;
+            }
+            if let Some(evicted) = self.frames.pop_front() {
+                self.bytes -= evicted.rgba.len();
+            }
+        }
+    }
+
+    /// Returns the newest frame captured at least `ago` before now, falling back
+    /// to the oldest buffered frame when the history does not reach back that far.
+    pub fn frame_at(&self, ago: Duration) -> Option<&Frame> {
+        let Some(target) = Instant::now().checked_sub(ago) else {
+            return self.frames.front();
+        };
+
+        self.frames
+            .iter()
+            .rev()
+            .find(|frame| frame.timestamp <= target)
+            .or_else(|| self.frames.front())
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x2 (16-byte) frame stamped `offset_ms` after `base`, identified by
+    /// its sequence number.
+    fn frame(base: Instant, offset_ms: u64) -> Frame {
+        let mut frame = Frame::solid_color(2, 2, [0, 0, 0, 255]);
+        frame.timestamp = base + Duration::from_millis(offset_ms);
+        frame.sequence = offset_ms;
+        frame
+    }
+
+    fn sequences(history: &FrameHistory) -> Vec<u64> {
+        history.frames.iter().map(|frame| frame.sequence).collect()
+    }
+
+    #[test]
+    fn evicts_frames_older_than_max_age() {
+        let base = Instant::now();
+        let mut history = FrameHistory::new(Duration::from_millis(100), usize::MAX);
+        for offset in [0, 50, 120, 200] {
+            history.push(frame(base, offset));
+        }
+        assert_eq!(sequences(&history), vec![120, 200]);
+        assert_eq!(history.bytes, 32);
+    }
+
+    #[test]
+    fn evicts_oldest_frames_over_byte_budget() {
+        let base = Instant::now();
+        let mut history = FrameHistory::new(Duration::from_secs(10), 40);
+        for offset in [0, 10, 20, 30] {
+            history.push(frame(base, offset));
+        }
+        assert_eq!(sequences(&history), vec![20, 30]);
+        assert_eq!(history.bytes, 32);
+    }
+
+    #[test]
+    fn keeps_the_newest_frame_even_over_budget() {
+        let mut history = FrameHistory::new(Duration::from_secs(10), 8);
+        history.push(frame(Instant::now(), 0));
+        assert_eq!(sequences(&history), vec![0]);
+    }
+
+    #[test]
+    fn frame_at_picks_the_newest_frame_old_enough() {
+        let base = Instant::now() - Duration::from_millis(1_000);
+        let mut history = FrameHistory::new(Duration::from_secs(10), usize::MAX);
+        for offset in [0, 500, 900] {
+            history.push(frame(base, offset));
+        }
+        let pick = |ago| {
+            history
+                .frame_at(Duration::from_millis(ago))
+                .unwrap()
+                .sequence
+        };
+        assert_eq!(pick(300), 500);
+        assert_eq!(pick(0), 900);
+        // Further back than the history reaches: the oldest frame.
+        assert_eq!(pick(5_000), 0);
+    }
+
+    #[test]
+    fn clear_resets_the_byte_count() {
+        let mut history = FrameHistory::new(Duration::from_secs(10), usize::MAX);
+        history.push(frame(Instant::now(), 0));
+        history.clear();
+        assert!(history.frame_at(Duration::ZERO).is_none());
+        assert_eq!(history.bytes, 0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use rayon::prelude::*;
+
+pub const MIN_GAMMA: f32 = 0.2;
+pub const MAX_GAMMA: f32 = 3.0;
+
+/// Precomputed 8-bit gamma curve, applied per channel with a table lookup.
+///
+/// Values above 1.0 brighten shadows (`out = in^(1/gamma)`), which helps palm
+/// detection in dim rooms.
+pub struct GammaLut {
+    gamma: f32,
+    table: [u8; 256],
+}
+
+impl GammaLut {
+    pub fn new(gamma: f32) -> Self {
+        let gamma = gamma.clamp(MIN_GAMMA, MAX_GAMMA);
+        let inv = 1.0 / gamma;
+        let mut table = [0u8; 256];
+        for (value, slot) in table.iter_mut().enumerate() {
+            let normalized = value as f32 / 255.0;
+            *slot = (normalized.powf(inv) * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+        Self { gamma, table }
+    }
+
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Maps the RGB channels of an RGBA buffer in place, leaving alpha untouched.
+    pub fn apply(&self, rgba: &mut [u8]) {
+        rgba.par_chunks_exact_mut(4).for_each(|px| {
+            px[0] = self.table[px[0] as usize];
+            px[1] = self.table[px[1] as usize];
+            px[2] = self.table[px[2] as usize];
+        });
+    }
+}
+
+/// Shared low-light correction settings, adjusted from the UI and read by the
+/// recognizer worker on every frame.
+#[derive(Clone, Debug)]
+pub struct GammaControl {
+    inner: Arc<GammaShared>,
+}
+
+#[derive(Debug)]
+struct GammaShared {
+    enabled: AtomicBool,
+    gamma_bits: AtomicU32,
+    apply_to_preview: AtomicBool,
+}
+
+impl GammaControl {
+    pub fn enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.inner.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn gamma(&self) -> f32 {
+        f32::from_bits(self.inner.gamma_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn set_gamma(&self, gamma: f32) {
+        let gamma = gamma.clamp(MIN_GAMMA, MAX_GAMMA);
+        self.inner
+            .gamma_bits
+            .store(gamma.to_bits(), Ordering::Relaxed);
+    }
+
+    /// When set, the corrected frame is also shown in the preview; otherwise
+    /// only the recognizer's copy is adjusted.
+    pub fn apply_to_preview(&self) -> bool {
+        self.inner.apply_to_preview.load(Ordering::Relaxed)
+    }
+
+    pub fn set_apply_to_preview(&self, apply: bool) {
+        self.inner.apply_to_preview.store(apply, Ordering::Relaxed);
+    }
+
+    /// The gamma to apply to the current frame, if correction is enabled and
+    /// would change anything.
+    pub fn active_gamma(&self) -> Option<f32> {
+        let gamma = self.gamma();
+        if self.enabled() && (gamma - 1.0).abs() > f32::EPSILON {
+            Some(gamma)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for GammaControl {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(GammaShared {
+                enabled: AtomicBool::new(false),
+                gamma_bits: AtomicU32::new(1.6f32.to_bits()),
+                apply_to_preview: AtomicBool::new(false),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_gamma_is_the_identity() {
+        let lut = GammaLut::new(1.0);
+        assert!(lut.table.iter().enumerate().all(|(i, &v)| v as usize == i));
+    }
+
+    #[test]
+    fn endpoints_are_fixed_and_the_curve_is_monotonic() {
+        for gamma in [MIN_GAMMA, 0.5, 1.6, 2.2, MAX_GAMMA] {
+            let lut = GammaLut::new(gamma);
+            assert_eq!(lut.table[0], 0, "gamma {gamma}");
+            assert_eq!(lut.table[255], 255, "gamma {gamma}");
+            assert!(lut.table.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+
+    #[test]
+    fn maps_known_values() {
+        // (64 / 255)^(1 / 2) * 255 = 127.75
+        assert_eq!(GammaLut::new(2.0).table[64], 128);
+        // (128 / 255)^2 * 255 = 64.25
+        assert_eq!(GammaLut::new(0.5).table[128], 64);
+    }
+
+    #[test]
+    fn apply_leaves_alpha_untouched() {
+        let mut rgba = vec![64, 64, 64, 10, 0, 255, 64, 200];
+        GammaLut::new(2.0).apply(&mut rgba);
+        assert_eq!(rgba, vec![128, 128, 128, 10, 0, 255, 128, 200]);
+    }
+
+    #[test]
+    fn gamma_is_clamped_to_the_supported_range() {
+        assert_eq!(GammaLut::new(10.0).gamma(), MAX_GAMMA);
+        assert_eq!(GammaLut::new(0.0).gamma(), MIN_GAMMA);
+    }
+
+    #[test]
+    fn active_gamma_requires_enabled_and_non_unit() {
+        let control = GammaControl::default();
+        assert_eq!(control.active_gamma(), None);
+        control.set_enabled(true);
+        assert_eq!(control.active_gamma(), Some(control.gamma()));
+        control.set_gamma(1.0);
+        assert_eq!(control.active_gamma(), None);
+    }
+}
@@ -1,10 +1,16 @@
+pub mod builder;
 pub mod camera;
+pub mod channel;
 pub mod compositor;
+pub mod frame_history;
+pub mod gamma;
 pub mod recognizer;
 pub mod rgba_converter;
 pub mod skeleton;
 
 // Re-exports for convenience
-pub use camera::{CameraDevice, CameraStream, available_cameras, start_camera_stream};
-pub use compositor::{CompositedFrame, start_frame_compositor};
+pub use camera::{CameraDevice, CameraStream, available_cameras, find_camera, start_camera_stream};
+pub use channel::{FrameSender, bounded_channel};
+pub use compositor::{CompositedFrame, RenderActivity, start_frame_compositor};
+pub use frame_history::FrameHistory;
 pub use recognizer::{RecognizerBackend, start_recognizer};
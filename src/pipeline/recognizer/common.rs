@@ -1,6 +1,9 @@
+use std::borrow::Cow;
+
 use anyhow::{Context, Result, anyhow};
 use fast_image_resize as fir;
-use ndarray::Array4;
+use ndarray::{Array4, ArrayViewD};
+use ort::session::Session;
 use rayon::prelude::*;
 
 use crate::types::Frame;
@@ -9,15 +12,173 @@ pub const INPUT_SIZE: u32 = 224;
 pub const NUM_LANDMARKS: usize = 21;
 pub const PALM_INPUT_SIZE: u32 = 192;
 
+/// Square input side of an NHWC `[batch, size, size, 3]` image model, read
+/// from the session's first input. Returns `Ok(None)` when the spatial
+/// dimensions are dynamic, and an error for non-square or non-RGB inputs.
+pub fn detect_input_size(session: &Session) -> Result<Option<u32>> {
+    let input = session
+        .inputs
+        .first()
+        .ok_or_else(|| anyhow!("model has no inputs"))?;
+    let shape = input
+        .input_type
+        .tensor_shape()
+        .ok_or_else(|| anyhow!("model input \"{}\" is not a tensor", input.name))?;
+
+    let &[_, height, width, channels] = &shape[..] else {
+        return Err(anyhow!(
+            "unsupported model input shape {shape}, expected [batch, height, width, 3]"
+        ));
+    };
+    if channels >= 0 && channels != 3 {
+        return Err(anyhow!(
+            "unsupported model input shape {shape}, expected 3 channels in NHWC layout"
+        ));
+    }
+    if height < 0 || width < 0 {
+        return Ok(None);
+    }
+    if height != width {
+        return Err(anyhow!(
+            "unsupported model input shape {shape}: {width}x{height} is not square"
+        ));
+    }
+    if height == 0 {
+        return Err(anyhow!("unsupported model input shape {shape}"));
+    }
+
+    Ok(Some(height as u32))
+}
+
+/// Picks the preprocessing size for a session: an explicit override wins (and
+/// must agree with the model if its shape is static), then the detected size,
+/// then `fallback`.
+pub fn resolve_input_size(
+    session: &Session,
+    override_size: Option<u32>,
+    fallback: u32,
+) -> Result<u32> {
+    let detected = detect_input_size(session)?;
+    match (override_size, detected) {
+        (Some(requested), Some(actual)) if requested != actual => Err(anyhow!(
+            "configured input size {requested} does not match model input size {actual}"
+        )),
+        (Some(requested), _) => Ok(requested),
+        (None, Some(actual)) => Ok(actual),
+        (None, None) => Ok(fallback),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct HandposeOutput {
     pub raw_landmarks: Vec<[f32; 3]>,
     pub projected_landmarks: Vec<(f32, f32)>,
+    /// Combined palm × handpose score used for gating.
     pub confidence: f32,
+    /// Score of the palm box the crop came from; `None` when the crop came
+    /// from tracking or the full frame.
+    pub palm_score: Option<f32>,
+    /// Presence score of the handpose model alone.
+    pub handpose_confidence: f32,
     pub handedness: f32,
     pub palm_regions: Vec<crate::types::PalmRegion>,
+    /// Hands from further palm boxes when `max_hands` allows more than one.
+    pub secondary_hands: Vec<SecondaryHand>,
+}
+
+/// Handpose output for a hand other than the tracked one. These come
+/// straight from their palm box each frame, without tracking.
+#[derive(Clone, Debug)]
+pub struct SecondaryHand {
+    pub raw_landmarks: Vec<[f32; 3]>,
+    pub projected_landmarks: Vec<(f32, f32)>,
+    /// Palm score times handpose confidence.
+    pub confidence: f32,
+    pub handedness: f32,
+}
+
+impl HandposeOutput {
+    /// Maps frame-space coordinates (projected landmarks and palm regions) by
+    /// `factor`, e.g. from a downscaled working frame back to the full frame.
+    pub fn rescale(&mut self, factor: f32) {
+        for point in &mut self.projected_landmarks {
+            point.0 *= factor;
+            point.1 *= factor;
+        }
+        for region in &mut self.palm_regions {
+            for value in &mut region.bbox {
+                *value *= factor;
+            }
+            for point in &mut region.landmarks {
+                point.0 *= factor;
+                point.1 *= factor;
+            }
+        }
+        for hand in &mut self.secondary_hands {
+            for point in &mut hand.projected_landmarks {
+                point.0 *= factor;
+                point.1 *= factor;
+            }
+        }
+    }
+}
+
+/// Downscales `frame` so its longer side is at most `max_side`. Returns the
+/// smaller frame and the factor that maps its coordinates back to the
+/// original, or `None` when the frame already fits.
+pub fn downscale_frame(frame: &Frame, max_side: u32) -> Result<Option<(Frame, f32)>> {
+    let long_side = frame.width.max(frame.height);
+    if max_side == 0 || long_side <= max_side {
+        return Ok(None);
+    }
+
+    let scale = max_side as f32 / long_side as f32;
+    let new_w = (frame.width as f32 * scale).round().max(1.0) as u32;
+    let new_h = (frame.height as f32 * scale).round().max(1.0) as u32;
+
+    let src_image =
+        fir::images::ImageRef::new(frame.width, frame.height, &frame.rgba, fir::PixelType::U8x4)?;
+    let mut dst_image = fir::images::Image::new(new_w, new_h, fir::PixelType::U8x4);
+    let resize_options = fir::ResizeOptions::new()
+        .resize_alg(fir::ResizeAlg::Interpolation(fir::FilterType::Bilinear));
+    fir::Resizer::new()
+        .resize(&src_image, &mut dst_image, Some(&resize_options))
+        .context("working-resolution resize failed")?;
+
+    let working = Frame {
+        rgba: dst_image.into_vec(),
+        width: new_w,
+        height: new_h,
+        timestamp: frame.timestamp,
+        sequence: frame.sequence,
+    };
+    Ok(Some((working, frame.width as f32 / new_w as f32)))
+}
+
+/// How a frame is fitted into the square model input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PreprocessMode {
+    /// Scale the whole frame to fit and pad the short side.
+    #[default]
+    Letterbox,
+    /// Take the largest centered square and scale it to fill the input.
+    /// Keeps hands larger on wide frames but ignores the sides.
+    CenterCrop,
 }
 
+impl PreprocessMode {
+    /// Parses the command-line spelling, `letterbox` or `center-crop`.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        match value {
+            "letterbox" => Some(PreprocessMode::Letterbox),
+            "center-crop" => Some(PreprocessMode::CenterCrop),
+            _ => None,
+        }
+    }
+}
+
+/// Maps model-input pixels back to the frame: `orig = (input - pad) / scale`.
+/// With [`PreprocessMode::CenterCrop`] the pads are negative.
 #[derive(Clone, Debug)]
 pub struct LetterboxInfo {
     pub scale: f32,
@@ -27,6 +188,19 @@ pub struct LetterboxInfo {
     pub orig_h: u32,
 }
 
+impl LetterboxInfo {
+    /// Inverse of the preprocessing resize, `input = orig * scale + pad`,
+    /// clamped to the frame.
+    pub fn project(&self, x: f32, y: f32) -> (f32, f32) {
+        let px = (x - self.pad_x) / self.scale;
+        let py = (y - self.pad_y) / self.scale;
+        (
+            px.clamp(0.0, (self.orig_w.saturating_sub(1)) as f32),
+            py.clamp(0.0, (self.orig_h.saturating_sub(1)) as f32),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CropTransform {
     pub center: (f32, f32),
@@ -37,40 +211,97 @@ pub struct CropTransform {
     pub orig_h: u32,
 }
 
+/// An RGBA buffer whose length disagrees with the frame's dimensions, as
+/// happens briefly when the camera changes resolution mid-stream. The
+/// worker skips such frames instead of treating them as inference failures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameSizeMismatch {
+    pub got: usize,
+    pub expected: usize,
+}
+
+impl std::fmt::Display for FrameSizeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "frame buffer size mismatch: got {}, expected {}",
+            self.got, self.expected
+        )
+    }
+}
+
+impl std::error::Error for FrameSizeMismatch {}
+
+pub fn check_frame_buffer(frame: &Frame) -> Result<(), FrameSizeMismatch> {
+    let expected = (frame.width as usize)
+        .saturating_mul(frame.height as usize)
+        .saturating_mul(4);
+    if frame.rgba.len() == expected {
+        Ok(())
+    } else {
+        Err(FrameSizeMismatch {
+            got: frame.rgba.len(),
+            expected,
+        })
+    }
+}
+
 #[allow(dead_code)]
 pub fn prepare_frame(frame: &Frame) -> Result<(Array4<f32>, LetterboxInfo)> {
-    prepare_frame_with_size(frame, INPUT_SIZE)
+    prepare_frame_with_size(frame, INPUT_SIZE, PreprocessMode::Letterbox, false)
 }
 
+/// Fits `frame` into a `target_size` square RGB tensor in `[0, 1]`.
+///
+/// With `grayscale` the frame is reduced to luma before resizing, so the
+/// resize and padding move a quarter of the bytes and skip the copy of the
+/// RGBA buffer; the luma is repeated into all three channels.
 pub fn prepare_frame_with_size(
     frame: &Frame,
     target_size: u32,
+    mode: PreprocessMode,
+    grayscale: bool,
 ) -> Result<(Array4<f32>, LetterboxInfo)> {
-    let expected_len = (frame.width as usize)
-        .saturating_mul(frame.height as usize)
-        .saturating_mul(4);
-    if frame.rgba.len() != expected_len {
+    if frame.is_too_small() {
         return Err(anyhow!(
-            "frame buffer size mismatch: got {}, expected {}",
-            frame.rgba.len(),
-            expected_len
+            "frame too small for inference: {}x{}",
+            frame.width,
+            frame.height
         ));
     }
+    check_frame_buffer(frame)?;
 
-    let scale = target_size as f32 / (frame.width.max(frame.height) as f32);
-    let new_w = (frame.width as f32 * scale).round().max(1.0) as u32;
-    let new_h = (frame.height as f32 * scale).round().max(1.0) as u32;
+    // Source square for center-crop, offset into the frame.
+    let crop = match mode {
+        PreprocessMode::Letterbox => None,
+        PreprocessMode::CenterCrop => {
+            let side = frame.width.min(frame.height);
+            Some(((frame.width - side) / 2, (frame.height - side) / 2, side))
+        }
+    };
+    let (scale, new_w, new_h) = match crop {
+        Some((_, _, side)) => (target_size as f32 / side as f32, target_size, target_size),
+        None => {
+            let scale = target_size as f32 / (frame.width.max(frame.height) as f32);
+            let new_w = (frame.width as f32 * scale).round().max(1.0) as u32;
+            let new_h = (frame.height as f32 * scale).round().max(1.0) as u32;
+            (scale, new_w, new_h)
+        }
+    };
 
-    let src_image = fir::images::Image::from_vec_u8(
-        frame.width,
-        frame.height,
-        frame.rgba.clone(),
-        fir::PixelType::U8x4,
-    )?;
-    let mut dst_image = fir::images::Image::new(new_w, new_h, fir::PixelType::U8x4);
+    let (pixels, pixel_type, channels) = if grayscale {
+        (luma(&frame.rgba), fir::PixelType::U8, 1)
+    } else {
+        (frame.rgba.clone(), fir::PixelType::U8x4, 4)
+    };
+    let src_image = fir::images::Image::from_vec_u8(frame.width, frame.height, pixels, pixel_type)?;
+    let mut dst_image = fir::images::Image::new(new_w, new_h, pixel_type);
     let mut resizer = fir::Resizer::new();
-    let resize_options = fir::ResizeOptions::new()
+    let mut resize_options = fir::ResizeOptions::new()
         .resize_alg(fir::ResizeAlg::Interpolation(fir::FilterType::Bilinear));
+    if let Some((left, top, side)) = crop {
+        resize_options = resize_options.crop(left as f64, top as f64, side as f64, side as f64);
+    }
     resizer
         .resize(&src_image, &mut dst_image, Some(&resize_options))
         .context("fast resize failed")?;
@@ -78,14 +309,11 @@ pub fn prepare_frame_with_size(
 
     let pad_x = ((target_size as i64 - new_w as i64) / 2).max(0) as usize;
     let pad_y = ((target_size as i64 - new_h as i64) / 2).max(0) as usize;
-    let mut canvas = vec![0u8; (target_size as usize) * (target_size as usize) * 4];
-    for px in canvas.chunks_mut(4) {
-        px[3] = 255;
-    }
-    let dst_stride = target_size as usize * 4;
-    let src_stride = new_w as usize * 4;
+    let mut canvas = vec![0u8; (target_size as usize) * (target_size as usize) * channels];
+    let dst_stride = target_size as usize * channels;
+    let src_stride = new_w as usize * channels;
     for row in 0..(new_h as usize) {
-        let dst_offset = (pad_y + row) * dst_stride + pad_x * 4;
+        let dst_offset = (pad_y + row) * dst_stride + pad_x * channels;
         let src_offset = row * src_stride;
         let dst_slice = &mut canvas[dst_offset..dst_offset + src_stride];
         let src_slice = &resized[src_offset..src_offset + src_stride];
@@ -93,13 +321,14 @@ pub fn prepare_frame_with_size(
     }
 
     let normalized: Vec<f32> = canvas
-        .par_chunks_exact(4)
+        .par_chunks_exact(channels)
         .flat_map_iter(|px| {
-            [
-                px[0] as f32 / 255.0,
-                px[1] as f32 / 255.0,
-                px[2] as f32 / 255.0,
-            ]
+            let [r, g, b] = match px {
+                [y] => [*y; 3],
+                [r, g, b, _] => [*r, *g, *b],
+                _ => unreachable!("chunks have 1 or 4 channels"),
+            };
+            [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]
         })
         .collect();
     let input = Array4::<f32>::from_shape_vec(
@@ -108,10 +337,14 @@ pub fn prepare_frame_with_size(
     )
     .map_err(|err| anyhow!("failed to build input tensor: {err}"))?;
 
+    let (info_pad_x, info_pad_y) = match crop {
+        Some((left, top, _)) => (-(left as f32) * scale, -(top as f32) * scale),
+        None => (pad_x as f32, pad_y as f32),
+    };
     let letterbox = LetterboxInfo {
         scale,
-        pad_x: pad_x as f32,
-        pad_y: pad_y as f32,
+        pad_x: info_pad_x,
+        pad_y: info_pad_y,
         orig_w: frame.width,
         orig_h: frame.height,
     };
@@ -119,6 +352,23 @@ pub fn prepare_frame_with_size(
     Ok((input, letterbox))
 }
 
+/// BT.601 luma of each RGBA pixel, in 8-bit fixed point.
+fn luma(rgba: &[u8]) -> Vec<u8> {
+    rgba.par_chunks_exact(4)
+        .map(|px| ((77 * px[0] as u32 + 150 * px[1] as u32 + 29 * px[2] as u32) >> 8) as u8)
+        .collect()
+}
+
+/// Row-major values of an output tensor. Borrowed when the array is already
+/// contiguous; otherwise, as some execution providers return outputs with
+/// non-standard strides, copied element by element in logical order.
+pub fn contiguous_values<'a>(array: &'a ArrayViewD<'_, f32>) -> Cow<'a, [f32]> {
+    match array.as_slice() {
+        Some(values) => Cow::Borrowed(values),
+        None => Cow::Owned(array.iter().copied().collect()),
+    }
+}
+
 pub fn decode_landmarks(flat: &[f32]) -> Result<Vec<[f32; 3]>> {
     if flat.len() < NUM_LANDMARKS * 3 {
         return Err(anyhow!(
@@ -135,47 +385,156 @@ pub fn decode_landmarks(flat: &[f32]) -> Result<Vec<[f32; 3]>> {
     Ok(landmarks)
 }
 
+/// Splits handpose model outputs into landmarks, presence confidence and
+/// handedness. Two layouts are understood: separate tensors
+/// `[landmarks, confidence, handedness?]`, and a single fused tensor of the
+/// 63 landmark values followed by confidence and optionally handedness.
+pub fn decode_handpose_outputs(outputs: &[Vec<f32>]) -> Result<(Vec<[f32; 3]>, f32, f32)> {
+    let landmark_len = NUM_LANDMARKS * 3;
+    match outputs {
+        [] => Err(anyhow!("model returned no outputs")),
+        [fused] => {
+            if fused.len() != landmark_len + 1 && fused.len() != landmark_len + 2 {
+                return Err(anyhow!(
+                    "unsupported handpose output: a single tensor of {} values, expected {} (landmarks + confidence) or {} (+ handedness), or separate landmark/confidence tensors",
+                    fused.len(),
+                    landmark_len + 1,
+                    landmark_len + 2
+                ));
+            }
+            let landmarks = decode_landmarks(&fused[..landmark_len])?;
+            let handedness = fused.get(landmark_len + 1).copied().unwrap_or(0.0);
+            Ok((landmarks, fused[landmark_len], handedness))
+        }
+        [coords, rest @ ..] => {
+            let landmarks = decode_landmarks(coords)?;
+            let confidence = rest[0].first().copied().unwrap_or(0.0);
+            let handedness = rest
+                .get(1)
+                .and_then(|values| values.first().copied())
+                .unwrap_or(0.0);
+            Ok((landmarks, confidence, handedness))
+        }
+    }
+}
+
+/// Parent of each landmark in the 21-point hand skeleton; the wrist is its
+/// own parent.
+const LANDMARK_PARENTS: [usize; NUM_LANDMARKS] = [
+    0, 0, 1, 2, 3, 0, 5, 6, 7, 0, 9, 10, 11, 0, 13, 14, 15, 0, 17, 18, 19,
+];
+/// Longest plausible bone, in units of the palm scale. Generous so fingers
+/// seen flat while the palm is tilted away still pass.
+const MAX_BONE_RATIO: f32 = 1.5;
+/// Widest plausible gap between neighboring knuckles, in palm scale units.
+const MAX_KNUCKLE_GAP_RATIO: f32 = 0.8;
+/// Largest plausible landmark bounding-box diagonal, in palm scale units; an
+/// outstretched hand stays well under 3.
+const MAX_SPREAD_RATIO: f32 = 4.0;
+
+/// Rejects handpose output that can't be a hand: collapsed or non-finite
+/// points, bones much longer than the palm, knuckles far apart, or points
+/// scattered well beyond the palm's reach. The handpose model always
+/// returns 21 points, even on a palm detector false positive such as wood
+/// grain or a face, and those skeletons are typically this degenerate.
+///
+/// Only 2D ratios to the palm scale are used — the larger of wrist to
+/// middle knuckle and index to pinky knuckle — so the check works in any
+/// coordinate space and tolerates the hand turning away from the camera.
+pub fn is_plausible_hand(landmarks: &[[f32; 3]]) -> bool {
+    if landmarks.len() != NUM_LANDMARKS
+        || landmarks
+            .iter()
+            .any(|[x, y, _]| !x.is_finite() || !y.is_finite())
+    {
+        return false;
+    }
+    let dist = |a: usize, b: usize| {
+        let dx = landmarks[a][0] - landmarks[b][0];
+        let dy = landmarks[a][1] - landmarks[b][1];
+        (dx * dx + dy * dy).sqrt()
+    };
+
+    let scale = dist(0, 9).max(dist(5, 17));
+    if scale <= f32::EPSILON {
+        return false;
+    }
+
+    let bones_ok = LANDMARK_PARENTS
+        .iter()
+        .enumerate()
+        .all(|(idx, &parent)| dist(idx, parent) <= MAX_BONE_RATIO * scale);
+    let knuckles_ok = [(5, 9), (9, 13), (13, 17)]
+        .iter()
+        .all(|&(a, b)| dist(a, b) <= MAX_KNUCKLE_GAP_RATIO * scale);
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for [x, y, _] in landmarks {
+        min_x = min_x.min(*x);
+        min_y = min_y.min(*y);
+        max_x = max_x.max(*x);
+        max_y = max_y.max(*y);
+    }
+    let spread = ((max_x - min_x).powi(2) + (max_y - min_y).powi(2)).sqrt();
+
+    bones_ok && knuckles_ok && spread <= MAX_SPREAD_RATIO * scale
+}
+
+/// Depth of each landmark relative to the wrist: its `z` minus the `z` of
+/// landmark 0, in the handpose model's crop pixels. The wrist is always 0
+/// and negative values are closer to the camera than the wrist, which is
+/// what the bundled model reports; `flip` negates the result for models
+/// that put the camera on the positive side.
+pub fn wrist_relative_depths(landmarks: &[[f32; 3]], flip: bool) -> Vec<f32> {
+    let Some(&[_, _, wrist_z]) = landmarks.first() else {
+        return Vec::new();
+    };
+    let sign = if flip { -1.0 } else { 1.0 };
+    landmarks
+        .iter()
+        .map(|[_, _, z]| sign * (z - wrist_z))
+        .collect()
+}
+
 #[allow(dead_code)]
 pub fn project_landmarks(landmarks: &[[f32; 3]], letterbox: &LetterboxInfo) -> Vec<(f32, f32)> {
     landmarks
         .iter()
-        .map(|[x, y, _z]| {
-            let px = (x - letterbox.pad_x) / letterbox.scale;
-            let py = (y - letterbox.pad_y) / letterbox.scale;
-            let cx = px.clamp(0.0, (letterbox.orig_w.saturating_sub(1)) as f32);
-            let cy = py.clamp(0.0, (letterbox.orig_h.saturating_sub(1)) as f32);
-            (cx, cy)
-        })
+        .map(|[x, y, _z]| letterbox.project(*x, *y))
         .collect()
 }
 
+/// Samples the `side`-pixel square around `center`, rotated by `angle`,
+/// into an `output_size` square tensor. With `crop_size` above
+/// `output_size` the square is sampled at `crop_size` first and then
+/// area-averaged down, so small hands keep detail that direct bilinear
+/// sampling would skip over.
 pub fn prepare_rotated_crop(
     frame: &Frame,
     center: (f32, f32),
     side: f32,
     angle: f32,
     output_size: u32,
+    crop_size: u32,
 ) -> Result<(Array4<f32>, CropTransform)> {
-    let expected_len = (frame.width as usize)
-        .saturating_mul(frame.height as usize)
-        .saturating_mul(4);
-    if frame.rgba.len() != expected_len {
+    if frame.is_too_small() {
         return Err(anyhow!(
-            "frame buffer size mismatch: got {}, expected {}",
-            frame.rgba.len(),
-            expected_len
+            "frame too small for inference: {}x{}",
+            frame.width,
+            frame.height
         ));
     }
-    let mut data =
-        Vec::with_capacity((output_size as usize).saturating_mul(output_size as usize * 3));
-    let half = output_size as f32 / 2.0;
-    let scale = side / output_size as f32;
+    check_frame_buffer(frame)?;
+    let crop_size = crop_size.max(output_size);
+    let mut data = Vec::with_capacity((crop_size as usize).saturating_mul(crop_size as usize * 3));
+    let half = crop_size as f32 / 2.0;
+    let scale = side / crop_size as f32;
     let cos = angle.cos();
     let sin = angle.sin();
 
-    for y in 0..output_size {
+    for y in 0..crop_size {
         let dy = (y as f32 + 0.5 - half) * scale;
-        for x in 0..output_size {
+        for x in 0..crop_size {
             let dx = (x as f32 + 0.5 - half) * scale;
             let src_x = center.0 + dx * cos - dy * sin;
             let src_y = center.1 + dx * sin + dy * cos;
@@ -183,6 +542,9 @@ pub fn prepare_rotated_crop(
             data.extend_from_slice(&rgb);
         }
     }
+    if crop_size > output_size {
+        data = downscale_area(&data, crop_size as usize, output_size as usize);
+    }
 
     let array =
         Array4::<f32>::from_shape_vec((1, output_size as usize, output_size as usize, 3), data)
@@ -210,7 +572,53 @@ pub fn project_landmarks_with_transform(
         .collect()
 }
 
+/// Area-average resize of a square RGB image from `src` to `dst` pixels a
+/// side, `dst <= src`. Each output pixel averages the source pixels it
+/// covers, partly covered ones weighted by the covered fraction.
+fn downscale_area(data: &[f32], src: usize, dst: usize) -> Vec<f32> {
+    let ratio = src as f32 / dst as f32;
+    // Source indices and weights per output index, the same for both axes.
+    let taps: Vec<Vec<(usize, f32)>> = (0..dst)
+        .map(|i| {
+            let start = i as f32 * ratio;
+            let end = start + ratio;
+            (start.floor() as usize..(end.ceil() as usize).min(src))
+                .filter_map(|j| {
+                    let covered = end.min(j as f32 + 1.0) - start.max(j as f32);
+                    (covered > 0.0).then_some((j, covered / ratio))
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut out = Vec::with_capacity(dst * dst * 3);
+    for row_taps in &taps {
+        for col_taps in &taps {
+            let mut rgb = [0.0f32; 3];
+            for &(sy, wy) in row_taps {
+                for &(sx, wx) in col_taps {
+                    let idx = (sy * src + sx) * 3;
+                    let weight = wy * wx;
+                    for (acc, value) in rgb.iter_mut().zip(&data[idx..idx + 3]) {
+                        *acc += value * weight;
+                    }
+                }
+            }
+            out.extend_from_slice(&rgb);
+        }
+    }
+    out
+}
+
 impl CropTransform {
+    /// Maps a crop-input point to the frame, clamped to it. This is the same
+    /// rotation and scale [`prepare_rotated_crop`] samples with, so a pixel
+    /// center `i + 0.5` lands exactly on the frame point that pixel was
+    /// sampled from. A larger `crop_size` needs no extra term: the area
+    /// resize maps that center to the middle of the source pixels it
+    /// averaged, `(i + 0.5) * crop_size / output_size`, and the finer
+    /// sampling step cancels the factor again. This is exact for whole
+    /// ratios and off by under a tenth of a source pixel otherwise.
     pub fn project(&self, x: f32, y: f32) -> (f32, f32) {
         let half = self.output_size as f32 / 2.0;
         let scale = self.side / self.output_size as f32;
@@ -268,3 +676,308 @@ fn sample_rgb(frame: &Frame, x: f32, y: f32) -> [f32; 3] {
         lerp(lerp(c00[2], c10[2], fx), lerp(c01[2], c11[2], fx), fy),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MIN_FRAME_SIDE;
+
+    #[test]
+    fn tiny_frames_are_rejected_before_inference() {
+        for (width, height) in [(MIN_FRAME_SIDE - 1, 480), (640, MIN_FRAME_SIDE - 1), (1, 1)] {
+            let frame = Frame::solid_color(width, height, [128, 128, 128, 255]);
+            assert!(frame.is_too_small());
+            assert!(
+                prepare_frame_with_size(&frame, PALM_INPUT_SIZE, PreprocessMode::Letterbox, false)
+                    .is_err()
+            );
+            assert!(
+                prepare_rotated_crop(&frame, (0.5, 0.5), 1.0, 0.0, INPUT_SIZE, INPUT_SIZE).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn smallest_allowed_frame_is_prepared() {
+        let frame = Frame::solid_color(MIN_FRAME_SIDE, MIN_FRAME_SIDE, [128, 128, 128, 255]);
+        assert!(!frame.is_too_small());
+        let (input, _) =
+            prepare_frame_with_size(&frame, PALM_INPUT_SIZE, PreprocessMode::Letterbox, false)
+                .unwrap();
+        let side = PALM_INPUT_SIZE as usize;
+        assert_eq!(input.shape(), &[1, side, side, 3]);
+    }
+
+    /// A 640x480 gray frame with a white 8x8 block whose top-left corner is
+    /// at `at`.
+    fn frame_with_block(at: (u32, u32)) -> Frame {
+        sized_frame_with_block(640, 480, at)
+    }
+
+    fn sized_frame_with_block(width: u32, height: u32, at: (u32, u32)) -> Frame {
+        let mut frame = Frame::solid_color(width, height, [64, 64, 64, 255]);
+        for y in at.1..at.1 + 8 {
+            for x in at.0..at.0 + 8 {
+                let idx = ((y * frame.width + x) * 4) as usize;
+                frame.rgba[idx..idx + 3].copy_from_slice(&[255, 255, 255]);
+            }
+        }
+        frame
+    }
+
+    /// Centroid of the input pixels brighter than the background.
+    fn bright_centroid(input: &Array4<f32>) -> (f32, f32) {
+        let (mut sum_x, mut sum_y, mut count) = (0.0, 0.0, 0.0);
+        for ((_, y, x, c), value) in input.indexed_iter() {
+            if c == 0 && *value > 0.5 {
+                sum_x += x as f32 + 0.5;
+                sum_y += y as f32 + 0.5;
+                count += 1.0;
+            }
+        }
+        assert!(count > 0.0, "block not found in the input");
+        (sum_x / count, sum_y / count)
+    }
+
+    #[test]
+    fn preprocessing_projects_back_onto_the_frame() {
+        let block_center = (404.0, 204.0);
+        let frame = frame_with_block((400, 200));
+        for mode in [PreprocessMode::Letterbox, PreprocessMode::CenterCrop] {
+            let (input, info) =
+                prepare_frame_with_size(&frame, PALM_INPUT_SIZE, mode, false).unwrap();
+            let (x, y) = bright_centroid(&input);
+            let (fx, fy) = info.project(x, y);
+            // Within one input pixel, which spans several frame pixels.
+            let tolerance = 1.0 / info.scale;
+            assert!(
+                (fx - block_center.0).abs() <= tolerance
+                    && (fy - block_center.1).abs() <= tolerance,
+                "{mode:?}: ({fx}, {fy})"
+            );
+        }
+    }
+
+    #[test]
+    fn center_crop_leaves_out_the_sides() {
+        let frame = frame_with_block((8, 200));
+        let (input, _) =
+            prepare_frame_with_size(&frame, PALM_INPUT_SIZE, PreprocessMode::CenterCrop, false)
+                .unwrap();
+        assert!(input.iter().all(|value| *value < 0.5));
+    }
+
+    #[test]
+    fn non_contiguous_outputs_are_copied_in_logical_order() {
+        // Stored as [3, 21] and viewed transposed, so as_slice() fails.
+        let stored = ndarray::Array2::from_shape_fn((3, NUM_LANDMARKS), |(axis, landmark)| {
+            (landmark * 3 + axis) as f32
+        });
+        let view = stored.t().into_dyn();
+        assert!(view.as_slice().is_none());
+
+        let values = contiguous_values(&view);
+        assert!(matches!(values, Cow::Owned(_)));
+        let landmarks = decode_landmarks(&values).unwrap();
+        assert_eq!(landmarks[0], [0.0, 1.0, 2.0]);
+        assert_eq!(landmarks[20], [60.0, 61.0, 62.0]);
+    }
+
+    #[test]
+    fn contiguous_outputs_are_borrowed() {
+        let stored = ndarray::Array2::<f32>::zeros((NUM_LANDMARKS, 3));
+        let view = stored.view().into_dyn();
+        assert!(matches!(contiguous_values(&view), Cow::Borrowed(_)));
+    }
+
+    /// An open right hand, fingers up, in handpose crop pixels.
+    const OPEN_HAND: [[f32; 3]; NUM_LANDMARKS] = [
+        [112.0, 200.0, 0.0],
+        [85.0, 185.0, -4.0],
+        [68.0, 165.0, -7.0],
+        [58.0, 145.0, -9.0],
+        [50.0, 128.0, -11.0],
+        [90.0, 130.0, -3.0],
+        [86.0, 105.0, -6.0],
+        [84.0, 88.0, -8.0],
+        [82.0, 72.0, -9.0],
+        [110.0, 126.0, -2.0],
+        [110.0, 98.0, -5.0],
+        [110.0, 80.0, -7.0],
+        [110.0, 62.0, -8.0],
+        [128.0, 130.0, -2.0],
+        [131.0, 104.0, -5.0],
+        [133.0, 88.0, -6.0],
+        [134.0, 74.0, -7.0],
+        [144.0, 138.0, -2.0],
+        [149.0, 118.0, -4.0],
+        [152.0, 105.0, -5.0],
+        [155.0, 93.0, -6.0],
+    ];
+
+    #[test]
+    fn a_realistic_hand_is_plausible_at_any_scale() {
+        assert!(is_plausible_hand(&OPEN_HAND));
+        let far_away: Vec<[f32; 3]> = OPEN_HAND
+            .iter()
+            .map(|[x, y, z]| [x * 0.1 + 500.0, y * 0.1 + 20.0, *z])
+            .collect();
+        assert!(is_plausible_hand(&far_away));
+    }
+
+    #[test]
+    fn degenerate_hands_are_rejected() {
+        assert!(!is_plausible_hand(&[[0.0; 3]; NUM_LANDMARKS]));
+        assert!(!is_plausible_hand(&OPEN_HAND[..20]));
+
+        let mut not_finite = OPEN_HAND;
+        not_finite[8][0] = f32::NAN;
+        assert!(!is_plausible_hand(&not_finite));
+
+        // A fingertip flung across the crop makes a bone far longer than
+        // the palm.
+        let mut stretched = OPEN_HAND;
+        stretched[12] = [110.0, -200.0, -8.0];
+        assert!(!is_plausible_hand(&stretched));
+    }
+
+    #[test]
+    fn letterbox_projection_inverts_the_resize() {
+        // Landscape pads top and bottom, portrait left and right.
+        for (orig_w, orig_h) in [(640u32, 480u32), (480, 640), (1920, 1080)] {
+            let scale = PALM_INPUT_SIZE as f32 / orig_w.max(orig_h) as f32;
+            let letterbox = LetterboxInfo {
+                scale,
+                pad_x: (PALM_INPUT_SIZE as f32 - orig_w as f32 * scale) / 2.0,
+                pad_y: (PALM_INPUT_SIZE as f32 - orig_h as f32 * scale) / 2.0,
+                orig_w,
+                orig_h,
+            };
+            let points = [
+                (0.0, 0.0),
+                (123.0, 45.0),
+                (orig_w as f32 - 1.0, orig_h as f32 - 1.0),
+            ];
+            let input: Vec<[f32; 3]> = points
+                .iter()
+                .map(|(x, y)| {
+                    [
+                        x * scale + letterbox.pad_x,
+                        y * scale + letterbox.pad_y,
+                        0.0,
+                    ]
+                })
+                .collect();
+            for (projected, expected) in project_landmarks(&input, &letterbox).iter().zip(points) {
+                assert!(
+                    (projected.0 - expected.0).abs() < 1e-2
+                        && (projected.1 - expected.1).abs() < 1e-2,
+                    "{orig_w}x{orig_h}: {projected:?} vs {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_crop_projection_inverts_the_crop() {
+        for (orig_w, orig_h) in [(640u32, 480u32), (480, 640)] {
+            for angle in [0.0, 0.6, -2.5] {
+                let transform = CropTransform {
+                    center: (orig_w as f32 * 0.45, orig_h as f32 * 0.55),
+                    side: 200.0,
+                    angle,
+                    output_size: INPUT_SIZE,
+                    orig_w,
+                    orig_h,
+                };
+                let half = INPUT_SIZE as f32 / 2.0;
+                let scale = transform.side / INPUT_SIZE as f32;
+                let (sin, cos) = angle.sin_cos();
+                let points = [
+                    transform.center,
+                    (transform.center.0 + 40.0, transform.center.1 - 25.0),
+                ];
+                // Forward: rotate the offset from the center by -angle.
+                let input: Vec<[f32; 3]> = points
+                    .iter()
+                    .map(|(x, y)| {
+                        let (dx, dy) = (x - transform.center.0, y - transform.center.1);
+                        [
+                            (dx * cos + dy * sin) / scale + half,
+                            (-dx * sin + dy * cos) / scale + half,
+                            0.0,
+                        ]
+                    })
+                    .collect();
+                let projected = project_landmarks_with_transform(&input, &transform);
+                for (projected, expected) in projected.iter().zip(points) {
+                    assert!(
+                        (projected.0 - expected.0).abs() < 1e-2
+                            && (projected.1 - expected.1).abs() < 1e-2,
+                        "{orig_w}x{orig_h} at {angle}: {projected:?} vs {expected:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_crop_of_a_non_square_frame_projects_back_onto_it() {
+        for (orig_w, orig_h) in [(640u32, 480u32), (480, 640)] {
+            let center = (orig_w as f32 * 0.45, orig_h as f32 * 0.55);
+            let block_at = (center.0 as u32 + 30, center.1 as u32 + 20);
+            let block_center = (block_at.0 as f32 + 4.0, block_at.1 as f32 + 4.0);
+            let frame = sized_frame_with_block(orig_w, orig_h, block_at);
+            // Sampled at the input size and at twice it, then averaged down.
+            for crop_size in [INPUT_SIZE, INPUT_SIZE * 2] {
+                let (input, transform) =
+                    prepare_rotated_crop(&frame, center, 200.0, 0.6, INPUT_SIZE, crop_size)
+                        .unwrap();
+                let (x, y) = bright_centroid(&input);
+                let (fx, fy) = transform.project(x, y);
+                // Within about one input pixel of the 200px crop.
+                let tolerance = 1.5 * 200.0 / INPUT_SIZE as f32;
+                assert!(
+                    (fx - block_center.0).abs() <= tolerance
+                        && (fy - block_center.1).abs() <= tolerance,
+                    "{orig_w}x{orig_h} sampled at {crop_size}: ({fx}, {fy})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wrist_depth_is_zero_either_way_round() {
+        for flip in [false, true] {
+            let depths = wrist_relative_depths(&OPEN_HAND, flip);
+            assert_eq!(depths.len(), NUM_LANDMARKS);
+            assert_eq!(depths[0], 0.0);
+            // The middle fingertip sits 8 toward the camera; flipping
+            // negates every depth.
+            let tip = if flip { 8.0 } else { -8.0 };
+            assert_eq!(depths[12], tip);
+        }
+        assert!(wrist_relative_depths(&[], false).is_empty());
+    }
+
+    #[test]
+    fn grayscale_input_repeats_the_luma_and_keeps_the_geometry() {
+        let frame = frame_with_block((400, 200));
+        let (color, color_info) =
+            prepare_frame_with_size(&frame, PALM_INPUT_SIZE, PreprocessMode::Letterbox, false)
+                .unwrap();
+        let (gray, gray_info) =
+            prepare_frame_with_size(&frame, PALM_INPUT_SIZE, PreprocessMode::Letterbox, true)
+                .unwrap();
+        assert_eq!(gray.shape(), color.shape());
+        assert_eq!(
+            (gray_info.scale, gray_info.pad_x, gray_info.pad_y),
+            (color_info.scale, color_info.pad_x, color_info.pad_y)
+        );
+        for pixel in gray.as_slice().unwrap().chunks_exact(3) {
+            assert!(pixel[0] == pixel[1] && pixel[1] == pixel[2]);
+        }
+        assert_eq!(bright_centroid(&gray), bright_centroid(&color));
+        assert_eq!(luma(&[255, 0, 0, 255, 0, 255, 0, 255]), [76, 149]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+/// Aggregates per-frame recognition stats and logs one summary line per
+/// interval, so long-running sessions can be monitored without per-frame
+/// log spam.
+pub struct MetricsLogger {
+    interval: Duration,
+    window_start: Instant,
+    frames: u32,
+    detections: u32,
+    confidence_sum: f32,
+    dropped: u32,
+    errors: u32,
+}
+
+impl MetricsLogger {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            window_start: Instant::now(),
+            frames: 0,
+            detections: 0,
+            confidence_sum: 0.0,
+            dropped: 0,
+            errors: 0,
+        }
+    }
+
+    /// Frames skipped to catch up with the camera.
+    pub fn record_dropped(&mut self, count: usize) {
+        self.dropped += count as u32;
+    }
+
+    pub fn record_frame(&mut self, confidence: f32, detected: bool) {
+        self.frames += 1;
+        if detected {
+            self.detections += 1;
+            self.confidence_sum += confidence;
+        }
+    }
+
+    pub fn record_error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Logs and resets the counters once the interval has elapsed.
+    pub fn maybe_emit(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.window_start);
+        if elapsed < self.interval {
+            return;
+        }
+
+        let fps = self.frames as f32 / elapsed.as_secs_f32().max(f32::EPSILON);
+        let detection_rate = self.detections as f32 / self.frames.max(1) as f32;
+        let avg_confidence = self.confidence_sum / self.detections.max(1) as f32;
+        log::info!(
+            target: "gesture_universe::metrics",
+            "frames={} fps={fps:.1} detection_rate={detection_rate:.2} avg_confidence={avg_confidence:.3} dropped={} errors={}",
+            self.frames,
+            self.dropped,
+            self.errors
+        );
+
+        *self = Self {
+            window_start: now,
+            ..Self::new(self.interval)
+        };
+    }
+}
@@ -1,59 +1,683 @@
-mod common;
+pub(crate) mod common;
+mod metrics;
 mod ort;
 pub(crate) mod palm;
+pub mod providers;
+mod sharpness;
+pub mod telemetry;
+mod watchdog;
 
-use std::{path::PathBuf, thread};
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::Receiver;
 
 use crate::{
-    gesture::GestureClassifier,
-    model_download::{default_handpose_estimator_model_path, default_palm_detector_model_path},
-    types::{Frame, GestureResult, RecognizedFrame},
+    gesture::{ConfidenceFloors, FingerClassifierConfig, GestureClassifier, VoterConfig},
+    model_download::{
+        SessionThreads, default_handpose_estimator_model_path, default_palm_detector_model_path,
+    },
+    pipeline::{
+        channel::{ChannelConfig, FrameSender},
+        gamma::{GammaControl, GammaLut},
+    },
+    types::{Frame, GestureResult, HandResult, RecognizedFrame},
 };
 
-use self::common::HandposeOutput;
+use self::{
+    common::HandposeOutput,
+    metrics::MetricsLogger,
+    telemetry::{MetricsEvent, MetricsSink, NoopMetricsSink},
+};
+pub use self::{
+    common::PreprocessMode,
+    palm::CropConfig,
+    providers::{InferenceProvider, ProviderStatus},
+    watchdog::Heartbeat,
+};
 
 pub(crate) trait HandposeEngine: Send + 'static {
     fn infer(&mut self, frame: &Frame) -> anyhow::Result<HandposeOutput>;
+
+    /// Cheap check used while idling in power-save mode. Engines without a
+    /// faster path report `true` so a full inference runs at the idle rate.
+    fn palm_present(&mut self, _frame: &Frame) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Whether the recognizer saw a hand in its latest frame, shared with the
+/// camera thread so it can drop to a cheaper capture format while idle.
+#[derive(Clone, Debug, Default)]
+pub struct HandActivity {
+    inner: Arc<AtomicBool>,
+}
+
+impl HandActivity {
+    pub fn hand_present(&self) -> bool {
+        self.inner.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, present: bool) {
+        self.inner.store(present, Ordering::Relaxed);
+    }
+}
+
+/// Weight of the newest interval in [`RecognitionRate`]'s EMA.
+const RECOGNITION_RATE_ALPHA: f32 = 0.2;
+
+/// Results per second coming out of the worker, shared with the UI. Unlike
+/// the preview frame rate, which the compositor caps, this drops as soon
+/// as inference slows down, e.g. after a fallback to the CPU provider.
+#[derive(Clone, Debug, Default)]
+pub struct RecognitionRate {
+    bits: Arc<AtomicU32>,
+}
+
+impl RecognitionRate {
+    /// `None` until the second result and while power saving, when a low
+    /// rate is intended.
+    pub fn fps(&self) -> Option<f32> {
+        let fps = f32::from_bits(self.bits.load(Ordering::Relaxed));
+        (fps > 0.0).then_some(fps)
+    }
+
+    /// Counts one result; `last_result` is the time of the previous one and
+    /// is advanced to now.
+    fn tick(&self, last_result: &mut Option<Instant>) {
+        let now = Instant::now();
+        let Some(interval) = last_result
+            .replace(now)
+            .map(|previous| now.saturating_duration_since(previous).as_secs_f32())
+            .filter(|secs| *secs > 0.0)
+        else {
+            return;
+        };
+        let current = 1.0 / interval;
+        let fps = self.fps().map_or(current, |previous| {
+            previous + (current - previous) * RECOGNITION_RATE_ALPHA
+        });
+        self.bits.store(fps.to_bits(), Ordering::Relaxed);
+    }
+
+    fn clear(&self) {
+        self.bits.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Finger-state thresholds handed from the UI to a running worker, e.g.
+/// after a calibration. The worker swaps them in before its next frame.
+#[derive(Clone, Debug, Default)]
+pub struct FingerConfigControl {
+    pending: Arc<Mutex<Option<FingerClassifierConfig>>>,
+}
+
+impl FingerConfigControl {
+    pub fn set(&self, config: FingerClassifierConfig) {
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = Some(config);
+        }
+    }
+
+    fn take(&self) -> Option<FingerClassifierConfig> {
+        self.pending.lock().ok()?.take()
+    }
+}
+
+pub const MIN_DETECTION_THRESHOLD: f32 = 0.05;
+pub const MAX_DETECTION_THRESHOLD: f32 = 0.95;
+
+/// Minimum palm detector score for a hand to count, shared between the UI
+/// and the worker, which reads it before every palm detection. Lower values
+/// find hands in harder conditions at the cost of more false positives.
+#[derive(Clone, Debug)]
+pub struct DetectionThreshold {
+    bits: Arc<AtomicU32>,
+}
+
+impl DetectionThreshold {
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, threshold: f32) {
+        let threshold = threshold.clamp(MIN_DETECTION_THRESHOLD, MAX_DETECTION_THRESHOLD);
+        self.bits.store(threshold.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl Default for DetectionThreshold {
+    fn default() -> Self {
+        Self {
+            bits: Arc::new(AtomicU32::new(
+                palm::PalmDetectorConfig::default()
+                    .score_threshold
+                    .to_bits(),
+            )),
+        }
+    }
+}
+
+/// Throttles inference after a stretch without hands, for battery-powered
+/// machines left running unattended.
+#[derive(Clone, Copy, Debug)]
+pub struct PowerSaveConfig {
+    /// Time without a detected hand before switching to the idle rate.
+    pub idle_timeout: Duration,
+    /// Palm checks per second while idle.
+    pub idle_fps: f32,
+}
+
+impl PowerSaveConfig {
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.idle_fps.max(0.01))
+    }
+}
+
+impl Default for PowerSaveConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(10),
+            idle_fps: 1.0,
+        }
+    }
+}
+
+/// Power-save setting shared between the UI and the worker, which reads it
+/// before every frame. `None`, the default, keeps inference at full rate.
+#[derive(Clone, Debug, Default)]
+pub struct PowerSave {
+    config: Arc<Mutex<Option<PowerSaveConfig>>>,
 }
 
-fn run_worker_loop<E: HandposeEngine>(
-    mut engine: E,
+impl PowerSave {
+    pub fn get(&self) -> Option<PowerSaveConfig> {
+        self.config.lock().ok().and_then(|config| *config)
+    }
+
+    pub fn set(&self, config: Option<PowerSaveConfig>) {
+        if let Ok(mut current) = self.config.lock() {
+            *current = config;
+        }
+    }
+}
+
+/// What the worker does with a frame under [`PowerSave`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IdleStep {
+    /// Full recognition.
+    Run,
+    /// Idle, and a palm check is due.
+    Poll,
+    /// Idle between palm checks; the frame is passed through untouched so
+    /// the preview keeps its normal frame rate.
+    Skip,
+}
+
+/// The worker's power-save state: idle once no hand has been seen for the
+/// configured timeout, then polling for a palm at the idle rate until one
+/// shows up.
+struct IdleState {
+    last_hand_seen: Instant,
+    last_idle_poll: Option<Instant>,
+}
+
+impl IdleState {
+    fn new(now: Instant) -> Self {
+        Self {
+            last_hand_seen: now,
+            last_idle_poll: None,
+        }
+    }
+
+    fn step(&mut self, config: Option<PowerSaveConfig>, now: Instant) -> IdleStep {
+        let Some(config) = config else {
+            // Turning power save on later starts the timeout afresh instead
+            // of idling straight away.
+            self.last_hand_seen = now;
+            self.last_idle_poll = None;
+            return IdleStep::Run;
+        };
+        if now.saturating_duration_since(self.last_hand_seen) < config.idle_timeout {
+            return IdleStep::Run;
+        }
+        if self.last_idle_poll.is_none() {
+            log::info!(
+                "no hand for {:?}, entering power-save mode",
+                config.idle_timeout
+            );
+        }
+        let poll_due = self
+            .last_idle_poll
+            .is_none_or(|at| now.saturating_duration_since(at) >= config.poll_interval());
+        if poll_due {
+            self.last_idle_poll = Some(now);
+            IdleStep::Poll
+        } else {
+            IdleStep::Skip
+        }
+    }
+
+    fn hand_seen(&mut self, now: Instant) {
+        if self.last_idle_poll.take().is_some() {
+            log::info!("hand detected, leaving power-save mode");
+        }
+        self.last_hand_seen = now;
+    }
+}
+
+/// Caller-supplied transform, e.g. background subtraction or skin-color
+/// masking, applied to each frame before palm detection.
+///
+/// The hook runs on the recognizer worker thread for every processed frame,
+/// so it should be cheap; slow hooks lower the recognition frame rate. It
+/// only sees the recognizer's copy of the frame: the preview is unaffected.
+#[derive(Clone)]
+pub struct PreprocessHook(Arc<dyn Fn(&mut Frame) + Send + Sync>);
+
+impl PreprocessHook {
+    pub fn new(hook: impl Fn(&mut Frame) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    /// Blacks out `[x1, y1, x2, y2]`, given as fractions of the frame, so a
+    /// busy background such as a TV or poster there can't produce palms.
+    pub fn black_out(region: [f32; 4]) -> Self {
+        Self::new(move |frame| {
+            let scale = |fraction: f32, side: u32| (fraction * side as f32).round() as usize;
+            let width = frame.width as usize;
+            let (x1, x2) = (scale(region[0], frame.width), scale(region[2], frame.width));
+            let (y1, y2) = (
+                scale(region[1], frame.height),
+                scale(region[3], frame.height),
+            );
+            for row in frame.rgba.chunks_exact_mut(width * 4).take(y2).skip(y1) {
+                for pixel in row[x1 * 4..x2 * 4].chunks_exact_mut(4) {
+                    pixel[..3].fill(0);
+                }
+            }
+        })
+    }
+
+    fn apply(&self, frame: &mut Frame) {
+        (self.0)(frame)
+    }
+}
+
+impl fmt::Debug for PreprocessHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PreprocessHook")
+    }
+}
+
+/// Exponential moving average over projected landmarks. Restarts from the
+/// raw points whenever the hand is lost, so a new hand never slides in from
+/// where the previous one left.
+struct LandmarkSmoother {
+    alpha: f32,
+    state: Option<Vec<(f32, f32)>>,
+}
+
+impl LandmarkSmoother {
+    fn new(alpha: f32) -> Self {
+        Self { alpha, state: None }
+    }
+
+    fn update(&mut self, landmarks: Option<&[(f32, f32)]>) -> Option<Vec<(f32, f32)>> {
+        let Some(landmarks) = landmarks else {
+            self.state = None;
+            return None;
+        };
+        let smoothed = match self.state.take() {
+            Some(previous) if previous.len() == landmarks.len() => previous
+                .iter()
+                .zip(landmarks)
+                .map(|(prev, next)| {
+                    (
+                        prev.0 + (next.0 - prev.0) * self.alpha,
+                        prev.1 + (next.1 - prev.1) * self.alpha,
+                    )
+                })
+                .collect(),
+            _ => landmarks.to_vec(),
+        };
+        self.state = Some(smoothed.clone());
+        Some(smoothed)
+    }
+}
+
+/// The recognition stack without threads or channels: gamma correction, the
+/// preprocess hook, palm detection, handpose and gesture classification run
+/// inline on the caller's thread. The worker thread wraps one of these; use
+/// it directly for deterministic tests and batch tools.
+pub struct Recognizer {
+    engine: Box<dyn HandposeEngine>,
+    classifier: GestureClassifier,
+    gamma: GammaControl,
+    gamma_lut: Option<GammaLut>,
+    preprocess: Option<PreprocessHook>,
+    finger_control: FingerConfigControl,
+    smoother: Option<LandmarkSmoother>,
+    flip_landmark_z: bool,
+}
+
+impl Recognizer {
+    /// Loads the models described by `backend`, downloading them if needed.
+    pub fn new(backend: &RecognizerBackend) -> anyhow::Result<Self> {
+        let engine = ort::load_engine(backend)?;
+        Ok(Self::with_engine(
+            Box::new(engine),
+            backend.build_classifier(),
+            backend,
+        ))
+    }
+
+    fn with_engine(
+        engine: Box<dyn HandposeEngine>,
+        classifier: GestureClassifier,
+        backend: &RecognizerBackend,
+    ) -> Self {
+        Self {
+            engine,
+            classifier,
+            gamma: backend.gamma(),
+            gamma_lut: None,
+            preprocess: backend.preprocess(),
+            finger_control: backend.finger_control(),
+            smoother: backend.landmark_smoothing().map(LandmarkSmoother::new),
+            flip_landmark_z: backend.flip_landmark_z(),
+        }
+    }
+
+    /// Runs one frame through the whole stack. Results depend on earlier
+    /// frames (tracking, voting, motion) through `frame.timestamp`, so space
+    /// timestamps apart to recognize unrelated images independently.
+    pub fn process(&mut self, frame: &Frame) -> anyhow::Result<GestureResult> {
+        let input = (self.gamma.active_gamma().is_some() || self.preprocess.is_some()).then(|| {
+            let mut copy = frame.clone();
+            self.prepare_input(&mut copy).unwrap_or(copy)
+        });
+        self.recognize(input.as_ref().unwrap_or(frame))
+    }
+
+    /// Applies gamma correction and the preprocess hook. Returns the frame to
+    /// run inference on when it differs from `frame`; when gamma is applied
+    /// to the preview as well, `frame` itself is corrected in place.
+    fn prepare_input(&mut self, frame: &mut Frame) -> Option<Frame> {
+        let mut corrected = match self.gamma.active_gamma() {
+            Some(value) => {
+                let lut = match self.gamma_lut.take() {
+                    Some(lut) if lut.gamma() == value => lut,
+                    _ => GammaLut::new(value),
+                };
+                let lut = self.gamma_lut.insert(lut);
+                if self.gamma.apply_to_preview() {
+                    lut.apply(&mut frame.rgba);
+                    None
+                } else {
+                    let mut copy = frame.clone();
+                    lut.apply(&mut copy.rgba);
+                    Some(copy)
+                }
+            }
+            None => None,
+        };
+        if let Some(hook) = &self.preprocess {
+            hook.apply(corrected.get_or_insert_with(|| frame.clone()));
+        }
+        corrected
+    }
+
+    /// Inference and classification on an already prepared frame.
+    fn recognize(&mut self, input: &Frame) -> anyhow::Result<GestureResult> {
+        if let Some(config) = self.finger_control.take() {
+            self.classifier.set_finger_config(config);
+        }
+        let output = self.engine.infer(input)?;
+        let mut result = build_gesture_result(output, input, &mut self.classifier);
+        if let Some(smoother) = &mut self.smoother {
+            result.smoothed_landmarks = smoother.update(result.landmarks.as_deref());
+        }
+        result.landmark_depths = result
+            .raw_landmarks
+            .as_deref()
+            .map(|raw| common::wrist_relative_depths(raw, self.flip_landmark_z));
+        Ok(result)
+    }
+
+    fn palm_present(&mut self, frame: &Frame) -> anyhow::Result<bool> {
+        self.engine.palm_present(frame)
+    }
+}
+
+fn run_worker_loop(
+    mut recognizer: Recognizer,
     frame_rx: Receiver<Frame>,
-    result_tx: Sender<RecognizedFrame>,
+    result_tx: FrameSender<RecognizedFrame>,
+    backend: &RecognizerBackend,
 ) {
-    let mut classifier = GestureClassifier::new();
+    // A lossless input channel means the producer wants every frame
+    // processed, so skip the catch-up drain.
+    let lossless_input = backend.frame_channel().is_lossless();
+    let power_save = backend.power_save();
+    let mut metrics = backend.metrics_interval().map(MetricsLogger::new);
+    let sink = backend.metrics_sink();
+    let min_sharpness = backend.min_sharpness();
+    let hand_activity = backend.hand_activity();
+    let recognition_rate = backend.recognition_rate();
+    let heartbeat = backend.heartbeat();
+    let _watchdog = backend
+        .watchdog_timeout()
+        .map(|timeout| watchdog::spawn_watchdog(heartbeat.clone(), timeout));
+    let mut last_result: Option<Instant> = None;
+    let mut blur_replay = sharpness::BlurReplay::default();
+    let mut idle = IdleState::new(Instant::now());
+
+    loop {
+        heartbeat.finish_frame();
+        let next = if lossless_input {
+            frame_rx.recv().ok().map(|frame| (frame, 0))
+        } else {
+            recv_latest_frame(&frame_rx)
+        };
+        let Some((mut frame, skipped)) = next else {
+            break;
+        };
+        heartbeat.start_frame();
+        if let Some(metrics) = metrics.as_mut() {
+            metrics.record_dropped(skipped);
+        }
+        if skipped > 0 {
+            sink.record(&MetricsEvent::FramesDropped { count: skipped });
+        }
+        if frame.is_too_small() {
+            log::warn!("skipping {}x{} frame", frame.width, frame.height);
+            continue;
+        }
 
-    while let Some(frame) = recv_latest_frame(&frame_rx) {
-        match engine.infer(&frame) {
-            Ok(output) => {
-                let gesture = build_gesture_result(output, &frame, &mut classifier);
+        let corrected = recognizer.prepare_input(&mut frame);
+        let input = corrected.as_ref().unwrap_or(&frame);
+        // Checked after the preprocess hook, which may resize. Nothing is sent
+        // for a malformed frame, so the UI keeps showing the previous result
+        // until a well-formed one arrives.
+        if let Err(err) = common::check_frame_buffer(input) {
+            log::debug!("skipping frame {}: {err}", frame.sequence);
+            continue;
+        }
+
+        let step = idle.step(power_save.get(), Instant::now());
+        if step != IdleStep::Run {
+            let palm_found = step == IdleStep::Poll
+                && recognizer.palm_present(input).unwrap_or_else(|err| {
+                    log::warn!("idle palm check failed: {err:?}");
+                    false
+                });
+            if !palm_found {
+                hand_activity.set(false);
+                recognition_rate.clear();
+                last_result = None;
+                let result = power_save_result(&frame);
+                if !result_tx.send(RecognizedFrame { frame, result }) {
+                    break;
+                }
+                continue;
+            }
+        }
+
+        if let Some(threshold) = min_sharpness {
+            let sharpness = sharpness::laplacian_variance(input);
+            log::trace!("frame sharpness {sharpness:.1}");
+            if let Some(previous) = blur_replay.replay(sharpness, threshold, frame.timestamp) {
+                let result = GestureResult {
+                    timestamp: frame.timestamp,
+                    sequence: frame.sequence,
+                    ..previous.clone()
+                };
+                recognition_rate.tick(&mut last_result);
+                if !result_tx.send(RecognizedFrame { frame, result }) {
+                    break;
+                }
+                continue;
+            }
+        }
+
+        let started = Instant::now();
+        sink.record(&MetricsEvent::InferenceStarted { at: started });
+        match recognizer.recognize(input) {
+            Ok(gesture) => {
+                sink.record(&MetricsEvent::InferenceFinished {
+                    elapsed: started.elapsed(),
+                    hand_detected: gesture.landmarks.is_some(),
+                });
+                if let Some(detail) = &gesture.detail {
+                    sink.record(&MetricsEvent::GestureRecognized {
+                        gesture: detail.primary,
+                        confidence: gesture.confidence,
+                    });
+                }
+                hand_activity.set(gesture.landmarks.is_some());
+                recognition_rate.tick(&mut last_result);
+                if gesture.landmarks.is_some() {
+                    idle.hand_seen(Instant::now());
+                }
+                if let Some(metrics) = metrics.as_mut() {
+                    metrics.record_frame(gesture.confidence, gesture.landmarks.is_some());
+                }
+                if min_sharpness.is_some() {
+                    blur_replay.record(&gesture, frame.timestamp);
+                }
                 let recognized = RecognizedFrame {
                     frame,
                     result: gesture,
                 };
-                let _ = result_tx.try_send(recognized);
+                if !result_tx.send(recognized) {
+                    break;
+                }
             }
             Err(err) => {
                 log::warn!("handpose inference failed: {err:?}");
+                sink.record(&MetricsEvent::Error { error: &err });
+                if let Some(metrics) = metrics.as_mut() {
+                    metrics.record_error();
+                }
             }
         }
+
+        if let Some(metrics) = metrics.as_mut() {
+            metrics.maybe_emit(Instant::now());
+        }
     }
 }
 
-fn recv_latest_frame(frame_rx: &Receiver<Frame>) -> Option<Frame> {
+/// Newest queued frame and how many older ones were skipped to reach it.
+fn recv_latest_frame(frame_rx: &Receiver<Frame>) -> Option<(Frame, usize)> {
     let mut frame = frame_rx.recv().ok()?;
+    let mut skipped = 0;
     while let Ok(newer) = frame_rx.try_recv() {
         frame = newer;
+        skipped += 1;
     }
-    Some(frame)
+    Some((frame, skipped))
 }
 
+/// Most hands recognized per frame, shared between the UI and the worker,
+/// which reads it before every palm detection. The best palm is tracked and
+/// drives `detail`; the rest are reported in `other_hands`. One hand skips
+/// the pairwise NMS pass and the extra handpose runs.
+#[derive(Clone, Debug)]
+pub struct MaxHands {
+    count: Arc<AtomicUsize>,
+}
+
+impl MaxHands {
+    pub fn get(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// At least one hand is always recognized.
+    pub fn set(&self, count: usize) {
+        self.count.store(count.max(1), Ordering::Relaxed);
+    }
+}
+
+impl Default for MaxHands {
+    fn default() -> Self {
+        Self {
+            count: Arc::new(AtomicUsize::new(1)),
+        }
+    }
+}
+
+/// Long enough to bridge a missed frame or two at 30fps without leaving a
+/// stale skeleton behind once the hand is really gone.
+const DEFAULT_SKELETON_HOLD: Duration = Duration::from_millis(150);
+
 #[derive(Clone, Debug)]
 pub struct RecognizerBackend {
     handpose_estimator_model_path: PathBuf,
     palm_detector_model_path: PathBuf,
+    crop: CropConfig,
+    skeleton_hold: Duration,
+    landmark_smoothing: Option<f32>,
+    flip_landmark_z: bool,
+    max_hands: MaxHands,
+    handpose_input_size: Option<u32>,
+    crop_output_size: Option<u32>,
+    skip_palm_detection: bool,
+    palm_preprocess: PreprocessMode,
+    palm_grayscale: bool,
+    palm_keypoint_separation: Option<f32>,
+    working_resolution: Option<u32>,
+    prefer_embedded_models: bool,
+    confidence_floors: ConfidenceFloors,
+    voter: VoterConfig,
+    finger_config: FingerClassifierConfig,
+    enable_motion: bool,
+    metrics_interval: Option<Duration>,
+    min_sharpness: Option<f32>,
+    metrics_sink: Arc<dyn MetricsSink>,
+    preprocess: Option<PreprocessHook>,
+    power_save: PowerSave,
+    gamma: GammaControl,
+    providers: Arc<[InferenceProvider]>,
+    session_threads: SessionThreads,
+    provider_status: ProviderStatus,
+    hand_activity: HandActivity,
+    recognition_rate: RecognitionRate,
+    heartbeat: Heartbeat,
+    watchdog_timeout: Option<Duration>,
+    finger_control: FingerConfigControl,
+    detection_threshold: DetectionThreshold,
+    frame_channel: ChannelConfig,
+    result_channel: ChannelConfig,
 }
 
 impl RecognizerBackend {
@@ -65,6 +689,359 @@ impl RecognizerBackend {
         self.palm_detector_model_path.clone()
     }
 
+    /// Handpose crop geometry, shared with the compositor's debug overlay.
+    pub fn crop(&self) -> CropConfig {
+        self.crop
+    }
+
+    pub fn with_crop_expansion(mut self, crop_expansion: f32) -> Self {
+        self.crop.expansion = crop_expansion;
+        self
+    }
+
+    /// Moves the crop center along the wrist → fingers axis by this fraction
+    /// of the palm box height; negative values move towards the fingers.
+    pub fn with_crop_shift(mut self, crop_shift: f32) -> Self {
+        self.crop.shift = crop_shift;
+        self
+    }
+
+    /// How long the compositor keeps drawing the last skeleton, dimmed, after
+    /// the hand is lost. Zero disables the hold.
+    pub fn skeleton_hold(&self) -> Duration {
+        self.skeleton_hold
+    }
+
+    pub fn with_skeleton_hold(mut self, hold: Duration) -> Self {
+        self.skeleton_hold = hold;
+        self
+    }
+
+    /// Weight of the newest frame in the landmark EMA behind
+    /// `GestureResult::smoothed_landmarks`, in `(0, 1]`. `None` skips
+    /// smoothing and leaves the field empty.
+    pub fn landmark_smoothing(&self) -> Option<f32> {
+        self.landmark_smoothing
+    }
+
+    pub fn with_landmark_smoothing(mut self, alpha: Option<f32>) -> Self {
+        self.landmark_smoothing = alpha.map(|alpha| alpha.clamp(0.01, 1.0));
+        self
+    }
+
+    /// Negate `GestureResult::landmark_depths`, for handpose models whose
+    /// `z` grows toward the camera instead of away from it.
+    pub fn flip_landmark_z(&self) -> bool {
+        self.flip_landmark_z
+    }
+
+    pub fn with_flip_landmark_z(mut self, flip: bool) -> Self {
+        self.flip_landmark_z = flip;
+        self
+    }
+
+    /// Number of hands recognized per frame, adjustable while the worker
+    /// runs.
+    pub fn max_hands(&self) -> MaxHands {
+        self.max_hands.clone()
+    }
+
+    /// Handpose input side in pixels. `None` reads it from the model, falling
+    /// back to 224 when the model's input shape is dynamic.
+    pub fn handpose_input_size(&self) -> Option<u32> {
+        self.handpose_input_size
+    }
+
+    pub fn with_handpose_input_size(mut self, size: u32) -> Self {
+        self.handpose_input_size = Some(size);
+        self
+    }
+
+    /// Side the handpose crop is sampled at before being resized to the
+    /// model input. Larger values capture more detail of small hands at a
+    /// higher sampling cost; values below the input size are raised to it.
+    /// `None` samples at the input size directly.
+    pub fn crop_output_size(&self) -> Option<u32> {
+        self.crop_output_size
+    }
+
+    pub fn with_crop_output_size(mut self, size: Option<u32>) -> Self {
+        self.crop_output_size = size;
+        self
+    }
+
+    /// Feed the whole frame to the handpose model instead of detecting and
+    /// cropping the palm first. Faster for close-up cameras where the hand
+    /// already fills the frame.
+    pub fn skip_palm_detection(&self) -> bool {
+        self.skip_palm_detection
+    }
+
+    pub fn with_skip_palm_detection(mut self, skip: bool) -> Self {
+        self.skip_palm_detection = skip;
+        self
+    }
+
+    /// How frames are fitted to the palm detector input.
+    pub fn palm_preprocess(&self) -> PreprocessMode {
+        self.palm_preprocess
+    }
+
+    pub fn with_palm_preprocess(mut self, mode: PreprocessMode) -> Self {
+        self.palm_preprocess = mode;
+        self
+    }
+
+    /// Whether the palm detector gets a grayscale copy of the frame; the
+    /// handpose model always sees color.
+    pub fn palm_grayscale(&self) -> bool {
+        self.palm_grayscale
+    }
+
+    pub fn with_palm_grayscale(mut self, grayscale: bool) -> Self {
+        self.palm_grayscale = grayscale;
+        self
+    }
+
+    /// Keep a palm box that overlaps a better one if their palm keypoints
+    /// are at least this far apart, relative to the box size. Lets two hands
+    /// held close together both be detected; `None` suppresses by box
+    /// overlap alone.
+    pub fn palm_keypoint_separation(&self) -> Option<f32> {
+        self.palm_keypoint_separation
+    }
+
+    pub fn with_palm_keypoint_separation(mut self, separation: Option<f32>) -> Self {
+        self.palm_keypoint_separation = separation;
+        self
+    }
+
+    /// Cap on the longer side of the frame used for palm detection and the
+    /// handpose crop. Larger camera frames are downscaled once per frame and
+    /// the landmarks are mapped back to full resolution for the overlay.
+    ///
+    /// Off by default: the downscale only pays for itself well above the
+    /// cap. `examples/working_resolution_bench.rs` timed the preprocessing
+    /// at 20 ms per 1080p frame, 33 ms with a 1280 cap; at 4K it went from
+    /// 80 ms to 53 ms with that cap and 45 ms with a 960 one.
+    pub fn working_resolution(&self) -> Option<u32> {
+        self.working_resolution
+    }
+
+    pub fn with_working_resolution(mut self, max_side: Option<u32>) -> Self {
+        self.working_resolution = max_side;
+        self
+    }
+
+    /// Load models compiled in by the `embed-models` feature instead of the
+    /// files above. Has no effect when the feature is off.
+    pub fn prefer_embedded_models(&self) -> bool {
+        self.prefer_embedded_models
+    }
+
+    pub fn with_prefer_embedded_models(mut self, prefer: bool) -> Self {
+        self.prefer_embedded_models = prefer;
+        self
+    }
+
+    /// Per-gesture minimum probabilities applied by the classifier.
+    pub fn confidence_floors(&self) -> ConfidenceFloors {
+        self.confidence_floors.clone()
+    }
+
+    pub fn with_confidence_floors(mut self, floors: ConfidenceFloors) -> Self {
+        self.confidence_floors = floors;
+        self
+    }
+
+    /// Temporal voting window and decay used to settle the gesture label.
+    pub fn voter(&self) -> VoterConfig {
+        self.voter
+    }
+
+    pub fn with_voter(mut self, config: VoterConfig) -> Self {
+        self.voter = config;
+        self
+    }
+
+    /// Thresholds for the per-finger extended/half-bent/folded states, and
+    /// what "恢复默认" on the calibration screen goes back to.
+    pub fn finger_config(&self) -> FingerClassifierConfig {
+        self.finger_config
+    }
+
+    pub fn with_finger_config(mut self, config: FingerClassifierConfig) -> Self {
+        self.finger_config = config;
+        self
+    }
+
+    /// Whether the classifier tracks wrist motion (waving, fanning). Off
+    /// saves the per-frame history work for static-gesture uses; motion is
+    /// then always reported as steady.
+    pub fn enable_motion(&self) -> bool {
+        self.enable_motion
+    }
+
+    pub fn with_enable_motion(mut self, enabled: bool) -> Self {
+        self.enable_motion = enabled;
+        self
+    }
+
+    /// Period of the aggregate stats line logged under the
+    /// `gesture_universe::metrics` target. `None` disables collection.
+    pub fn metrics_interval(&self) -> Option<Duration> {
+        self.metrics_interval
+    }
+
+    pub fn with_metrics_interval(mut self, interval: Option<Duration>) -> Self {
+        self.metrics_interval = interval.filter(|interval| !interval.is_zero());
+        self
+    }
+
+    /// Laplacian-variance floor below which a frame counts as too blurry to
+    /// recognize; the last sharp result with a hand is repeated instead, for
+    /// up to [`sharpness::MAX_BLUR_REPLAY_AGE`]. `None` (the default) runs
+    /// inference on every frame.
+    pub fn min_sharpness(&self) -> Option<f32> {
+        self.min_sharpness
+    }
+
+    pub fn with_min_sharpness(mut self, threshold: Option<f32>) -> Self {
+        self.min_sharpness = threshold;
+        self
+    }
+
+    /// Receiver for per-frame recognizer events. Defaults to a no-op.
+    pub fn metrics_sink(&self) -> Arc<dyn MetricsSink> {
+        self.metrics_sink.clone()
+    }
+
+    pub fn with_metrics_sink(mut self, sink: Arc<dyn MetricsSink>) -> Self {
+        self.metrics_sink = sink;
+        self
+    }
+
+    /// Hook applied to the recognizer's copy of each frame. `None` by default.
+    pub fn preprocess(&self) -> Option<PreprocessHook> {
+        self.preprocess.clone()
+    }
+
+    pub fn with_preprocess(mut self, hook: Option<PreprocessHook>) -> Self {
+        self.preprocess = hook;
+        self
+    }
+
+    /// Idle throttling applied after no hand has been seen for a while,
+    /// adjustable while the worker runs. Off by default.
+    pub fn power_save(&self) -> PowerSave {
+        self.power_save.clone()
+    }
+
+    /// Classifier configured with this backend's floors and voting window.
+    /// Must be called on the worker thread since it loads the ONNX model.
+    pub fn build_classifier(&self) -> GestureClassifier {
+        GestureClassifier::new(self.session_threads(), self.prefer_embedded_models())
+            .with_confidence_floors(self.confidence_floors())
+            .with_voter(self.voter())
+            .with_finger_config(self.finger_config())
+            .with_motion(self.enable_motion())
+    }
+
+    /// Low-light gamma correction shared between the UI and the worker.
+    pub fn gamma(&self) -> GammaControl {
+        self.gamma.clone()
+    }
+
+    /// Execution providers tried in order for the handpose and palm sessions;
+    /// unavailable ones are skipped and CPU is the final fallback.
+    pub fn providers(&self) -> Arc<[InferenceProvider]> {
+        self.providers.clone()
+    }
+
+    pub fn with_providers(mut self, providers: Vec<InferenceProvider>) -> Self {
+        self.providers = providers.into();
+        self
+    }
+
+    /// ONNX Runtime thread counts for the handpose, palm and classifier
+    /// sessions. Defaults to half the available cores, capped at four.
+    pub fn session_threads(&self) -> SessionThreads {
+        self.session_threads
+    }
+
+    pub fn with_session_threads(mut self, threads: SessionThreads) -> Self {
+        self.session_threads = threads;
+        self
+    }
+
+    /// Provider the running worker actually bound, for display.
+    pub fn provider_status(&self) -> ProviderStatus {
+        self.provider_status.clone()
+    }
+
+    /// Hand presence as last seen by the worker; drives the camera's idle
+    /// capture format.
+    pub fn hand_activity(&self) -> HandActivity {
+        self.hand_activity.clone()
+    }
+
+    /// Results per second as measured by the worker.
+    pub fn recognition_rate(&self) -> RecognitionRate {
+        self.recognition_rate.clone()
+    }
+
+    /// Worker liveness; only reports stalls while a
+    /// [`watchdog_timeout`](Self::watchdog_timeout) is set.
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    /// How long the worker may spend on one frame before the watchdog logs
+    /// a warning and flags it as stalled. `None` runs no watchdog.
+    pub fn watchdog_timeout(&self) -> Option<Duration> {
+        self.watchdog_timeout
+    }
+
+    pub fn with_watchdog_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.watchdog_timeout = timeout;
+        self
+    }
+
+    /// Replaces the finger thresholds of the running classifier; the
+    /// configured [`finger_config`](Self::finger_config) applies until then.
+    pub fn finger_control(&self) -> FingerConfigControl {
+        self.finger_control.clone()
+    }
+
+    /// Palm detection score threshold, adjustable while the worker runs.
+    /// Has no effect with [`skip_palm_detection`](Self::skip_palm_detection).
+    pub fn detection_threshold(&self) -> DetectionThreshold {
+        self.detection_threshold.clone()
+    }
+
+    /// Camera-to-recognizer channel. With [`OverflowPolicy::Block`] the
+    /// worker also stops skipping to the newest frame.
+    ///
+    /// [`OverflowPolicy::Block`]: crate::pipeline::channel::OverflowPolicy::Block
+    pub fn frame_channel(&self) -> ChannelConfig {
+        self.frame_channel
+    }
+
+    pub fn with_frame_channel(mut self, config: ChannelConfig) -> Self {
+        self.frame_channel = config;
+        self
+    }
+
+    /// Recognizer-to-consumer channel for [`RecognizedFrame`]s.
+    pub fn result_channel(&self) -> ChannelConfig {
+        self.result_channel
+    }
+
+    pub fn with_result_channel(mut self, config: ChannelConfig) -> Self {
+        self.result_channel = config;
+        self
+    }
+
     pub fn backend_label(&self) -> &'static str {
         "ort"
     }
@@ -75,6 +1052,40 @@ impl Default for RecognizerBackend {
         RecognizerBackend {
             handpose_estimator_model_path: default_handpose_estimator_model_path(),
             palm_detector_model_path: default_palm_detector_model_path(),
+            crop: CropConfig::default(),
+            skeleton_hold: DEFAULT_SKELETON_HOLD,
+            landmark_smoothing: None,
+            flip_landmark_z: false,
+            max_hands: MaxHands::default(),
+            handpose_input_size: None,
+            crop_output_size: None,
+            skip_palm_detection: false,
+            palm_preprocess: PreprocessMode::Letterbox,
+            palm_grayscale: false,
+            palm_keypoint_separation: None,
+            working_resolution: None,
+            prefer_embedded_models: cfg!(feature = "embed-models"),
+            confidence_floors: ConfidenceFloors::default(),
+            voter: VoterConfig::default(),
+            finger_config: FingerClassifierConfig::default(),
+            enable_motion: true,
+            metrics_interval: None,
+            min_sharpness: None,
+            metrics_sink: Arc::new(NoopMetricsSink),
+            preprocess: None,
+            power_save: PowerSave::default(),
+            gamma: GammaControl::default(),
+            providers: providers::DEFAULT_PROVIDER_PRIORITY.into(),
+            session_threads: SessionThreads::default(),
+            provider_status: ProviderStatus::default(),
+            hand_activity: HandActivity::default(),
+            recognition_rate: RecognitionRate::default(),
+            heartbeat: Heartbeat::default(),
+            watchdog_timeout: None,
+            finger_control: FingerConfigControl::default(),
+            detection_threshold: DetectionThreshold::default(),
+            frame_channel: ChannelConfig::latest_only(),
+            result_channel: ChannelConfig::latest_only(),
         }
     }
 }
@@ -82,13 +1093,32 @@ impl Default for RecognizerBackend {
 pub fn start_recognizer(
     backend: RecognizerBackend,
     frame_rx: Receiver<Frame>,
-    result_tx: Sender<RecognizedFrame>,
+    result_tx: FrameSender<RecognizedFrame>,
 ) -> thread::JoinHandle<()> {
     log::info!("starting handpose backend: {}", backend.backend_label());
 
     ort::start_worker(backend, frame_rx, result_tx)
 }
 
+fn power_save_result(frame: &Frame) -> GestureResult {
+    GestureResult {
+        label: "节能模式 — 未检测到手".to_string(),
+        confidence: 0.0,
+        palm_score: None,
+        handpose_confidence: 0.0,
+        timestamp: frame.timestamp,
+        sequence: frame.sequence,
+        landmarks: None,
+        smoothed_landmarks: None,
+        raw_landmarks: None,
+        landmark_depths: None,
+        detail: None,
+        palm_regions: Vec::new(),
+        other_hands: Vec::new(),
+        power_saving: true,
+    }
+}
+
 pub(crate) fn build_gesture_result(
     output: HandposeOutput,
     frame: &Frame,
@@ -107,6 +1137,24 @@ pub(crate) fn build_gesture_result(
         None
     };
 
+    let other_hands = output
+        .secondary_hands
+        .into_iter()
+        .filter_map(|hand| {
+            let detail = classifier.classify_snapshot(
+                &hand.raw_landmarks,
+                &hand.projected_landmarks,
+                hand.confidence,
+                hand.handedness,
+            )?;
+            Some(HandResult {
+                detail,
+                confidence: hand.confidence,
+                landmarks: hand.projected_landmarks,
+            })
+        })
+        .collect();
+
     let label = detail
         .as_ref()
         .map(|d| format!("{}{}", d.primary.emoji(), d.primary.display_name()))
@@ -121,13 +1169,150 @@ pub(crate) fn build_gesture_result(
     GestureResult {
         label,
         confidence: output.confidence,
+        palm_score: output.palm_score,
+        handpose_confidence: output.handpose_confidence,
         timestamp: frame.timestamp,
+        sequence: frame.sequence,
         landmarks: if has_detection {
             Some(output.projected_landmarks)
         } else {
             None
         },
+        smoothed_landmarks: None,
+        raw_landmarks: if has_detection {
+            Some(output.raw_landmarks)
+        } else {
+            None
+        },
+        landmark_depths: None,
         detail,
         palm_regions: output.palm_regions,
+        other_hands,
+        power_saving: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: PowerSaveConfig = PowerSaveConfig {
+        idle_timeout: Duration::from_secs(10),
+        idle_fps: 2.0,
+    };
+
+    fn secs(base: Instant, offset: f32) -> Instant {
+        base + Duration::from_secs_f32(offset)
+    }
+
+    #[test]
+    fn power_save_is_off_by_default() {
+        assert!(RecognizerBackend::default().power_save().get().is_none());
+        let base = Instant::now();
+        let mut idle = IdleState::new(base);
+        assert_eq!(idle.step(None, secs(base, 3600.0)), IdleStep::Run);
+    }
+
+    #[test]
+    fn idles_after_the_timeout_and_polls_at_the_idle_rate() {
+        let base = Instant::now();
+        let mut idle = IdleState::new(base);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 9.9)), IdleStep::Run);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 10.0)), IdleStep::Poll);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 10.2)), IdleStep::Skip);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 10.5)), IdleStep::Poll);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 10.6)), IdleStep::Skip);
+    }
+
+    #[test]
+    fn a_hand_wakes_the_worker_until_the_next_timeout() {
+        let base = Instant::now();
+        let mut idle = IdleState::new(base);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 10.0)), IdleStep::Poll);
+        idle.hand_seen(secs(base, 10.0));
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 10.1)), IdleStep::Run);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 19.9)), IdleStep::Run);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 20.0)), IdleStep::Poll);
+    }
+
+    #[test]
+    fn enabling_power_save_starts_the_timeout_afresh() {
+        let base = Instant::now();
+        let mut idle = IdleState::new(base);
+        assert_eq!(idle.step(None, secs(base, 60.0)), IdleStep::Run);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 61.0)), IdleStep::Run);
+        assert_eq!(idle.step(Some(CONFIG), secs(base, 70.0)), IdleStep::Poll);
+        // Turning it off mid-idle resumes full recognition.
+        assert_eq!(idle.step(None, secs(base, 70.1)), IdleStep::Run);
+    }
+
+    /// Finds a "palm" wherever the frame has a lit pixel.
+    struct BrightnessEngine;
+
+    impl HandposeEngine for BrightnessEngine {
+        fn infer(&mut self, frame: &Frame) -> anyhow::Result<HandposeOutput> {
+            let lit = frame
+                .rgba
+                .chunks_exact(4)
+                .any(|pixel| pixel[..3] != [0, 0, 0]);
+            let palm_regions = if lit {
+                vec![crate::types::PalmRegion {
+                    bbox: [0.0, 0.0, frame.width as f32, frame.height as f32],
+                    landmarks: Vec::new(),
+                    score: 0.9,
+                }]
+            } else {
+                Vec::new()
+            };
+            Ok(HandposeOutput {
+                raw_landmarks: Vec::new(),
+                projected_landmarks: Vec::new(),
+                confidence: 0.0,
+                palm_score: None,
+                handpose_confidence: 0.0,
+                handedness: 0.0,
+                palm_regions,
+                secondary_hands: Vec::new(),
+            })
+        }
+    }
+
+    fn recognizer(backend: &RecognizerBackend) -> Recognizer {
+        Recognizer::with_engine(
+            Box::new(BrightnessEngine),
+            GestureClassifier::without_model(),
+            backend,
+        )
+    }
+
+    #[test]
+    fn a_black_out_hook_drops_detection_to_zero() {
+        let frame = Frame::solid_color(64, 48, [200, 160, 120, 255]);
+        let plain = RecognizerBackend::default();
+        let result = recognizer(&plain).process(&frame).unwrap();
+        assert_eq!(result.palm_regions.len(), 1);
+
+        let masked = plain.with_preprocess(Some(PreprocessHook::black_out([0.0, 0.0, 1.0, 1.0])));
+        let result = recognizer(&masked).process(&frame).unwrap();
+        assert!(result.palm_regions.is_empty());
+        // The hook works on the recognizer's copy only.
+        assert_eq!(&frame.rgba[..4], &[200, 160, 120, 255]);
+    }
+
+    #[test]
+    fn black_out_only_touches_its_region() {
+        let mut frame = Frame::solid_color(10, 4, [255, 255, 255, 255]);
+        PreprocessHook::black_out([0.5, 0.5, 1.0, 1.0]).apply(&mut frame);
+        let black: Vec<(usize, usize)> = frame
+            .rgba
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| pixel[..3] == [0, 0, 0])
+            .map(|(index, _)| (index % 10, index / 10))
+            .collect();
+        let expected: Vec<(usize, usize)> =
+            (2..4).flat_map(|y| (5..10).map(move |x| (x, y))).collect();
+        assert_eq!(black, expected);
+        assert!(frame.rgba.chunks_exact(4).all(|pixel| pixel[3] == 255));
     }
 }
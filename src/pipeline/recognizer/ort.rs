@@ -1,110 +1,223 @@
 use std::{
-    path::PathBuf,
     thread,
     time::{Duration, Instant},
 };
 
-use anyhow::{Context, Result, anyhow};
-use crossbeam_channel::{Receiver, Sender};
+use anyhow::{Context, Result};
+use crossbeam_channel::Receiver;
 use ort::session::{Session, builder::GraphOptimizationLevel};
 use ort::value::Tensor;
 
 use super::{
-    HandposeEngine, RecognizerBackend,
-    common::{self, HandposeOutput},
-    palm::{PalmDetector, PalmDetectorConfig, crop_from_palm, pick_primary_region},
+    DetectionThreshold, HandposeEngine, MaxHands, Recognizer, RecognizerBackend,
+    common::{self, HandposeOutput, SecondaryHand},
+    palm::{CropConfig, PalmDetector, PalmDetectorConfig, crop_from_palm, pick_primary_region},
+    providers::{self, InferenceProvider},
     run_worker_loop,
 };
 use crate::{
-    model_download::{ensure_handpose_estimator_model_ready, ensure_palm_detector_model_ready},
+    model_download::{ModelKind, ModelSource, prepare_model_source},
+    pipeline::channel::FrameSender,
     types::{Frame, RecognizedFrame},
 };
 
 pub fn start_worker(
     backend: RecognizerBackend,
     frame_rx: Receiver<Frame>,
-    result_tx: Sender<RecognizedFrame>,
+    result_tx: FrameSender<RecognizedFrame>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
-        let handpose_estimator_model_path = backend.handpose_estimator_model_path();
-        let palm_detector_model_path = backend.palm_detector_model_path();
-
-        if let Err(err) =
-            ensure_handpose_estimator_model_ready(&handpose_estimator_model_path, |_evt| {})
-        {
-            log::error!(
-                "failed to prepare handpose model at {}: {err:?}",
-                handpose_estimator_model_path.display()
-            );
-            return;
-        }
-
-        if let Err(err) = ensure_palm_detector_model_ready(&palm_detector_model_path, |_evt| {}) {
-            log::error!(
-                "failed to prepare palm detector model at {}: {err:?}",
-                palm_detector_model_path.display()
-            );
-            return;
-        }
-
-        let engine = match OrtEngine::new(&handpose_estimator_model_path, &palm_detector_model_path)
-        {
-            Ok(engine) => {
-                log::info!(
-                    "handpose ORT backend ready using {} and palm detector {}",
-                    handpose_estimator_model_path.display(),
-                    palm_detector_model_path.display()
-                );
-                engine
-            }
+        let engine = match load_engine(&backend) {
+            Ok(engine) => engine,
             Err(err) => {
-                log::error!("failed to load ORT handpose model: {err:?}");
+                log::error!("failed to start handpose backend: {err:?}");
                 return;
             }
         };
-
-        run_worker_loop(engine, frame_rx, result_tx);
+        let recognizer =
+            Recognizer::with_engine(Box::new(engine), backend.build_classifier(), &backend);
+        run_worker_loop(recognizer, frame_rx, result_tx, &backend);
     })
 }
 
-struct OrtEngine {
+/// Prepares both models and builds their sessions, publishing the bound
+/// execution provider to the backend's status.
+pub(super) fn load_engine(backend: &RecognizerBackend) -> Result<OrtEngine> {
+    let handpose_estimator_model_path = backend.handpose_estimator_model_path();
+    let palm_detector_model_path = backend.palm_detector_model_path();
+    let prefer_embedded = backend.prefer_embedded_models();
+
+    let handpose_source = prepare_model_source(
+        ModelKind::HandposeEstimator,
+        &handpose_estimator_model_path,
+        prefer_embedded,
+        |_evt| {},
+    )
+    .with_context(|| {
+        format!(
+            "failed to prepare handpose model at {}",
+            handpose_estimator_model_path.display()
+        )
+    })?;
+    let palm_source = prepare_model_source(
+        ModelKind::PalmDetector,
+        &palm_detector_model_path,
+        prefer_embedded,
+        |_evt| {},
+    )
+    .with_context(|| {
+        format!(
+            "failed to prepare palm detector model at {}",
+            palm_detector_model_path.display()
+        )
+    })?;
+
+    let engine = OrtEngine::new(backend, &handpose_source, &palm_source)
+        .context("failed to load ORT handpose model")?;
+    log::info!(
+        "handpose ORT backend ready on {} using {} and palm detector {}",
+        engine.provider.label(),
+        handpose_source.describe(),
+        palm_source.describe()
+    );
+    backend.provider_status().set(engine.provider);
+    Ok(engine)
+}
+
+pub(super) struct OrtEngine {
     handpose: Session,
-    palm_detector: PalmDetector,
+    /// `None` when palm detection is skipped and the full frame is used.
+    palm_detector: Option<PalmDetector>,
     tracker: HandTracker,
+    crop: CropConfig,
+    /// Palm boxes beyond the primary one get their own handpose pass, up to
+    /// this many hands in total.
+    max_hands: MaxHands,
+    input_size: u32,
+    /// Side the rotated crop is sampled at before it is resized to
+    /// `input_size`; never smaller than it.
+    crop_size: u32,
+    working_resolution: Option<u32>,
+    /// Execution provider the handpose session bound to.
+    provider: InferenceProvider,
+    detection_threshold: DetectionThreshold,
 }
 
 impl OrtEngine {
-    fn new(model_path: &PathBuf, palm_detector_model_path: &PathBuf) -> Result<Self> {
-        let handpose = Session::builder()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_intra_threads(2)?
-            .commit_from_file(model_path)
-            .with_context(|| format!("failed to load ORT session from {}", model_path.display()))?;
-
-        let palm_detector =
-            PalmDetector::new(palm_detector_model_path, PalmDetectorConfig::default())?;
+    fn new(
+        backend: &RecognizerBackend,
+        handpose_source: &ModelSource,
+        palm_source: &ModelSource,
+    ) -> Result<Self> {
+        let (builder, provider) = providers::session_builder(&backend.providers())?;
+        let builder = backend
+            .session_threads()
+            .apply(builder.with_optimization_level(GraphOptimizationLevel::Level3)?)?;
+        let handpose = handpose_source.commit(builder).with_context(|| {
+            format!(
+                "failed to load ORT session from {}",
+                handpose_source.describe()
+            )
+        })?;
+        let input_size = common::resolve_input_size(
+            &handpose,
+            backend.handpose_input_size(),
+            common::INPUT_SIZE,
+        )
+        .context("handpose model input")?;
+
+        let palm_config = (!backend.skip_palm_detection()).then(|| PalmDetectorConfig {
+            score_threshold: backend.detection_threshold().get(),
+            top_k: backend.max_hands().get(),
+            preprocess: backend.palm_preprocess(),
+            grayscale: backend.palm_grayscale(),
+            keypoint_separation: backend.palm_keypoint_separation(),
+            providers: backend.providers(),
+            threads: backend.session_threads(),
+            ..PalmDetectorConfig::default()
+        });
+        let palm_detector = palm_config
+            .map(|cfg| PalmDetector::from_source(palm_source, cfg))
+            .transpose()?;
 
         Ok(Self {
             handpose,
             palm_detector,
             tracker: HandTracker::new(),
+            crop: backend.crop(),
+            max_hands: backend.max_hands(),
+            input_size,
+            crop_size: backend
+                .crop_output_size()
+                .map_or(input_size, |size| size.max(input_size)),
+            working_resolution: backend.working_resolution(),
+            provider,
+            detection_threshold: backend.detection_threshold(),
         })
     }
 }
 
 impl HandposeEngine for OrtEngine {
     fn infer(&mut self, frame: &Frame) -> Result<HandposeOutput> {
+        // Palm detection, cropping and tracking all run in working-resolution
+        // space; only the frame-space outputs are mapped back for the overlay.
+        let working = match self.working_resolution {
+            Some(max_side) => common::downscale_frame(frame, max_side)?,
+            None => None,
+        };
+        match working {
+            Some((working, factor)) => {
+                let mut output = self.infer_working(&working)?;
+                output.rescale(factor);
+                Ok(output)
+            }
+            None => self.infer_working(frame),
+        }
+    }
+
+    fn palm_present(&mut self, frame: &Frame) -> Result<bool> {
+        let threshold = self.detection_threshold.get();
+        let Some(palm_detector) = self.palm_detector.as_mut() else {
+            return Ok(true);
+        };
+        palm_detector.set_score_threshold(threshold);
+        // The detector letterboxes to 192px anyway, so the working-size
+        // downscale is skipped here.
+        Ok(!palm_detector.detect(frame)?.is_empty())
+    }
+}
+
+impl OrtEngine {
+    fn infer_working(&mut self, frame: &Frame) -> Result<HandposeOutput> {
         let now = frame.timestamp;
-        let palm_regions = self.palm_detector.detect(frame).unwrap_or_else(|err| {
+        let threshold = self.detection_threshold.get();
+        let max_hands = self.max_hands.get();
+        let Some(palm_detector) = self.palm_detector.as_mut() else {
+            return self.infer_full_frame(frame);
+        };
+        palm_detector.set_score_threshold(threshold);
+        palm_detector.set_top_k(max_hands);
+        let palm_regions = palm_detector.detect(frame).unwrap_or_else(|err| {
             log::warn!("palm detection failed: {err:?}");
             Vec::new()
         });
 
         let mut used_tracking_fallback = false;
-        let (center, side, angle, prior_score) = if let Some(selected) =
-            pick_primary_region(&palm_regions).or_else(|| palm_regions.get(0))
-        {
-            let (center, side, angle) = crop_from_palm(selected);
+        let mut palm_score = None;
+        let selected = pick_primary_region(&palm_regions).or_else(|| palm_regions.get(0));
+        let secondary_crops: Vec<_> = selected
+            .map(|selected| {
+                palm_regions
+                    .iter()
+                    .filter(|region| !std::ptr::eq(*region, selected))
+                    .take(max_hands.saturating_sub(1))
+                    .map(|region| (crop_from_palm(region, &self.crop), region.score))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let (center, side, angle, prior_score) = if let Some(selected) = selected {
+            let (center, side, angle) = crop_from_palm(selected, &self.crop);
+            palm_score = Some(selected.score);
             (center, side, angle, selected.score)
         } else if let Some((tracked, score)) = self.tracker.estimate_roi(now) {
             used_tracking_fallback = true;
@@ -114,64 +227,129 @@ impl HandposeEngine for OrtEngine {
                 raw_landmarks: Vec::new(),
                 projected_landmarks: Vec::new(),
                 confidence: 0.0,
+                palm_score: None,
+                handpose_confidence: 0.0,
                 handedness: 0.0,
                 palm_regions,
+                secondary_hands: Vec::new(),
             });
         };
 
-        let (input, transform) =
-            common::prepare_rotated_crop(frame, center, side, angle, common::INPUT_SIZE)?;
-        let tensor = Tensor::from_array(input)?;
-        let outputs = self
-            .handpose
-            .run(ort::inputs![tensor])
-            .context("failed to run ORT session")?;
-
-        if outputs.len() < 1 {
-            return Err(anyhow!("model returned no outputs"));
-        }
-
-        let coords = outputs[0].try_extract_array::<f32>()?;
-        let flattened: Vec<f32> = coords.iter().copied().collect();
-        let landmarks = common::decode_landmarks(&flattened)?;
-
-        let confidence = if outputs.len() > 1 {
-            outputs[1]
-                .try_extract_array::<f32>()
-                .ok()
-                .and_then(|arr| arr.iter().next().copied())
-                .unwrap_or(0.0)
-        } else {
-            0.0
-        };
-        let handedness = if outputs.len() > 2 {
-            outputs[2]
-                .try_extract_array::<f32>()
-                .ok()
-                .and_then(|arr| arr.iter().next().copied())
-                .unwrap_or(0.0)
-        } else {
-            0.0
-        };
+        let (landmarks, handpose_confidence, handedness, transform) =
+            self.run_handpose(frame, center, side, angle)?;
 
         let projected = common::project_landmarks_with_transform(&landmarks, &transform);
-        let mut confidence = (confidence * prior_score).clamp(0.0, 1.0);
+        let mut confidence = (handpose_confidence * prior_score).clamp(0.0, 1.0);
         if used_tracking_fallback {
             confidence *= 0.9;
         }
+        // A skeleton that can't be a hand is a palm false positive: drop to
+        // the no-detection path and keep it out of the tracker.
+        let plausible = common::is_plausible_hand(&landmarks);
+        if !plausible {
+            log::trace!("implausible hand skeleton rejected");
+            confidence = 0.0;
+        }
 
-        if !landmarks.is_empty() {
+        if plausible {
             self.tracker.update(&transform, &projected, confidence, now);
         }
 
+        let mut secondary_hands = Vec::with_capacity(secondary_crops.len());
+        // An extra hand failing must not cost the primary hand its result.
+        for ((center, side, angle), score) in secondary_crops {
+            let (landmarks, handpose_confidence, handedness, transform) =
+                match self.run_handpose(frame, center, side, angle) {
+                    Ok(output) => output,
+                    Err(err) => {
+                        log::warn!("secondary hand inference failed: {err:?}");
+                        continue;
+                    }
+                };
+            if !common::is_plausible_hand(&landmarks) {
+                continue;
+            }
+            secondary_hands.push(SecondaryHand {
+                projected_landmarks: common::project_landmarks_with_transform(
+                    &landmarks, &transform,
+                ),
+                raw_landmarks: landmarks,
+                confidence: (handpose_confidence * score).clamp(0.0, 1.0),
+                handedness,
+            });
+        }
+
         Ok(HandposeOutput {
             raw_landmarks: landmarks,
             projected_landmarks: projected,
             confidence,
+            palm_score,
+            handpose_confidence: handpose_confidence.clamp(0.0, 1.0),
             handedness,
             palm_regions,
+            secondary_hands,
+        })
+    }
+
+    /// Letterboxes the whole frame into the handpose model, skipping palm
+    /// detection and tracking.
+    fn infer_full_frame(&mut self, frame: &Frame) -> Result<HandposeOutput> {
+        let center = (frame.width as f32 / 2.0, frame.height as f32 / 2.0);
+        let side = frame.width.max(frame.height) as f32;
+        let (landmarks, confidence, handedness, transform) =
+            self.run_handpose(frame, center, side, 0.0)?;
+        let projected = common::project_landmarks_with_transform(&landmarks, &transform);
+        let confidence = if common::is_plausible_hand(&landmarks) {
+            confidence
+        } else {
+            0.0
+        };
+
+        Ok(HandposeOutput {
+            raw_landmarks: landmarks,
+            projected_landmarks: projected,
+            confidence: confidence.clamp(0.0, 1.0),
+            palm_score: None,
+            handpose_confidence: confidence.clamp(0.0, 1.0),
+            handedness,
+            palm_regions: Vec::new(),
+            secondary_hands: Vec::new(),
         })
     }
+
+    fn run_handpose(
+        &mut self,
+        frame: &Frame,
+        center: (f32, f32),
+        side: f32,
+        angle: f32,
+    ) -> Result<(Vec<[f32; 3]>, f32, f32, common::CropTransform)> {
+        let (input, transform) = common::prepare_rotated_crop(
+            frame,
+            center,
+            side,
+            angle,
+            self.input_size,
+            self.crop_size,
+        )?;
+        let tensor = Tensor::from_array(input)?;
+        let outputs = self
+            .handpose
+            .run(ort::inputs![tensor])
+            .context("failed to run ORT session")?;
+
+        let values = (0..outputs.len())
+            .map(|idx| {
+                outputs[idx]
+                    .try_extract_array::<f32>()
+                    .map(|array| array.iter().copied().collect())
+                    .with_context(|| format!("handpose output {idx} is not an f32 tensor"))
+            })
+            .collect::<Result<Vec<Vec<f32>>>>()?;
+        let (landmarks, confidence, handedness) = common::decode_handpose_outputs(&values)?;
+
+        Ok((landmarks, confidence, handedness, transform))
+    }
 }
 
 // Keep a short-lived track so the hand does not disappear immediately when palm
@@ -0,0 +1,179 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU8, Ordering},
+};
+
+use anyhow::Result;
+use ort::{
+    execution_providers::{
+        CUDAExecutionProvider, CoreMLExecutionProvider, DirectMLExecutionProvider,
+        ExecutionProvider,
+    },
+    session::{Session, builder::SessionBuilder},
+};
+
+/// Hardware backends tried, in order, when building an inference session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InferenceProvider {
+    CoreMl,
+    Cuda,
+    DirectMl,
+    Cpu,
+}
+
+/// GPU providers first; CPU always works and ends the list.
+pub const DEFAULT_PROVIDER_PRIORITY: &[InferenceProvider] = &[
+    InferenceProvider::CoreMl,
+    InferenceProvider::Cuda,
+    InferenceProvider::DirectMl,
+    InferenceProvider::Cpu,
+];
+
+impl InferenceProvider {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InferenceProvider::CoreMl => "CoreML",
+            InferenceProvider::Cuda => "CUDA",
+            InferenceProvider::DirectMl => "DirectML",
+            InferenceProvider::Cpu => "CPU",
+        }
+    }
+
+    /// Parses the command-line spelling: `coreml`, `cuda`, `directml` or
+    /// `cpu`.
+    pub fn from_flag(value: &str) -> Option<Self> {
+        match value {
+            "coreml" => Some(InferenceProvider::CoreMl),
+            "cuda" => Some(InferenceProvider::Cuda),
+            "directml" => Some(InferenceProvider::DirectMl),
+            "cpu" => Some(InferenceProvider::Cpu),
+            _ => None,
+        }
+    }
+
+    fn register(&self, builder: &mut SessionBuilder) -> Result<()> {
+        match self {
+            InferenceProvider::CoreMl => register_ep(&CoreMLExecutionProvider::default(), builder),
+            InferenceProvider::Cuda => register_ep(&CUDAExecutionProvider::default(), builder),
+            InferenceProvider::DirectMl => {
+                register_ep(&DirectMLExecutionProvider::default(), builder)
+            }
+            // ONNX Runtime always falls back to its CPU kernels.
+            InferenceProvider::Cpu => Ok(()),
+        }
+    }
+}
+
+fn register_ep(ep: &impl ExecutionProvider, builder: &mut SessionBuilder) -> Result<()> {
+    if !ep.supported_by_platform() {
+        anyhow::bail!("not supported on this platform");
+    }
+    if !ep.is_available()? {
+        anyhow::bail!("not compiled into this ONNX Runtime build");
+    }
+    ep.register(builder)?;
+    Ok(())
+}
+
+/// Session builder with the first provider from `priority` that registers.
+/// A provider that is compiled in but whose runtime is missing is logged and
+/// skipped, so startup never fails on a GPU problem.
+pub fn session_builder(
+    priority: &[InferenceProvider],
+) -> Result<(SessionBuilder, InferenceProvider)> {
+    let mut builder = Session::builder()?;
+    let provider = select_provider(priority, |provider| provider.register(&mut builder));
+    log::info!("inference provider: {}", provider.label());
+    Ok((builder, provider))
+}
+
+/// Walks `priority` until `try_register` succeeds, defaulting to CPU.
+fn select_provider(
+    priority: &[InferenceProvider],
+    mut try_register: impl FnMut(InferenceProvider) -> Result<()>,
+) -> InferenceProvider {
+    for &provider in priority {
+        match try_register(provider) {
+            Ok(()) => return provider,
+            Err(err) => log::debug!("{} provider unavailable: {err}", provider.label()),
+        }
+    }
+    InferenceProvider::Cpu
+}
+
+/// Provider the recognizer actually bound, shared with the UI.
+#[derive(Clone, Debug, Default)]
+pub struct ProviderStatus {
+    inner: Arc<AtomicU8>,
+}
+
+impl ProviderStatus {
+    pub fn get(&self) -> Option<InferenceProvider> {
+        match self.inner.load(Ordering::Relaxed) {
+            1 => Some(InferenceProvider::CoreMl),
+            2 => Some(InferenceProvider::Cuda),
+            3 => Some(InferenceProvider::DirectMl),
+            4 => Some(InferenceProvider::Cpu),
+            _ => None,
+        }
+    }
+
+    pub fn set(&self, provider: InferenceProvider) {
+        let value = match provider {
+            InferenceProvider::CoreMl => 1,
+            InferenceProvider::Cuda => 2,
+            InferenceProvider::DirectMl => 3,
+            InferenceProvider::Cpu => 4,
+        };
+        self.inner.store(value, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs [`select_provider`] with only `available` registering, returning
+    /// the chosen provider and every one that was tried.
+    fn select_with(
+        priority: &[InferenceProvider],
+        available: &[InferenceProvider],
+    ) -> (InferenceProvider, Vec<InferenceProvider>) {
+        let mut tried = Vec::new();
+        let chosen = select_provider(priority, |provider| {
+            tried.push(provider);
+            if available.contains(&provider) {
+                Ok(())
+            } else {
+                anyhow::bail!("runtime missing")
+            }
+        });
+        (chosen, tried)
+    }
+
+    #[test]
+    fn first_registering_provider_wins() {
+        let (chosen, tried) = select_with(DEFAULT_PROVIDER_PRIORITY, &[InferenceProvider::Cuda]);
+        assert_eq!(chosen, InferenceProvider::Cuda);
+        assert_eq!(tried, [InferenceProvider::CoreMl, InferenceProvider::Cuda]);
+    }
+
+    #[test]
+    fn failed_gpu_providers_fall_back_to_cpu() {
+        let gpus_only = &[InferenceProvider::CoreMl, InferenceProvider::Cuda];
+        let (chosen, tried) = select_with(gpus_only, &[]);
+        assert_eq!(chosen, InferenceProvider::Cpu);
+        assert_eq!(tried, gpus_only);
+        assert_eq!(select_with(&[], &[]).0, InferenceProvider::Cpu);
+    }
+
+    #[test]
+    fn status_reports_the_bound_provider() {
+        let status = ProviderStatus::default();
+        assert_eq!(status.get(), None);
+        for &provider in DEFAULT_PROVIDER_PRIORITY {
+            status.set(provider);
+            assert_eq!(status.get(), Some(provider));
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+
+use crate::types::{Frame, GestureResult};
+
+/// Long side of the grayscale sample the sharpness is measured on. Small
+/// enough to cost well under a millisecond, large enough that finger edges
+/// survive the downsampling.
+pub const SHARPNESS_SAMPLE_SIDE: u32 = 160;
+
+/// Longest a sharp result stands in for blurry frames. Blur that lasts
+/// longer is more likely a hand moving away than a brief shake, so the
+/// worker goes back to running inference on it.
+pub const MAX_BLUR_REPLAY_AGE: Duration = Duration::from_millis(300);
+
+/// The last sharp frame's result, repeated in place of blurry frames for at
+/// most [`MAX_BLUR_REPLAY_AGE`].
+#[derive(Default)]
+pub struct BlurReplay {
+    last_good: Option<(GestureResult, Instant)>,
+}
+
+impl BlurReplay {
+    /// The result to repeat for a frame captured at `now`, or `None` when
+    /// it is sharp enough or nothing recent enough is left to repeat.
+    pub fn replay(&self, sharpness: f32, threshold: f32, now: Instant) -> Option<&GestureResult> {
+        if sharpness >= threshold {
+            return None;
+        }
+        self.last_good
+            .as_ref()
+            .filter(|(_, at)| now.saturating_duration_since(*at) <= MAX_BLUR_REPLAY_AGE)
+            .map(|(result, _)| result)
+    }
+
+    /// Keeps a sharp frame's result. One without a hand clears it, so blur
+    /// never brings back a hand that has already left.
+    pub fn record(&mut self, result: &GestureResult, now: Instant) {
+        self.last_good = result.landmarks.is_some().then(|| (result.clone(), now));
+    }
+}
+
+/// Variance of the Laplacian over a downsampled grayscale copy of `frame`.
+///
+/// Sharp frames have strong edges and a high variance; motion blur smears
+/// them out and the variance drops. The value depends on the scene and on
+/// [`SHARPNESS_SAMPLE_SIDE`], so thresholds are best tuned per camera from
+/// the values logged at trace level.
+pub fn laplacian_variance(frame: &Frame) -> f32 {
+    let (gray, width, height) = sample_gray(frame);
+    if width < 3 || height < 3 {
+        return 0.0;
+    }
+
+    let mut sum = 0.0f64;
+    let mut sum_sq = 0.0f64;
+    let mut count = 0usize;
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let idx = y * width + x;
+            let lap = 4.0 * gray[idx]
+                - gray[idx - 1]
+                - gray[idx + 1]
+                - gray[idx - width]
+                - gray[idx + width];
+            sum += lap as f64;
+            sum_sq += (lap * lap) as f64;
+            count += 1;
+        }
+    }
+    let mean = sum / count as f64;
+    (sum_sq / count as f64 - mean * mean).max(0.0) as f32
+}
+
+/// Nearest-neighbor grayscale sample with the long side at most
+/// [`SHARPNESS_SAMPLE_SIDE`].
+fn sample_gray(frame: &Frame) -> (Vec<f32>, usize, usize) {
+    let step = frame
+        .width
+        .max(frame.height)
+        .div_ceil(SHARPNESS_SAMPLE_SIDE)
+        .max(1) as usize;
+    let src_w = frame.width as usize;
+    let width = src_w.div_ceil(step);
+    let height = (frame.height as usize).div_ceil(step);
+
+    let mut gray = Vec::with_capacity(width * height);
+    for y in (0..frame.height as usize).step_by(step) {
+        for x in (0..src_w).step_by(step) {
+            let px = (y * src_w + x) * 4;
+            let [r, g, b] = [
+                frame.rgba[px] as f32,
+                frame.rgba[px + 1] as f32,
+                frame.rgba[px + 2] as f32,
+            ];
+            gray.push(0.299 * r + 0.587 * g + 0.114 * b);
+        }
+    }
+    (gray, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: f32 = 100.0;
+
+    fn result(hand: bool) -> GestureResult {
+        GestureResult {
+            label: String::new(),
+            confidence: 0.0,
+            palm_score: None,
+            handpose_confidence: 0.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+            landmarks: hand.then(|| vec![(10.0, 10.0); 21]),
+            smoothed_landmarks: None,
+            raw_landmarks: None,
+            landmark_depths: None,
+            detail: None,
+            palm_regions: Vec::new(),
+            other_hands: Vec::new(),
+            power_saving: false,
+        }
+    }
+
+    #[test]
+    fn a_flat_image_has_no_sharpness() {
+        let frame = Frame::solid_color(320, 240, [90, 120, 150, 255]);
+        assert!(laplacian_variance(&frame) < 1e-3);
+    }
+
+    #[test]
+    fn a_checkerboard_is_sharp() {
+        // 4-pixel squares survive the 2x downsampling to the sample side.
+        let mut rgba = Vec::with_capacity(320 * 240 * 4);
+        for y in 0..240u32 {
+            for x in 0..320u32 {
+                let value = if (x / 4 + y / 4) % 2 == 0 { 255 } else { 0 };
+                rgba.extend_from_slice(&[value, value, value, 255]);
+            }
+        }
+        let frame = Frame::from_rgba(320, 240, rgba).unwrap();
+        assert!(laplacian_variance(&frame) > 10_000.0);
+    }
+
+    #[test]
+    fn blurry_frames_repeat_a_recent_hand() {
+        let base = Instant::now();
+        let mut replay = BlurReplay::default();
+        assert!(replay.replay(0.0, THRESHOLD, base).is_none());
+
+        replay.record(&result(true), base);
+        let soon = base + Duration::from_millis(100);
+        assert!(replay.replay(THRESHOLD - 1.0, THRESHOLD, soon).is_some());
+        assert!(replay.replay(THRESHOLD, THRESHOLD, soon).is_none());
+    }
+
+    #[test]
+    fn the_repeated_result_expires() {
+        let base = Instant::now();
+        let mut replay = BlurReplay::default();
+        replay.record(&result(true), base);
+        let late = base + MAX_BLUR_REPLAY_AGE + Duration::from_millis(1);
+        assert!(replay.replay(0.0, THRESHOLD, late).is_none());
+    }
+
+    #[test]
+    fn a_sharp_frame_without_a_hand_clears_the_replay() {
+        let base = Instant::now();
+        let mut replay = BlurReplay::default();
+        replay.record(&result(true), base);
+        replay.record(&result(false), base);
+        assert!(replay.replay(0.0, THRESHOLD, base).is_none());
+    }
+}
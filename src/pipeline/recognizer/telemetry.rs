@@ -0,0 +1,166 @@
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    io::{LineWriter, Write},
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+
+use crate::types::GestureKind;
+
+/// Something the recognizer worker did. Events carry only timings, counts
+/// and labels, never frame pixels or landmark coordinates.
+#[derive(Clone, Copy, Debug)]
+pub enum MetricsEvent<'a> {
+    /// Frames discarded so the worker could catch up with the camera.
+    FramesDropped {
+        count: usize,
+    },
+    InferenceStarted {
+        at: Instant,
+    },
+    InferenceFinished {
+        elapsed: Duration,
+        hand_detected: bool,
+    },
+    GestureRecognized {
+        gesture: GestureKind,
+        confidence: f32,
+    },
+    Error {
+        error: &'a anyhow::Error,
+    },
+}
+
+/// Local extension point for recognizer events, e.g. to append to a log
+/// file or maintain a Prometheus textfile. Called on the worker thread, so
+/// implementations should return quickly.
+pub trait MetricsSink: Send + Sync + 'static {
+    fn record(&self, event: &MetricsEvent<'_>);
+}
+
+impl fmt::Debug for dyn MetricsSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsSink")
+    }
+}
+
+/// Default sink that discards every event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopMetricsSink;
+
+impl MetricsSink for NoopMetricsSink {
+    fn record(&self, _event: &MetricsEvent<'_>) {}
+}
+
+/// Appends one tab-separated line per event to a local file: seconds since
+/// the sink was created, the event name, then `key=value` fields, e.g.
+/// `12.034\tinference_finished\telapsed_ms=18.2\thand=true`. Lines are
+/// flushed as they are written, so the file can be followed live.
+#[derive(Debug)]
+pub struct FileMetricsSink {
+    file: Mutex<LineWriter<File>>,
+    started: Instant,
+}
+
+impl FileMetricsSink {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open metrics log {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(LineWriter::new(file)),
+            started: Instant::now(),
+        })
+    }
+
+    fn line(&self, event: &MetricsEvent<'_>) -> String {
+        let now = Instant::now();
+        let (at, fields) = match event {
+            MetricsEvent::FramesDropped { count } => {
+                (now, format!("frames_dropped\tcount={count}"))
+            }
+            MetricsEvent::InferenceStarted { at } => (*at, "inference_started".to_string()),
+            MetricsEvent::InferenceFinished {
+                elapsed,
+                hand_detected,
+            } => (
+                now,
+                format!(
+                    "inference_finished\telapsed_ms={:.1}\thand={hand_detected}",
+                    elapsed.as_secs_f64() * 1000.0
+                ),
+            ),
+            MetricsEvent::GestureRecognized {
+                gesture,
+                confidence,
+            } => (
+                now,
+                format!("gesture_recognized\tgesture={gesture:?}\tconfidence={confidence:.3}"),
+            ),
+            MetricsEvent::Error { error } => {
+                let message = format!("{error:#}").replace(['\t', '\n', '\r'], " ");
+                (now, format!("error\tmessage={message}"))
+            }
+        };
+        let secs = at.saturating_duration_since(self.started).as_secs_f64();
+        format!("{secs:.3}\t{fields}")
+    }
+}
+
+impl MetricsSink for FileMetricsSink {
+    fn record(&self, event: &MetricsEvent<'_>) {
+        let line = self.line(event);
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(err) = writeln!(file, "{line}") {
+            log::debug!("failed to write metrics event: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_sink_writes_one_line_per_event() {
+        let path = std::env::temp_dir().join(format!("metrics-{}.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = FileMetricsSink::create(&path).unwrap();
+        let error = anyhow::anyhow!("model\nmissing");
+        sink.record(&MetricsEvent::FramesDropped { count: 2 });
+        sink.record(&MetricsEvent::InferenceFinished {
+            elapsed: Duration::from_millis(18),
+            hand_detected: true,
+        });
+        sink.record(&MetricsEvent::GestureRecognized {
+            gesture: GestureKind::Palm,
+            confidence: 0.9,
+        });
+        sink.record(&MetricsEvent::Error { error: &error });
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let events: Vec<Vec<&str>> = written
+            .lines()
+            .map(|line| line.split('\t').skip(1).collect())
+            .collect();
+        assert_eq!(
+            events,
+            [
+                vec!["frames_dropped", "count=2"],
+                vec!["inference_finished", "elapsed_ms=18.0", "hand=true"],
+                vec!["gesture_recognized", "gesture=Palm", "confidence=0.900"],
+                vec!["error", "message=model missing"],
+            ]
+        );
+    }
+}
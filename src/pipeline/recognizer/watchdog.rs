@@ -0,0 +1,108 @@
+use std::{
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Shortest pause between two watchdog checks.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Liveness of the recognizer worker, shared with the watchdog and the UI.
+///
+/// The worker marks when it picks up a frame and when it is done with it.
+/// Only time spent on a frame counts toward a stall, so a camera that stops
+/// delivering frames never looks like a hung recognizer.
+#[derive(Clone, Debug)]
+pub struct Heartbeat {
+    inner: Arc<HeartbeatShared>,
+}
+
+#[derive(Debug)]
+struct HeartbeatShared {
+    epoch: Instant,
+    /// Milliseconds since `epoch` of the last pickup or completion.
+    last_beat_ms: AtomicU64,
+    busy: AtomicBool,
+    stalled: AtomicBool,
+}
+
+impl Heartbeat {
+    /// Whether the watchdog currently considers the worker hung. Always
+    /// false when no watchdog runs.
+    pub fn is_stalled(&self) -> bool {
+        self.inner.stalled.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn start_frame(&self) {
+        self.beat();
+        self.inner.busy.store(true, Ordering::Relaxed);
+    }
+
+    pub(super) fn finish_frame(&self) {
+        self.beat();
+        self.inner.busy.store(false, Ordering::Relaxed);
+    }
+
+    fn beat(&self) {
+        let now = self.inner.epoch.elapsed().as_millis() as u64;
+        self.inner.last_beat_ms.store(now, Ordering::Relaxed);
+    }
+
+    /// Time spent on the current frame; `None` while waiting for one.
+    fn busy_for(&self) -> Option<Duration> {
+        if !self.inner.busy.load(Ordering::Relaxed) {
+            return None;
+        }
+        let last = Duration::from_millis(self.inner.last_beat_ms.load(Ordering::Relaxed));
+        Some(self.inner.epoch.elapsed().saturating_sub(last))
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(HeartbeatShared {
+                epoch: Instant::now(),
+                last_beat_ms: AtomicU64::new(0),
+                busy: AtomicBool::new(false),
+                stalled: AtomicBool::new(false),
+            }),
+        }
+    }
+}
+
+/// Keeps the watchdog thread running; it exits shortly after this is
+/// dropped.
+pub(super) struct WatchdogGuard {
+    _alive: Arc<()>,
+}
+
+/// Starts a thread that warns once the worker has spent longer than
+/// `timeout` on a single frame, and again when it moves on.
+pub(super) fn spawn_watchdog(heartbeat: Heartbeat, timeout: Duration) -> WatchdogGuard {
+    let alive = Arc::new(());
+    let watched: Weak<()> = Arc::downgrade(&alive);
+    let poll = (timeout / 4).max(MIN_POLL_INTERVAL);
+    thread::spawn(move || {
+        while watched.strong_count() > 0 {
+            thread::sleep(poll);
+            let stuck_for = heartbeat.busy_for().filter(|busy| *busy >= timeout);
+            let was_stalled = heartbeat
+                .inner
+                .stalled
+                .swap(stuck_for.is_some(), Ordering::Relaxed);
+            match (stuck_for, was_stalled) {
+                (Some(busy), false) => log::warn!(
+                    "recognizer has been on one frame for {busy:?}, inference may be hung"
+                ),
+                (None, true) => log::info!("recognizer is responding again"),
+                _ => {}
+            }
+        }
+        heartbeat.inner.stalled.store(false, Ordering::Relaxed);
+    });
+    WatchdogGuard { _alive: alive }
+}
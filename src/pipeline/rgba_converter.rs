@@ -1,5 +1,3 @@
-use std::convert::TryFrom;
-
 use anyhow::{Result, anyhow};
 use nokhwa::{Buffer, utils::FrameFormat};
 use rayon::prelude::*;
@@ -12,6 +10,35 @@ use zune_jpeg::{
     zune_core::{bytestream::ZCursor, colorspace::ColorSpace, options::DecoderOptions},
 };
 
+/// Output size of MJPEG frames relative to the encoded resolution.
+///
+/// zune-jpeg has no reduced-size IDCT, so frames are box-filtered right after
+/// decoding: the JPEG decode itself costs the same, but the RGBA buffer and
+/// everything downstream (preview upload, history, recognition resize)
+/// shrink with the pixel count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JpegDecodeScale {
+    #[default]
+    Full,
+    #[allow(dead_code)]
+    Half,
+    #[allow(dead_code)]
+    Quarter,
+    #[allow(dead_code)]
+    Eighth,
+}
+
+impl JpegDecodeScale {
+    fn divisor(self) -> u32 {
+        match self {
+            JpegDecodeScale::Full => 1,
+            JpegDecodeScale::Half => 2,
+            JpegDecodeScale::Quarter => 4,
+            JpegDecodeScale::Eighth => 8,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RgbaFrame {
     pub rgba: Vec<u8>,
@@ -19,7 +46,7 @@ pub struct RgbaFrame {
     pub height: u32,
 }
 
-pub fn convert_camera_frame(frame: &Buffer) -> Result<RgbaFrame> {
+pub fn convert_camera_frame(frame: &Buffer, mjpeg_scale: JpegDecodeScale) -> Result<RgbaFrame> {
     let resolution = frame.resolution();
     let width = resolution.width_x;
     let height = resolution.height_y;
@@ -28,7 +55,8 @@ pub fn convert_camera_frame(frame: &Buffer) -> Result<RgbaFrame> {
     let rgba = match frame.source_frame_format() {
         FrameFormat::NV12 => nv12_to_rgba(data, width, height)?,
         FrameFormat::YUYV => yuyv_to_rgba(data, width, height)?,
-        FrameFormat::MJPEG => mjpeg_to_rgba(data)?,
+        // The JPEG header is authoritative for the size, and scaling changes it.
+        FrameFormat::MJPEG => return mjpeg_to_rgba(data, mjpeg_scale),
         FrameFormat::RAWRGB => raw_rgb_to_rgba(data, width, height)?,
         FrameFormat::RAWBGR => raw_bgr_to_rgba(data, width, height)?,
         FrameFormat::GRAY => gray_to_rgba(data, width, height)?,
@@ -109,27 +137,70 @@ fn yuyv_to_rgba(data: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
     Ok(rgba)
 }
 
-fn mjpeg_to_rgba(data: &[u8]) -> Result<Vec<u8>> {
+fn mjpeg_to_rgba(data: &[u8], scale: JpegDecodeScale) -> Result<RgbaFrame> {
     let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
     let mut decoder = JpegDecoder::new_with_options(ZCursor::new(data), options);
-    let rgba = decoder
+    let mut rgba = decoder
         .decode()
         .map_err(|err| anyhow!("MJPEG decode failed: {err:?}"))?;
 
-    if let Some(info) = decoder.info() {
-        let expected_len = usize::try_from(info.width)
-            .and_then(|w| usize::try_from(info.height).map(|h| w * h * 4))
-            .map_err(|_| anyhow!("MJPEG dimensions do not fit usize"))?;
-        if rgba.len() < expected_len {
-            return Err(anyhow!(
-                "MJPEG decode produced too few bytes: got {}, expected {}",
-                rgba.len(),
-                expected_len
-            ));
-        }
+    let info = decoder
+        .info()
+        .ok_or_else(|| anyhow!("MJPEG decoder reported no image info"))?;
+    let (width, height) = (u32::from(info.width), u32::from(info.height));
+    let expected_len = usize::from(info.width) * usize::from(info.height) * 4;
+    if rgba.len() < expected_len {
+        return Err(anyhow!(
+            "MJPEG decode produced too few bytes: got {}, expected {}",
+            rgba.len(),
+            expected_len
+        ));
     }
+    rgba.truncate(expected_len);
 
-    Ok(rgba)
+    let factor = scale.divisor();
+    if factor == 1 || width < factor || height < factor {
+        return Ok(RgbaFrame {
+            rgba,
+            width,
+            height,
+        });
+    }
+    Ok(box_downsample(&rgba, width, height, factor))
+}
+
+/// Averages each `factor`×`factor` block into one pixel, dropping the
+/// partial blocks at the right and bottom edges.
+fn box_downsample(rgba: &[u8], width: u32, height: u32, factor: u32) -> RgbaFrame {
+    let (out_w, out_h) = (width / factor, height / factor);
+    let (src_stride, factor) = (width as usize * 4, factor as usize);
+    let area = (factor * factor) as u32;
+
+    let mut out = vec![0u8; out_w as usize * out_h as usize * 4];
+    out.par_chunks_mut(out_w as usize * 4)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, dst) in row.chunks_exact_mut(4).enumerate() {
+                let mut sum = [0u32; 4];
+                for dy in 0..factor {
+                    let start = (y * factor + dy) * src_stride + x * factor * 4;
+                    for px in rgba[start..start + factor * 4].chunks_exact(4) {
+                        for (acc, value) in sum.iter_mut().zip(px) {
+                            *acc += u32::from(*value);
+                        }
+                    }
+                }
+                for (dst, acc) in dst.iter_mut().zip(sum) {
+                    *dst = (acc / area) as u8;
+                }
+            }
+        });
+
+    RgbaFrame {
+        rgba: out,
+        width: out_w,
+        height: out_h,
+    }
 }
 
 fn raw_rgb_to_rgba(data: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
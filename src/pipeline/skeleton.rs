@@ -1,3 +1,5 @@
+use super::recognizer::palm::{CropConfig, crop_from_palm};
+
 pub const CONNECTIONS: &[(usize, usize)] = &[
     (0, 1),
     (1, 2),
@@ -26,24 +28,90 @@ pub const CONNECTIONS: &[(usize, usize)] = &[
 
 
 const PALM_BOX_THICKNESS: i32 = 6;
+/// Skeleton line thickness as a fraction of the frame's longer side, so the
+/// overlay looks the same once the preview is scaled to fit the panel.
+const SKELETON_LINE_SCALE: f32 = 1.0 / 320.0;
 const PALM_SCORE_THRESHOLD: f32 = 0.25;
 
 pub const DRAW_PALM_BBOX: bool = false;
 pub const DRAW_ENLARGED_BOX: bool = false;
 pub const DRAW_ROTATED_BOX: bool = false;
 
+/// Colors and sizes used when drawing the hand skeleton.
+#[derive(Clone, Copy, Debug)]
+pub struct SkeletonStyle {
+    pub line_color: [u8; 4],
+    pub point_color: [u8; 4],
+    /// Fixed line thickness in pixels; `None` scales with the frame size.
+    pub line_thickness: Option<i32>,
+    /// Fixed point radius in pixels; `None` sizes points from the hand span
+    /// and joint depth.
+    pub point_radius: Option<i32>,
+    /// Outline each point with `line_color`.
+    pub point_outline: bool,
+}
+
+impl Default for SkeletonStyle {
+    fn default() -> Self {
+        Self {
+            line_color: [34, 197, 94, 255],
+            point_color: [248, 113, 113, 255],
+            line_thickness: None,
+            point_radius: None,
+            point_outline: true,
+        }
+    }
+}
+
+impl SkeletonStyle {
+    /// Same style at half brightness, for landmarks that are being held over
+    /// from an earlier frame.
+    pub fn dimmed(self) -> Self {
+        let dim = |[r, g, b, a]: [u8; 4]| [r / 2, g / 2, b / 2, a];
+        Self {
+            line_color: dim(self.line_color),
+            point_color: dim(self.point_color),
+            ..self
+        }
+    }
+}
+
 pub fn draw_skeleton(buffer: &mut [u8], width: u32, height: u32, points: &[(f32, f32)]) {
+    draw_skeleton_with_style(buffer, width, height, points, &SkeletonStyle::default());
+}
+
+/// Draws landmarks onto any RGBA image, e.g. for offline annotation.
+#[allow(dead_code)]
+pub fn draw_landmarks_on_image(
+    img: &mut image::RgbaImage,
+    points: &[(f32, f32)],
+    style: &SkeletonStyle,
+) {
+    let (width, height) = img.dimensions();
+    draw_skeleton_with_style(img, width, height, points, style);
+}
+
+pub fn draw_skeleton_with_style(
+    buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    points: &[(f32, f32)],
+    style: &SkeletonStyle,
+) {
     if points.len() < 2 {
         return;
     }
 
     let hand_span = calculate_hand_span(points);
-    
-    let line_thickness = (hand_span * 0.0125).max(1.0) as i32;
-    
+
+    let line_thickness = style
+        .line_thickness
+        .unwrap_or_else(|| skeleton_line_thickness(width, height))
+        .max(1);
+
     let radius_step = (hand_span * 0.006).max(1.0) as i32;
 
-    let line_color = [34u8, 197u8, 94u8, 255u8];
+    let line_color = style.line_color;
     for &(a, b) in CONNECTIONS {
         if let (Some(pa), Some(pb)) = (points.get(a), points.get(b)) {
             draw_line(
@@ -58,22 +126,26 @@ pub fn draw_skeleton(buffer: &mut [u8], width: u32, height: u32, points: &[(f32,
         }
     }
 
-    let point_color = [248u8, 113u8, 113u8, 255u8];
+    let point_color = style.point_color;
     let border_color = line_color;
     for (i, &(x, y)) in points.iter().enumerate() {
-        let depth = get_point_depth(i);
-        let base_radius = (hand_span * 0.02).max(2.0) as i32;
-        let point_radius = (base_radius + depth * radius_step).max(2);
+        let point_radius = style.point_radius.unwrap_or_else(|| {
+            let depth = get_point_depth(i);
+            let base_radius = (hand_span * 0.02).max(2.0) as i32;
+            (base_radius + depth * radius_step).max(2)
+        });
+
+        if style.point_outline {
+            draw_circle(
+                buffer,
+                width,
+                height,
+                (x as i32, y as i32),
+                point_radius + line_thickness,
+                border_color,
+            );
+        }
 
-        draw_circle(
-            buffer,
-            width,
-            height,
-            (x as i32, y as i32),
-            point_radius + line_thickness,
-            border_color,
-        );
-        
         draw_circle(
             buffer,
             width,
@@ -85,6 +157,10 @@ pub fn draw_skeleton(buffer: &mut [u8], width: u32, height: u32, points: &[(f32,
     }
 }
 
+fn skeleton_line_thickness(width: u32, height: u32) -> i32 {
+    (width.max(height) as f32 * SKELETON_LINE_SCALE).round().max(1.0) as i32
+}
+
 fn calculate_hand_span(points: &[(f32, f32)]) -> f32 {
     if points.is_empty() {
         return 100.0; 
@@ -120,6 +196,7 @@ pub fn draw_palm_regions(
     width: u32,
     height: u32,
     regions: &[crate::types::PalmRegion],
+    crop: &CropConfig,
 ) {
     for region in regions {
         if region.score < PALM_SCORE_THRESHOLD {
@@ -154,42 +231,7 @@ pub fn draw_palm_regions(
             }
         }
 
-        let bbox_center = (
-            (region.bbox[0] + region.bbox[2]) * 0.5,
-            (region.bbox[1] + region.bbox[3]) * 0.5,
-        );
-
-        const SHIFT_Y: f32 = -0.4;
-        const ENLARGE_FACTOR: f32 = 3.0;
-
-        let base_w = (region.bbox[2] - region.bbox[0]).abs();
-        let base_h = (region.bbox[3] - region.bbox[1]).abs();
-        let (center_x, center_y) = (bbox_center.0, bbox_center.1 + SHIFT_Y * base_h);
-
-        let landmark_span = if region.landmarks.is_empty() {
-            0.0
-        } else {
-            let (min_x, max_x, min_y, max_y) = region
-                .landmarks
-                .iter()
-                .fold((f32::MAX, f32::MIN, f32::MAX, f32::MIN), |acc, (x, y)| {
-                    (acc.0.min(*x), acc.1.max(*x), acc.2.min(*y), acc.3.max(*y))
-                });
-            (max_x - min_x).max(max_y - min_y)
-        };
-
-        let side = base_w.max(base_h).max(landmark_span).max(80.0) * ENLARGE_FACTOR;
-
-        let angle = if region.landmarks.len() < 3 {
-            0.0
-        } else {
-            use std::f32::consts::PI;
-            let p1 = region.landmarks[0];
-            let p2 = region.landmarks[2];
-            let radians = PI / 2.0 - (-(p2.1 - p1.1)).atan2(p2.0 - p1.0);
-            let two_pi = 2.0 * PI;
-            radians - two_pi * ((radians + PI) / two_pi).floor()
-        };
+        let ((center_x, center_y), side, angle) = crop_from_palm(region, crop);
 
         if DRAW_ENLARGED_BOX {
             let half_side = side / 2.0;
@@ -212,25 +254,7 @@ pub fn draw_palm_regions(
         }
 
         if DRAW_ROTATED_BOX {
-            let half_side = side / 2.0;
-            let cos_a = angle.cos();
-            let sin_a = angle.sin();
-
-            let corners = [
-                (-half_side, -half_side),
-                (half_side, -half_side),
-                (half_side, half_side),
-                (-half_side, half_side),
-            ];
-
-            let rotated_corners: Vec<(f32, f32)> = corners
-                .iter()
-                .map(|(dx, dy)| {
-                    let rx = dx * cos_a - dy * sin_a + center_x;
-                    let ry = dx * sin_a + dy * cos_a + center_y;
-                    (rx, ry)
-                })
-                .collect();
+            let rotated_corners = rotated_crop_corners((center_x, center_y), side, angle);
 
             let rotated_color = [255u8, 0u8, 255u8, 200u8];
             for i in 0..4 {
@@ -249,6 +273,27 @@ pub fn draw_palm_regions(
     }
 }
 
+/// Frame-space corners of the square the handpose crop samples, clockwise
+/// from the crop's top-left, with the same rotation as
+/// [`prepare_rotated_crop`](super::recognizer::common::prepare_rotated_crop).
+fn rotated_crop_corners(center: (f32, f32), side: f32, angle: f32) -> [(f32, f32); 4] {
+    let half_side = side / 2.0;
+    let cos_a = angle.cos();
+    let sin_a = angle.sin();
+    [
+        (-half_side, -half_side),
+        (half_side, -half_side),
+        (half_side, half_side),
+        (-half_side, half_side),
+    ]
+    .map(|(dx, dy)| {
+        (
+            dx * cos_a - dy * sin_a + center.0,
+            dx * sin_a + dy * cos_a + center.1,
+        )
+    })
+}
+
 fn draw_rect(
     buffer: &mut [u8],
     width: u32,
@@ -376,3 +421,65 @@ fn put_pixel_safe(buffer: &mut [u8], width: u32, height: u32, x: i32, y: i32, co
         buffer[idx..idx + 4].copy_from_slice(&color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_thickness_scales_linearly_with_the_longer_side() {
+        assert_eq!(skeleton_line_thickness(320, 240), 1);
+        assert_eq!(skeleton_line_thickness(640, 480), 2);
+        assert_eq!(skeleton_line_thickness(1280, 720), 4);
+        assert_eq!(skeleton_line_thickness(2560, 1440), 8);
+        // Portrait frames scale by their height.
+        assert_eq!(skeleton_line_thickness(720, 1280), 4);
+    }
+
+    #[test]
+    fn line_thickness_never_drops_below_one_pixel() {
+        assert_eq!(skeleton_line_thickness(100, 80), 1);
+        assert_eq!(skeleton_line_thickness(0, 0), 1);
+    }
+
+    #[test]
+    fn overlay_box_matches_the_crop_for_a_custom_shift() {
+        use super::super::recognizer::common::CropTransform;
+        use crate::types::PalmRegion;
+
+        // A sideways hand, so the shift moves the crop along x.
+        let mut landmarks = vec![(300.0, 400.0); 7];
+        landmarks[2] = (420.0, 400.0);
+        let region = PalmRegion {
+            bbox: [250.0, 340.0, 470.0, 460.0],
+            landmarks,
+            score: 0.9,
+        };
+        let crop = CropConfig {
+            expansion: 2.0,
+            shift: -0.6,
+        };
+        let (center, side, angle) = crop_from_palm(&region, &crop);
+        assert_ne!(crop_from_palm(&region, &CropConfig::default()).0, center);
+
+        let transform = CropTransform {
+            center,
+            side,
+            angle,
+            output_size: 224,
+            orig_w: 2000,
+            orig_h: 2000,
+        };
+        let crop_corners = [(0.0, 0.0), (224.0, 0.0), (224.0, 224.0), (0.0, 224.0)];
+        for (overlay, (x, y)) in rotated_crop_corners(center, side, angle)
+            .into_iter()
+            .zip(crop_corners)
+        {
+            let projected = transform.project(x, y);
+            assert!(
+                (overlay.0 - projected.0).abs() < 1e-3 && (overlay.1 - projected.1).abs() < 1e-3,
+                "overlay corner {overlay:?} vs crop corner {projected:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::{path::PathBuf, thread, time::Duration};
+
+use crossbeam_channel::{Sender, unbounded};
+
+use crate::types::Frame;
+
+/// How far back a snapshot reaches. By the time the gesture is recognized
+/// the hand has already moved on to the pose, so the frame from just before
+/// shows the scene the user meant to capture.
+pub const SNAPSHOT_LOOKBACK: Duration = Duration::from_millis(300);
+
+/// Saves a PNG of the preview whenever the "take picture" gesture fires.
+///
+/// Encoding runs on a background thread; files are named after the frame's
+/// capture sequence so repeated snapshots never overwrite each other.
+pub struct SnapshotOutput {
+    tx: Sender<Frame>,
+}
+
+impl SnapshotOutput {
+    pub fn spawn(dir: PathBuf) -> Self {
+        let (tx, rx) = unbounded::<Frame>();
+        thread::spawn(move || {
+            if let Err(err) = std::fs::create_dir_all(&dir) {
+                log::warn!("failed to create {}: {err}", dir.display());
+                return;
+            }
+            for frame in rx {
+                let Some(image) = frame.to_rgba_image() else {
+                    continue;
+                };
+                let path = dir.join(format!("snapshot-{}.png", frame.sequence));
+                match image.save(&path) {
+                    Ok(()) => log::info!("saved snapshot {}", path.display()),
+                    Err(err) => log::warn!("failed to save {}: {err}", path.display()),
+                }
+            }
+        });
+        Self { tx }
+    }
+
+    pub fn send(&self, frame: Frame) {
+        let _ = self.tx.send(frame);
+    }
+}
@@ -1,4 +1,6 @@
-use std::time::Instant;
+use std::{fmt, str::FromStr, time::Instant};
+
+use anyhow::anyhow;
 
 #[derive(Clone, Debug)]
 pub struct Frame {
@@ -7,22 +9,104 @@ pub struct Frame {
     pub height: u32,
     #[allow(dead_code)]
     pub timestamp: Instant,
+    /// Capture ordinal assigned by the camera thread, increasing across the
+    /// whole process. Stable for ordering and deduplicating results outside
+    /// it, unlike `timestamp`; zero for frames that didn't come from a camera.
+    /// Every frame the camera sends gets the next number, but results can
+    /// still skip numbers where a full channel or the recognizer's catch-up
+    /// dropped frames.
+    pub sequence: u64,
+}
+
+/// Frames narrower or shorter than this are skipped before inference; the
+/// letterbox and crop math degenerates on anything smaller.
+pub const MIN_FRAME_SIDE: u32 = 16;
+
+impl Frame {
+    /// Wraps an RGBA8 buffer of `width * height * 4` bytes, stamped now.
+    /// Returns `None` when the buffer is any other length.
+    pub fn from_rgba(width: u32, height: u32, rgba: Vec<u8>) -> Option<Self> {
+        (rgba.len() == width as usize * height as usize * 4).then(|| Self {
+            rgba,
+            width,
+            height,
+            timestamp: Instant::now(),
+            sequence: 0,
+        })
+    }
+
+    /// A frame filled with one color, for building deterministic inputs
+    /// without a camera.
+    #[allow(dead_code)]
+    pub fn solid_color(width: u32, height: u32, color: [u8; 4]) -> Self {
+        let rgba = color.repeat(width as usize * height as usize);
+        Self::from_rgba(width, height, rgba).expect("a solid color fills the whole frame")
+    }
+
+    /// Copies the frame into a standalone `image::RgbaImage`. Returns `None`
+    /// when the buffer length doesn't match `width * height * 4`.
+    pub fn to_rgba_image(&self) -> Option<image::RgbaImage> {
+        if self.rgba.len() != self.width as usize * self.height as usize * 4 {
+            return None;
+        }
+        image::RgbaImage::from_raw(self.width, self.height, self.rgba.clone())
+    }
+
+    /// True when either side is below [`MIN_FRAME_SIDE`].
+    pub fn is_too_small(&self) -> bool {
+        self.width < MIN_FRAME_SIDE || self.height < MIN_FRAME_SIDE
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct GestureResult {
     pub label: String,
+    /// Palm score times handpose confidence; what detection is gated on.
     pub confidence: f32,
+    /// Palm detector score behind this result, if a palm box was used.
+    pub palm_score: Option<f32>,
+    /// Handpose model presence score on its own.
+    pub handpose_confidence: f32,
     #[allow(dead_code)]
     pub timestamp: Instant,
+    /// `sequence` of the frame this result was computed from.
+    pub sequence: u64,
     pub landmarks: Option<Vec<(f32, f32)>>,
+    /// `landmarks` after temporal smoothing. Only filled when the backend's
+    /// landmark smoothing is on, so the extra vector costs nothing otherwise.
+    pub smoothed_landmarks: Option<Vec<(f32, f32)>>,
+    pub raw_landmarks: Option<Vec<[f32; 3]>>,
+    /// Depth of each `raw_landmarks` point relative to the wrist, negative
+    /// toward the camera whatever the model's own convention (see
+    /// `RecognizerBackend::flip_landmark_z`).
+    pub landmark_depths: Option<Vec<f32>>,
     pub detail: Option<GestureDetail>,
     pub palm_regions: Vec<PalmRegion>,
+    /// Hands besides the one in `detail`, when the backend's `max_hands`
+    /// allows more than one. Empty in single-hand mode.
+    pub other_hands: Vec<HandResult>,
+    /// Set while the recognizer is idling at a reduced rate because no hand
+    /// has been seen for a while.
+    pub power_saving: bool,
+}
+
+/// Classification of one additional hand in multi-hand mode.
+#[derive(Clone, Debug)]
+pub struct HandResult {
+    pub detail: GestureDetail,
+    pub confidence: f32,
+    /// Projected landmarks in frame pixels.
+    pub landmarks: Vec<(f32, f32)>,
 }
 
+/// A palm found by the palm detector. Coordinates are full-frame pixels of
+/// the frame passed to detection, origin at the top-left corner.
 #[derive(Clone, Debug)]
 pub struct PalmRegion {
+    /// `[x1, y1, x2, y2]`, clamped to the frame.
     pub bbox: [f32; 4],
+    /// The 7 palm keypoints: wrist, index/middle/ring/pinky MCPs, and two
+    /// thumb joints.
     pub landmarks: Vec<(f32, f32)>,
     pub score: f32,
 }
@@ -34,6 +118,23 @@ pub struct RecognizedFrame {
 }
 
 impl GestureResult {
+    /// Landmarks to draw: smoothed when available, for steadier visuals.
+    pub fn overlay_landmarks(&self) -> Option<&[(f32, f32)]> {
+        self.smoothed_landmarks
+            .as_deref()
+            .or(self.landmarks.as_deref())
+    }
+
+    /// Copy whose `landmarks` are the smoothed ones when there are any, for
+    /// exports that prefer stable points over the raw model output.
+    pub fn with_smoothed_landmarks(&self) -> Self {
+        let mut result = self.clone();
+        if let Some(smoothed) = result.smoothed_landmarks.clone() {
+            result.landmarks = Some(smoothed);
+        }
+        result
+    }
+
     #[allow(dead_code)]
     pub fn display_text(&self) -> String {
         if let Some(detail) = &self.detail {
@@ -47,6 +148,146 @@ impl GestureResult {
             format!("{} ({:.0}%)", self.label, self.confidence * 100.0)
         }
     }
+
+    /// Serializes the raw model-space and projected frame-space landmarks as
+    /// `{"raw": [[x, y, z], ...], "projected": [[x, y], ...]}`.
+    pub fn landmarks_json(&self) -> Option<String> {
+        let raw = self.raw_landmarks.as_ref()?;
+        let projected = self.landmarks.as_ref()?;
+
+        let raw = raw
+            .iter()
+            .map(|[x, y, z]| format!("[{x},{y},{z}]"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let projected = projected
+            .iter()
+            .map(|(x, y)| format!("[{x},{y}]"))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Some(format!(
+            "{{\"sequence\":{},\"raw\":[{raw}],\"projected\":[{projected}]}}",
+            self.sequence
+        ))
+    }
+
+    /// One line of the structured gesture stream, without the newline:
+    ///
+    /// ```text
+    /// {"sequence":42,"gesture":"Palm","confidence":0.91,"handedness":"right","landmarks":[[x,y],...],"depths":[0,...]}
+    /// ```
+    ///
+    /// `gesture` is the [`GestureKind`] variant name and `handedness` one of
+    /// `"left"`, `"right"` or `"unknown"`. Both are `null` without a
+    /// classified hand, and `landmarks` is `null` without a detected hand.
+    /// Otherwise it holds the 21 projected landmarks in frame pixels.
+    /// `depths` is likewise `null` without a hand, else the 21 landmark
+    /// depths relative to the wrist (so the first is always 0), negative
+    /// toward the camera; see [`GestureResult::landmark_depths`].
+    /// `confidence` is the combined palm and handpose score.
+    pub fn event_json(&self) -> String {
+        let (gesture, handedness) = match &self.detail {
+            Some(detail) => (
+                format!("\"{:?}\"", detail.primary),
+                format!("\"{}\"", detail.handedness.key()),
+            ),
+            None => ("null".to_string(), "null".to_string()),
+        };
+        let landmarks = match &self.landmarks {
+            Some(points) => format!(
+                "[{}]",
+                points
+                    .iter()
+                    .map(|(x, y)| format!("[{x},{y}]"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            None => "null".to_string(),
+        };
+        let depths = match &self.landmark_depths {
+            Some(depths) => format!(
+                "[{}]",
+                depths
+                    .iter()
+                    .map(f32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"sequence\":{},\"gesture\":{gesture},\"confidence\":{},\"handedness\":{handedness},\"landmarks\":{landmarks},\"depths\":{depths}}}",
+            self.sequence, self.confidence
+        )
+    }
+
+    /// Serializes the hands as one person in OpenPose's `--write_json`
+    /// layout, for pose toolchains that read that format.
+    ///
+    /// OpenPose's hand model uses the same 21-point order as the handpose
+    /// model: 0 is the wrist, then four points per finger from the base to
+    /// the tip, thumb (1-4), index (5-8), middle (9-12), ring (13-16) and
+    /// pinky (17-20). Indices therefore carry over unchanged. Each keypoint
+    /// is a flat `x, y, confidence` triple in frame pixels, and the hand's
+    /// overall confidence stands in for the per-point score.
+    ///
+    /// The hand goes into `hand_left_keypoints_2d` or
+    /// `hand_right_keypoints_2d` according to its handedness. Unknown
+    /// handedness counts as right, and one additional hand fills the other
+    /// side if it is free. Like OpenPose, a missing hand is 63 zeros. The
+    /// body, face and 3D arrays stay empty.
+    pub fn openpose_json(&self) -> Option<String> {
+        let landmarks = self.landmarks.as_ref()?;
+        let handedness = self
+            .detail
+            .as_ref()
+            .map_or(Handedness::Unknown, |detail| detail.handedness);
+
+        let mut left = None;
+        let mut right = None;
+        let hands = std::iter::once((handedness, landmarks.as_slice(), self.confidence)).chain(
+            self.other_hands.iter().map(|hand| {
+                (
+                    hand.detail.handedness,
+                    hand.landmarks.as_slice(),
+                    hand.confidence,
+                )
+            }),
+        );
+        for (handedness, points, confidence) in hands {
+            let (preferred, other) = match handedness {
+                Handedness::Left => (&mut left, &mut right),
+                Handedness::Right | Handedness::Unknown => (&mut right, &mut left),
+            };
+            let slot = if preferred.is_none() {
+                preferred
+            } else {
+                other
+            };
+            if slot.is_none() {
+                *slot = Some(openpose_keypoints(points, confidence));
+            }
+        }
+        let empty = || openpose_keypoints(&[], 0.0);
+
+        Some(format!(
+            "{{\"version\":1.3,\"people\":[{{\"person_id\":[-1],\"pose_keypoints_2d\":[],\"face_keypoints_2d\":[],\"hand_left_keypoints_2d\":[{}],\"hand_right_keypoints_2d\":[{}],\"pose_keypoints_3d\":[],\"face_keypoints_3d\":[],\"hand_left_keypoints_3d\":[],\"hand_right_keypoints_3d\":[]}}]}}",
+            left.unwrap_or_else(empty),
+            right.unwrap_or_else(empty)
+        ))
+    }
+}
+
+/// 21 `x,y,confidence` triples, zero-filled past the end of `points`.
+fn openpose_keypoints(points: &[(f32, f32)], confidence: f32) -> String {
+    (0..21)
+        .map(|idx| match points.get(idx) {
+            Some((x, y)) => format!("{x},{y},{confidence}"),
+            None => "0,0,0".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -57,6 +298,11 @@ pub enum Handedness {
 }
 
 impl Handedness {
+    #[allow(dead_code)]
+    pub const fn all() -> &'static [Handedness] {
+        &[Handedness::Left, Handedness::Right, Handedness::Unknown]
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             Handedness::Left => "左手",
@@ -64,6 +310,29 @@ impl Handedness {
             Handedness::Unknown => "未知",
         }
     }
+
+    /// Stable lowercase name for machine-readable output.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Handedness::Left => "left",
+            Handedness::Right => "right",
+            Handedness::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Handedness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for Handedness {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        parse_named(s, Self::all(), Self::label, "handedness")
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -74,6 +343,15 @@ pub enum FingerState {
 }
 
 impl FingerState {
+    #[allow(dead_code)]
+    pub const fn all() -> &'static [FingerState] {
+        &[
+            FingerState::Extended,
+            FingerState::HalfBent,
+            FingerState::Folded,
+        ]
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             FingerState::Extended => "伸直",
@@ -83,7 +361,21 @@ impl FingerState {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+impl fmt::Display for FingerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for FingerState {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        parse_named(s, Self::all(), Self::label, "finger state")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GestureKind {
     Call,
     Dislike,
@@ -123,6 +415,47 @@ pub enum GestureKind {
 }
 
 impl GestureKind {
+    /// Every class the classifier can report, in HAGRID label order;
+    /// excludes `Unknown`.
+    pub const fn all() -> &'static [GestureKind] {
+        &[
+            GestureKind::Call,
+            GestureKind::Dislike,
+            GestureKind::Fist,
+            GestureKind::Four,
+            GestureKind::Grabbing,
+            GestureKind::Grip,
+            GestureKind::HandHeart,
+            GestureKind::HandHeart2,
+            GestureKind::Holy,
+            GestureKind::Like,
+            GestureKind::LittleFinger,
+            GestureKind::MiddleFinger,
+            GestureKind::Mute,
+            GestureKind::NoGesture,
+            GestureKind::Ok,
+            GestureKind::One,
+            GestureKind::Palm,
+            GestureKind::Peace,
+            GestureKind::PeaceInverted,
+            GestureKind::Point,
+            GestureKind::Rock,
+            GestureKind::Stop,
+            GestureKind::StopInverted,
+            GestureKind::TakePicture,
+            GestureKind::Three,
+            GestureKind::Three2,
+            GestureKind::Three3,
+            GestureKind::ThreeGun,
+            GestureKind::ThumbIndex,
+            GestureKind::ThumbIndex2,
+            GestureKind::Timeout,
+            GestureKind::TwoUp,
+            GestureKind::TwoUpInverted,
+            GestureKind::XSign,
+        ]
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             GestureKind::Call => "打电话",
@@ -204,6 +537,22 @@ impl GestureKind {
     }
 }
 
+impl fmt::Display for GestureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+impl FromStr for GestureKind {
+    type Err = anyhow::Error;
+
+    /// Also accepts `Unknown`, which [`all`](Self::all) leaves out.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let kinds = [Self::all(), &[GestureKind::Unknown]].concat();
+        parse_named(s, &kinds, Self::display_name, "gesture")
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GestureMotion {
     Steady,
@@ -213,6 +562,16 @@ pub enum GestureMotion {
 }
 
 impl GestureMotion {
+    #[allow(dead_code)]
+    pub const fn all() -> &'static [GestureMotion] {
+        &[
+            GestureMotion::Steady,
+            GestureMotion::Fanning,
+            GestureMotion::VerticalWave,
+            GestureMotion::Moving,
+        ]
+    }
+
     #[allow(dead_code)]
     pub fn label(&self) -> &'static str {
         match self {
@@ -224,6 +583,37 @@ impl GestureMotion {
     }
 }
 
+impl fmt::Display for GestureMotion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for GestureMotion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        parse_named(s, Self::all(), Self::label, "motion")
+    }
+}
+
+/// The variant of `values` whose English name (as in the source, compared
+/// ignoring ASCII case) or displayed label is `s`. Both are accepted so
+/// config files can use either, and `Display` output parses back.
+fn parse_named<T: Copy + fmt::Debug>(
+    s: &str,
+    values: &[T],
+    label: impl Fn(&T) -> &'static str,
+    what: &str,
+) -> anyhow::Result<T> {
+    let s = s.trim();
+    values
+        .iter()
+        .find(|value| format!("{value:?}").eq_ignore_ascii_case(s) || label(value) == s)
+        .copied()
+        .ok_or_else(|| anyhow!("unknown {what} \"{s}\""))
+}
+
 #[derive(Clone, Debug)]
 pub struct GestureDetail {
     pub primary: GestureKind,
@@ -231,4 +621,136 @@ pub struct GestureDetail {
     pub handedness: Handedness,
     pub finger_states: [FingerState; 5],
     pub motion: GestureMotion,
+    /// Abduction angles in degrees between adjacent fingers' MCP-to-tip
+    /// directions: thumb-index, index-middle, middle-ring, ring-pinky.
+    pub finger_spreads: [f32; 4],
+    /// In-plane hand roll in degrees from the wrist to the middle-finger MCP,
+    /// 0 with fingers pointing up and positive clockwise on screen, in
+    /// `-180..=180`.
+    pub roll_degrees: f32,
+    /// Palm width in frame pixels, from the index MCP (5) to the pinky MCP
+    /// (17). Grows as the hand approaches the camera.
+    pub hand_scale_px: f32,
+    /// Probability per classifier class, sorted most likely first: the
+    /// distribution `primary` was picked from, so voted across recent
+    /// frames for the tracked hand and a single frame's softmax otherwise.
+    pub probabilities: Vec<(GestureKind, f32)>,
+}
+
+/// Typical adult index-to-pinky knuckle width, for [`GestureDetail::approx_distance_cm`].
+pub const AVERAGE_PALM_WIDTH_CM: f32 = 8.0;
+
+impl GestureDetail {
+    /// Rough camera-to-hand distance from the pinhole model, given the
+    /// camera's focal length in pixels (about `0.8 * frame width` for a
+    /// typical webcam). Assumes an [`AVERAGE_PALM_WIDTH_CM`] palm seen
+    /// face-on, so treat it as "near or far" rather than a measurement.
+    #[allow(dead_code)]
+    pub fn approx_distance_cm(&self, focal_length_px: f32) -> Option<f32> {
+        (self.hand_scale_px > f32::EPSILON)
+            .then(|| AVERAGE_PALM_WIDTH_CM * focal_length_px / self.hand_scale_px)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_rgba_rejects_a_mismatched_buffer() {
+        assert!(Frame::from_rgba(2, 2, vec![0; 16]).is_some());
+        assert!(Frame::from_rgba(2, 2, vec![0; 15]).is_none());
+        assert!(Frame::from_rgba(2, 2, vec![0; 20]).is_none());
+    }
+
+    #[test]
+    fn to_rgba_image_copies_the_pixels() {
+        let rgba = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let frame = Frame::from_rgba(2, 1, rgba.clone()).unwrap();
+        let image = frame.to_rgba_image().unwrap();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(1, 0).0, [5, 6, 7, 8]);
+        assert_eq!(image.into_raw(), rgba);
+    }
+
+    #[test]
+    fn to_rgba_image_rejects_a_mismatched_buffer() {
+        let mut frame = Frame::solid_color(2, 2, [0, 0, 0, 255]);
+        frame.rgba.truncate(12);
+        assert!(frame.to_rgba_image().is_none());
+    }
+
+    fn result(landmarks: Option<Vec<(f32, f32)>>, depths: Option<Vec<f32>>) -> GestureResult {
+        GestureResult {
+            label: String::new(),
+            confidence: 0.5,
+            palm_score: None,
+            handpose_confidence: 0.5,
+            timestamp: Instant::now(),
+            sequence: 7,
+            landmarks,
+            smoothed_landmarks: None,
+            raw_landmarks: None,
+            landmark_depths: depths,
+            detail: None,
+            palm_regions: Vec::new(),
+            other_hands: Vec::new(),
+            power_saving: false,
+        }
+    }
+
+    #[test]
+    fn event_json_depths_are_null_without_a_hand() {
+        let json = result(None, None).event_json();
+        assert!(
+            json.ends_with("\"landmarks\":null,\"depths\":null}"),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn event_json_depths_start_at_the_wrist() {
+        let depths: Vec<f32> = (0..21).map(|i| -(i as f32) * 0.5).collect();
+        let json = result(Some(vec![(1.0, 2.0); 21]), Some(depths)).event_json();
+        let listed = json
+            .split("\"depths\":[")
+            .nth(1)
+            .and_then(|rest| rest.strip_suffix("]}"))
+            .unwrap();
+        let values: Vec<f32> = listed.split(',').map(|v| v.parse().unwrap()).collect();
+        assert_eq!(values.len(), 21);
+        assert_eq!(values[0], 0.0);
+        assert_eq!(values[20], -10.0);
+    }
+
+    /// `parse(display(x)) == x` and the English variant name parses in any
+    /// ASCII case, for every value.
+    fn assert_round_trips<T>(values: &[T])
+    where
+        T: Copy + fmt::Debug + fmt::Display + FromStr<Err = anyhow::Error> + PartialEq,
+    {
+        for value in values {
+            assert_eq!(value.to_string().parse::<T>().unwrap(), *value);
+            let name = format!("{value:?}");
+            assert_eq!(name.parse::<T>().unwrap(), *value);
+            assert_eq!(name.to_lowercase().parse::<T>().unwrap(), *value);
+            assert_eq!(name.to_uppercase().parse::<T>().unwrap(), *value);
+        }
+    }
+
+    #[test]
+    fn core_enums_round_trip_through_display() {
+        assert_round_trips(GestureKind::all());
+        assert_round_trips(&[GestureKind::Unknown]);
+        assert_round_trips(Handedness::all());
+        assert_round_trips(FingerState::all());
+        assert_round_trips(GestureMotion::all());
+    }
+
+    #[test]
+    fn unknown_names_fail_to_parse() {
+        assert!("Wave".parse::<GestureKind>().is_err());
+        assert!("".parse::<Handedness>().is_err());
+        assert!("bent".parse::<FingerState>().is_err());
+    }
 }
@@ -0,0 +1,302 @@
+use std::time::{Duration, Instant};
+
+use super::{
+    AnyElement, AppView, Button, Context, FluentBuilder, IntoElement, ObjectFit, ParentElement,
+    Screen, SharedString, Styled, StyledImage, Window, h_flex, v_flex,
+};
+use crate::gesture::{FingerClassifierConfig, FingerMetrics, finger_metrics};
+use crate::types::GestureResult;
+use gpui_component::{StyledExt, progress::Progress};
+
+/// Time given to change pose before samples count, so the tail of the
+/// previous pose doesn't leak into the next one.
+const CALIBRATION_SETTLE: Duration = Duration::from_millis(500);
+/// How long each pose is sampled once settled.
+const CALIBRATION_HOLD: Duration = Duration::from_secs(1);
+const CALIBRATION_PREVIEW_WIDTH: f32 = 480.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CalibrationPose {
+    OpenPalm,
+    Fist,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CalibrationStep {
+    /// Sampling `pose`; `since` is when the hand was first seen in it and is
+    /// reset whenever the hand is lost.
+    Capturing {
+        pose: CalibrationPose,
+        since: Option<Instant>,
+    },
+    Finished,
+    /// The open palm and the fist could not be told apart.
+    Failed,
+}
+
+/// Guided capture of an open palm and a fist, fitted into personal finger
+/// thresholds with [`FingerClassifierConfig::calibrated`].
+pub(super) struct Calibration {
+    base: FingerClassifierConfig,
+    step: CalibrationStep,
+    last_seen: Option<Instant>,
+    open: Vec<[FingerMetrics; 4]>,
+    fist: Vec<[FingerMetrics; 4]>,
+}
+
+impl Calibration {
+    /// Starts with the open palm; thumb thresholds are kept from `base`.
+    pub fn new(base: FingerClassifierConfig) -> Self {
+        Self {
+            base,
+            step: CalibrationStep::Capturing {
+                pose: CalibrationPose::OpenPalm,
+                since: None,
+            },
+            last_seen: None,
+            open: Vec::new(),
+            fist: Vec::new(),
+        }
+    }
+
+    /// Feeds one recognition result. Returns the fitted thresholds once,
+    /// when the fist has been held long enough.
+    pub fn push(&mut self, result: &GestureResult) -> Option<FingerClassifierConfig> {
+        let CalibrationStep::Capturing { pose, since } = &mut self.step else {
+            return None;
+        };
+        let pose = *pose;
+        let samples = match pose {
+            CalibrationPose::OpenPalm => &mut self.open,
+            CalibrationPose::Fist => &mut self.fist,
+        };
+        let Some(metrics) = result.raw_landmarks.as_deref().and_then(finger_metrics) else {
+            // The pose has to be held without interruption.
+            *since = None;
+            samples.clear();
+            return None;
+        };
+
+        self.last_seen = Some(result.timestamp);
+        let started = *since.get_or_insert(result.timestamp);
+        let elapsed = result.timestamp.saturating_duration_since(started);
+        if elapsed >= CALIBRATION_SETTLE {
+            samples.push(metrics);
+        }
+        if elapsed < CALIBRATION_SETTLE + CALIBRATION_HOLD {
+            return None;
+        }
+
+        match pose {
+            CalibrationPose::OpenPalm => {
+                self.step = CalibrationStep::Capturing {
+                    pose: CalibrationPose::Fist,
+                    since: None,
+                };
+                None
+            }
+            CalibrationPose::Fist => match self.base.calibrated(&self.open, &self.fist) {
+                Some(config) if config != self.base => {
+                    self.step = CalibrationStep::Finished;
+                    Some(config)
+                }
+                _ => {
+                    self.step = CalibrationStep::Failed;
+                    None
+                }
+            },
+        }
+    }
+
+    /// Share of the current pose's settle and hold time already done.
+    fn progress(&self) -> f32 {
+        match self.step {
+            CalibrationStep::Capturing {
+                since: Some(since), ..
+            } => {
+                let total = CALIBRATION_SETTLE + CALIBRATION_HOLD;
+                let elapsed = self
+                    .last_seen
+                    .map_or(Duration::ZERO, |seen| seen.saturating_duration_since(since));
+                (elapsed.as_secs_f32() / total.as_secs_f32()).clamp(0.0, 1.0)
+            }
+            CalibrationStep::Capturing { since: None, .. } => 0.0,
+            CalibrationStep::Finished | CalibrationStep::Failed => 1.0,
+        }
+    }
+}
+
+impl AppView {
+    pub(super) fn start_calibration(&mut self) {
+        let base = self
+            .settings
+            .finger_config
+            .unwrap_or_else(|| self.recognizer_backend.finger_config());
+        self.screen = Screen::Calibration(Calibration::new(base));
+    }
+
+    /// Stores and applies calibrated thresholds.
+    fn apply_finger_config(&mut self, config: FingerClassifierConfig) {
+        self.recognizer_backend.finger_control().set(config);
+        self.settings.finger_config = Some(config);
+        self.settings.save();
+    }
+
+    pub(super) fn render_calibration(
+        &mut self,
+        calibration: &mut Calibration,
+        window: &mut Window,
+        cx: &mut Context<'_, Self>,
+    ) -> AnyElement {
+        let mut fitted = None;
+        self.poll_composited_frames(window, cx, |result| {
+            if let Some(config) = calibration.push(result) {
+                fitted = Some(config);
+            }
+        });
+        if let Some(config) = fitted {
+            log::info!("finger thresholds calibrated: {config:?}");
+            self.apply_finger_config(config);
+        }
+
+        let titlebar = self.render_titlebar(
+            gpui::hsla(0.55, 0.8, 0.6, 1.0),
+            "●",
+            "校准中",
+            gpui::hsla(0.3, 0.8, 0.5, 1.0),
+            "●",
+            "摄像头就绪",
+            window,
+            cx,
+        );
+
+        let (title, hint) = match calibration.step {
+            CalibrationStep::Capturing {
+                pose: CalibrationPose::OpenPalm,
+                since,
+            } => (
+                "第 1 步：张开手掌",
+                if since.is_some() {
+                    "保持五指张开，不要移动"
+                } else {
+                    "将张开的手掌对准摄像头"
+                },
+            ),
+            CalibrationStep::Capturing {
+                pose: CalibrationPose::Fist,
+                since,
+            } => (
+                "第 2 步：握拳",
+                if since.is_some() {
+                    "保持握拳，不要移动"
+                } else {
+                    "握紧拳头对准摄像头"
+                },
+            ),
+            CalibrationStep::Finished => ("校准完成", "新的手指阈值已保存并生效"),
+            CalibrationStep::Failed => (
+                "校准失败",
+                "张开与握拳的差别太小，请让整只手留在画面内后重试",
+            ),
+        };
+        let done = matches!(
+            calibration.step,
+            CalibrationStep::Finished | CalibrationStep::Failed
+        );
+
+        let preview_height = CALIBRATION_PREVIEW_WIDTH / self.camera_aspect_ratio();
+        let preview: AnyElement = match &self.latest_image {
+            Some(image) => super::img(image.clone())
+                .size_full()
+                .object_fit(ObjectFit::Contain)
+                .into_any_element(),
+            None => super::div().size_full().into_any_element(),
+        };
+
+        let card = v_flex()
+            .w(super::px(CALIBRATION_PREVIEW_WIDTH))
+            .gap_3()
+            .p_4()
+            .rounded_lg()
+            .bg(gpui::rgb(0x0f172a))
+            .border_1()
+            .border_color(gpui::rgba(0xffffff1a))
+            .child(
+                super::div()
+                    .w_full()
+                    .h(super::px(preview_height))
+                    .rounded_md()
+                    .overflow_hidden()
+                    .bg(gpui::rgb(0x000000))
+                    .child(preview),
+            )
+            .child(
+                super::div()
+                    .text_lg()
+                    .font_semibold()
+                    .text_color(gpui::rgb(0xffffff))
+                    .child(title),
+            )
+            .child(self.caption(gpui::rgb(0x94a3b8)).child(hint))
+            .when(!done, |this| {
+                this.child(
+                    Progress::new()
+                        .bg(gpui::rgb(0x38bdf8))
+                        .value(calibration.progress() * 100.0),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .when(done, |this| {
+                        this.child(
+                            Button::new(SharedString::from("calibration-restart"))
+                                .outline()
+                                .label("重新校准")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.start_calibration();
+                                    cx.notify();
+                                })),
+                        )
+                    })
+                    .child(
+                        Button::new(SharedString::from("calibration-reset"))
+                            .outline()
+                            .label("恢复默认")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.recognizer_backend
+                                    .finger_control()
+                                    .set(this.recognizer_backend.finger_config());
+                                this.settings.finger_config = None;
+                                this.settings.save();
+                                this.screen = Screen::Main;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new(SharedString::from("calibration-close"))
+                            .outline()
+                            .label(if done { "完成" } else { "取消" })
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.screen = Screen::Main;
+                                cx.notify();
+                            })),
+                    ),
+            );
+
+        v_flex()
+            .size_full()
+            .bg(gpui::rgb(0x1a2332))
+            .child(titlebar)
+            .child(
+                super::div()
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .child(card),
+            )
+            .into_any_element()
+    }
+}
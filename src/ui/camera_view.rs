@@ -4,6 +4,9 @@ use super::{
     v_flex,
 };
 use crate::pipeline;
+use crossbeam_channel::TryRecvError;
+use gpui_component::checkbox::Checkbox;
+use std::{thread, time::Instant};
 
 impl AppView {
     fn render_camera_picker_startup(
@@ -113,6 +116,18 @@ impl AppView {
             );
         }
 
+        picker = picker.child(
+            div().mt_2().child(
+                Checkbox::new("remember-camera")
+                    .label("记住选择并自动启动")
+                    .checked(self.remember_camera)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.set_remember_camera(*checked);
+                        cx.notify();
+                    })),
+            ),
+        );
+
         if let Some(err) = error_msg {
             picker = picker.child(
                 div()
@@ -123,6 +138,20 @@ impl AppView {
             );
         }
 
+        if !self.download.finished {
+            picker = picker.child(
+                div()
+                    .mt_2()
+                    .text_xs()
+                    .text_color(gpui::rgb(0x737373))
+                    .child(format!(
+                        "{} {}",
+                        self.download.message,
+                        super::download::progress_detail(&self.download)
+                    )),
+            );
+        }
+
         picker.into_any_element()
     }
 
@@ -282,7 +311,13 @@ impl AppView {
     }
 
     pub(super) fn initial_camera_state() -> (CameraState, Vec<CameraDevice>) {
-        match pipeline::available_cameras() {
+        Self::camera_state_from(pipeline::available_cameras())
+    }
+
+    fn camera_state_from(
+        cameras: anyhow::Result<Vec<CameraDevice>>,
+    ) -> (CameraState, Vec<CameraDevice>) {
+        match cameras {
             Ok(cameras) if cameras.is_empty() => (
                 CameraState::Unavailable {
                     message: String::new(),
@@ -400,18 +435,10 @@ impl AppView {
                                 .border_color(gpui::rgb(0x262626))
                                 .cursor_pointer()
                                 .hover(|this| this.bg(gpui::rgb(0x262626)))
-                                .on_mouse_down(
-                                    gpui::MouseButton::Left,
-                                    cx.listener(|this, _, _, cx| {
-                                        this.is_refreshing_cameras = true;
-                                        cx.notify();
-                                    }),
-                                )
                                 .on_mouse_up(
                                     gpui::MouseButton::Left,
                                     cx.listener(|this, _, _, cx| {
                                         this.refresh_cameras();
-                                        this.is_refreshing_cameras = false;
                                         cx.notify();
                                     }),
                                 )
@@ -551,15 +578,90 @@ impl AppView {
     fn start_camera_for_device(&mut self, device: &CameraDevice) -> Result<(), String> {
         self.stop_camera_stream();
 
-        pipeline::start_camera_stream(device.index.clone(), self.camera_frame_tx.clone())
-            .map(|stream| {
-                self.camera_stream = Some(stream);
-                self.latest_frame = None;
-                self.latest_result = None;
-                self.latest_image = None;
-                self.camera_error = None;
-            })
-            .map_err(|err| format!("{err:#}"))
+        pipeline::start_camera_stream(
+            device.index.clone(),
+            self.pipeline.frame_tx.clone(),
+            self.camera_capture,
+            self.recognizer_backend.hand_activity(),
+        )
+        .map(|stream| {
+            self.remember_camera_choice(device);
+            self.camera_stream = Some(stream);
+            self.latest_frame = None;
+            self.camera_resolution = None;
+            self.frame_history.clear();
+            self.gesture_stability.clear();
+            self.latest_result = None;
+            self.latest_image = None;
+            self.camera_error = None;
+        })
+        .map_err(|err| format!("{err:#}"))
+    }
+
+    /// Starts the camera named by `--camera`, or else the remembered one,
+    /// directly. The picker stays up with a note when it can't be found or
+    /// opened.
+    pub(super) fn apply_camera_arg(&mut self, state: &mut CameraState) {
+        let remembered = self.auto_start_camera.take();
+        let (query, is_remembered) = match (self.camera_arg.take(), remembered) {
+            (Some(query), _) => (query, false),
+            (None, Some(label)) => (label, true),
+            (None, None) => return,
+        };
+        let CameraState::Selection {
+            options,
+            selected,
+            start_error,
+        } = state
+        else {
+            log::warn!("camera {query} not started: no cameras available");
+            return;
+        };
+
+        // The remembered label must match exactly, so a different camera
+        // sharing part of its name is never started unasked.
+        let found = if is_remembered {
+            options.iter().position(|device| device.label == query)
+        } else {
+            pipeline::find_camera(options, &query)
+        };
+        let Some(idx) = found else {
+            *start_error = Some(if is_remembered {
+                format!("上次使用的摄像头 \"{query}\" 未连接，请重新选择")
+            } else {
+                format!("未找到摄像头 \"{query}\"，请手动选择")
+            });
+            return;
+        };
+        *selected = idx;
+        let device = options[idx].clone();
+        match self.start_camera_for_device(&device) {
+            Ok(()) => {
+                self.available_cameras = options.clone();
+                self.selected_camera_idx = Some(idx);
+                *state = CameraState::Ready;
+            }
+            Err(err) => {
+                *start_error = Some(format!("无法启动摄像头: {err}"));
+            }
+        }
+    }
+
+    /// Saves `device` as the auto-start camera when remembering is on.
+    fn remember_camera_choice(&mut self, device: &CameraDevice) {
+        if !self.remember_camera || self.settings.auto_start_camera.as_ref() == Some(&device.label)
+        {
+            return;
+        }
+        self.settings.auto_start_camera = Some(device.label.clone());
+        self.settings.save();
+    }
+
+    fn set_remember_camera(&mut self, remember: bool) {
+        self.remember_camera = remember;
+        if !remember && self.settings.auto_start_camera.take().is_some() {
+            self.settings.save();
+        }
     }
 
     fn start_selected_camera(&mut self) {
@@ -600,14 +702,46 @@ impl AppView {
         }
     }
 
+    /// Re-enumerates cameras on a background thread; driver enumeration can
+    /// take long enough to visibly stall the UI. Clicks while a refresh is in
+    /// flight, or within the debounce window of the last one, are ignored.
     pub(super) fn refresh_cameras(&mut self) {
-        let (new_state, new_cameras) = Self::initial_camera_state();
-        self.screen = Screen::Camera(new_state);
+        if self.camera_refresh_rx.is_some() {
+            return;
+        }
+        if self
+            .last_camera_refresh
+            .is_some_and(|at| at.elapsed() < super::CAMERA_REFRESH_DEBOUNCE)
+        {
+            return;
+        }
+
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        thread::spawn(move || {
+            let _ = tx.send(pipeline::available_cameras());
+        });
+        self.camera_refresh_rx = Some(rx);
+        self.last_camera_refresh = Some(Instant::now());
+        self.is_refreshing_cameras = true;
+    }
+
+    /// Returns the refreshed camera screen state once enumeration finishes.
+    pub(super) fn poll_camera_refresh(&mut self) -> Option<CameraState> {
+        let cameras = match self.camera_refresh_rx.as_ref()?.try_recv() {
+            Ok(cameras) => cameras,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("camera enumeration stopped")),
+        };
+        self.camera_refresh_rx = None;
+        self.is_refreshing_cameras = false;
+
+        let (new_state, new_cameras) = Self::camera_state_from(cameras);
         self.available_cameras = new_cameras;
         self.selected_camera_idx = if self.available_cameras.is_empty() {
             None
         } else {
             Some(0)
         };
+        Some(new_state)
     }
 }
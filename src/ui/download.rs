@@ -2,14 +2,12 @@ use super::{
     AnyElement, AppView, Context, DownloadMessage, DownloadState, IntoElement, ParentElement,
     RecognizerBackend, Sender, Styled, StyledExt, div, h_flex, thread, v_flex,
 };
-use crate::model_download::{
-    ensure_gesture_classifier_model_ready, ensure_handpose_estimator_model_ready,
-    ensure_palm_detector_model_ready,
-};
+use crate::model_download::{ModelKind, prepare_model_source};
 use gpui::{SharedString, px};
 
 impl AppView {
-    pub(super) fn poll_download_events(&mut self, state: &mut DownloadState) {
+    pub(super) fn poll_download_events(&mut self) {
+        let state = &mut self.download;
         while let Ok(msg) = self.download_rx.try_recv() {
             match msg {
                 DownloadMessage::Event(event) => state.update_from_event(event),
@@ -22,20 +20,10 @@ impl AppView {
         }
     }
 
-    pub(super) fn render_download_view(
-        &self,
-        state: &DownloadState,
-        _cx: &mut Context<'_, Self>,
-    ) -> AnyElement {
+    pub(super) fn render_download_view(&self, _cx: &mut Context<'_, Self>) -> AnyElement {
+        let state = &self.download;
         let bar = progress_bar_string(state.downloaded, state.total);
-        let detail = match (state.total, state.finished) {
-            (_, true) => "下载完成".to_string(),
-            (Some(total), false) if total > 0 => {
-                let percent = (state.downloaded as f64 / total as f64 * 100.0).clamp(0.0, 100.0);
-                format!("{percent:.1}%")
-            }
-            _ => format!("{:.1} MB", state.downloaded as f64 / 1024.0 / 1024.0),
-        };
+        let detail = progress_detail(state);
 
         let (status_icon, status_text, status_color) = if state.finished && state.error.is_none() {
             ("✓", "模型就绪", gpui::rgb(0x4ade80))
@@ -104,6 +92,22 @@ impl AppView {
                         .text_color(gpui::rgb(0xa3a3a3))
                         .child(state.message.clone()),
                 );
+            if let Some(throughput) = throughput_string(state) {
+                container = container.child(
+                    div()
+                        .text_xs()
+                        .text_color(gpui::rgb(0x737373))
+                        .child(throughput),
+                );
+            }
+            if self.camera_stream.is_some() {
+                container = container.child(
+                    div()
+                        .text_xs()
+                        .text_color(gpui::rgb(0x737373))
+                        .child("摄像头已就绪，模型下载完成后自动开始识别"),
+                );
+            }
         } else if let Some(err) = &state.error {
             container = container.child(
                 v_flex()
@@ -151,33 +155,54 @@ pub(super) fn spawn_model_download(
         let gesture_classifier_model_path =
             crate::model_download::default_gesture_classifier_model_path();
 
-        if let Err(err) = ensure_palm_detector_model_ready(&palm_detector_model_path, |event| {
-            let _ = tx.send(DownloadMessage::Event(event));
-        }) {
-            log::error!("failed to prepare palm detector model: {err:?}");
-            let _ = tx.send(DownloadMessage::Error(format!("{err:#}")));
-            return;
-        }
+        let prefer_embedded = backend.prefer_embedded_models();
+        let models = [
+            (ModelKind::PalmDetector, palm_detector_model_path),
+            (ModelKind::HandposeEstimator, handpose_estimator_model_path),
+            (ModelKind::GestureClassifier, gesture_classifier_model_path),
+        ];
 
-        if let Err(err) =
-            ensure_handpose_estimator_model_ready(&handpose_estimator_model_path, |event| {
+        for (model, path) in models {
+            if let Err(err) = prepare_model_source(model, &path, prefer_embedded, |event| {
                 let _ = tx.send(DownloadMessage::Event(event));
-            })
-        {
-            log::error!("failed to prepare handpose estimator model: {err:?}");
-            let _ = tx.send(DownloadMessage::Error(format!("{err:#}")));
-            return;
+            }) {
+                log::error!("failed to prepare {model:?} model: {err:?}");
+                let _ = tx.send(DownloadMessage::Error(format!("{err:#}")));
+                return;
+            }
         }
+    })
+}
 
-        if let Err(err) =
-            ensure_gesture_classifier_model_ready(&gesture_classifier_model_path, |event| {
-                let _ = tx.send(DownloadMessage::Event(event));
-            })
-        {
-            log::error!("failed to prepare gesture classifier model: {err:?}");
-            let _ = tx.send(DownloadMessage::Error(format!("{err:#}")));
+/// One-line progress for the current model, e.g. "42.0%" or "3.1 MB".
+pub(super) fn progress_detail(state: &DownloadState) -> String {
+    match (state.total, state.finished) {
+        (_, true) => "下载完成".to_string(),
+        (Some(total), false) if total > 0 => {
+            let percent = (state.downloaded as f64 / total as f64 * 100.0).clamp(0.0, 100.0);
+            format!("{percent:.1}%")
         }
-    })
+        _ => format!("{:.1} MB", state.downloaded as f64 / 1024.0 / 1024.0),
+    }
+}
+
+/// "3.2 MB/s, 约 12 秒" once a speed sample exists; the ETA is dropped when
+/// the server sent no content length.
+fn throughput_string(state: &DownloadState) -> Option<String> {
+    if state.finished {
+        return None;
+    }
+    let speed = state.bytes_per_sec?;
+    let rate = format!("{:.1} MB/s", speed / 1024.0 / 1024.0);
+    match state.eta_secs() {
+        Some(eta) if eta >= 60.0 => Some(format!(
+            "{rate}, 约 {} 分 {} 秒",
+            eta as u64 / 60,
+            eta as u64 % 60
+        )),
+        Some(eta) => Some(format!("{rate}, 约 {} 秒", eta.ceil() as u64)),
+        None => Some(rate),
+    }
 }
 
 fn progress_bar_string(downloaded: u64, total: Option<u64>) -> String {
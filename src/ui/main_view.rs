@@ -1,14 +1,21 @@
-use super::render_util::frame_to_image;
+use super::render_util::{fit_pixel_budget, frame_to_image};
+use super::settings::PreviewFit;
 use super::{
-    ActiveTheme, AnyElement, AppView, Button, Context, DEFAULT_CAMERA_RATIO, FluentBuilder,
-    InteractiveElement, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    ObjectFit, PanelResizeState, ParentElement, RIGHT_PANEL_MAX_WIDTH, RIGHT_PANEL_MIN_WIDTH,
-    SharedString, Styled, StyledImage, Window, h_flex, v_flex,
+    ActiveTheme, AnyElement, AppView, Button, Context, DEFAULT_CAMERA_RATIO,
+    DETECTION_ZONE_FRACTION, FluentBuilder, InteractiveElement, IntoElement, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PanelResizeState, ParentElement,
+    RIGHT_PANEL_MAX_WIDTH, RIGHT_PANEL_MIN_WIDTH, SharedString, Styled, StyledImage, Window,
+    h_flex, v_flex,
 };
-use crate::pipeline::CompositedFrame;
-use crate::types::{FingerState, GestureMotion};
-use gpui_component::StyledExt;
+use crate::pipeline::{CompositedFrame, recognizer::PowerSaveConfig};
+use crate::snapshot_output::SNAPSHOT_LOOKBACK;
+use crate::types::{FingerState, GestureKind, GestureMotion, GestureResult, Handedness};
+use gpui::{ClipboardItem, StatefulInteractiveElement};
+use gpui_component::{Disableable, StyledExt, progress::Progress, slider::Slider};
 use std::sync::Arc;
+use std::time::Instant;
+
+const FINGER_LABELS: [&str; 5] = ["拇指", "食指", "中指", "无名指", "小指"];
 
 impl AppView {
     pub(super) fn render_main(
@@ -16,28 +23,7 @@ impl AppView {
         window: &mut Window,
         cx: &mut Context<'_, Self>,
     ) -> AnyElement {
-        let composited_rx = self.composited_rx.take();
-        if let Some(rx) = composited_rx.as_ref() {
-            let mut frames = Vec::new();
-            while let Ok(frame) = rx.try_recv() {
-                frames.push(frame);
-            }
-
-            for frame in frames {
-                let CompositedFrame { frame, result } = frame;
-
-                self.latest_result = Some(result);
-
-                if let Some(image) = frame_to_image(&frame, None) {
-                    self.replace_latest_image(image, window, cx);
-                }
-                self.latest_frame = Some(frame);
-                if let Some(ts) = self.latest_frame.as_ref().map(|f| f.timestamp) {
-                    self.update_fps(ts);
-                }
-            }
-        }
-        self.composited_rx = composited_rx;
+        self.poll_composited_frames(window, cx, |_| {});
 
         let camera_label = self
             .selected_camera_idx
@@ -52,34 +38,45 @@ impl AppView {
             });
 
         let frame_status = self
-            .latest_frame
-            .as_ref()
-            .map(|f| format!("摄像头: {camera_label} {}x{} (最新)", f.width, f.height))
+            .camera_resolution
+            .map(|(width, height)| format!("摄像头: {camera_label} {width}x{height} (最新)"))
             .unwrap_or_else(|| format!("摄像头: {camera_label}，等待画面..."));
 
         let confidence_text = self
-            .latest_result
-            .as_ref()
-            .map(|r| format!("{:.0}%", r.confidence * 100.0))
+            .smoothed_confidence
+            .map(|c| format!("{:.0}%", c * 100.0))
             .unwrap_or_else(|| "--".to_string());
         let fps_text = self
             .latest_fps
             .as_ref()
             .map(|v| format!("{:.1} fps", v))
             .unwrap_or_else(|| "-- fps".to_string());
+        let drop_rate_text = |rate: Option<f32>| {
+            rate.map(|v| format!("{v:.0}/s"))
+                .unwrap_or_else(|| "--".to_string())
+        };
+        let drops_text = format!(
+            "识别 {} 界面 {}",
+            drop_rate_text(self.recognizer_drops.per_sec),
+            drop_rate_text(self.ui_drops.per_sec)
+        );
+        let latency_text = self
+            .latest_latency_ms
+            .as_ref()
+            .map(|v| format!("{:.0}ms", v))
+            .unwrap_or_else(|| "--".to_string());
 
         let ratio = self.camera_aspect_ratio();
         let panel_width = self
             .right_panel_width
             .clamp(RIGHT_PANEL_MIN_WIDTH, RIGHT_PANEL_MAX_WIDTH);
         self.right_panel_width = panel_width;
-        let camera_height =
-            (panel_width / ratio).clamp(super::CAMERA_MIN_SIZE.1, super::CAMERA_MAX_SIZE.1);
+        let camera_height = camera_height_for(panel_width, ratio);
 
         let frame_view: AnyElement = if let Some(image) = &self.latest_image {
             super::img(image.clone())
                 .size_full()
-                .object_fit(ObjectFit::Contain)
+                .object_fit(self.settings.preview_fit.object_fit())
                 .rounded_t_lg()
                 .into_any_element()
         } else {
@@ -95,14 +92,27 @@ impl AppView {
                 .into_any_element()
         };
 
-        let camera_shell = super::div()
+        // Shows through the `Contain` letterbox bars, so follow the theme
+        // rather than hard black.
+        let preview_background = cx.theme().background;
+        let mut camera_shell = super::div()
             .relative()
             .w(super::px(panel_width))
             .h(super::px(camera_height))
             .overflow_hidden()
             .rounded_t_lg()
-            .bg(gpui::rgb(0x000000))
+            .bg(preview_background)
             .child(frame_view);
+        if self.settings.show_detection_zone {
+            for element in self.detection_zone_overlay(panel_width, camera_height) {
+                camera_shell = camera_shell.child(element);
+            }
+        }
+        if self.show_landmark_indices {
+            for label in self.landmark_index_labels(panel_width, camera_height) {
+                camera_shell = camera_shell.child(label);
+            }
+        }
 
         let mut picker_panel: Option<AnyElement> = None;
         if self.camera_picker_open && !self.available_cameras.is_empty() {
@@ -118,12 +128,7 @@ impl AppView {
                     .border_1()
                     .border_color(gpui::rgba(0xef4444ff))
                     .child(super::div().text_base().child("⚠️"))
-                    .child(
-                        super::div()
-                            .text_xs()
-                            .text_color(gpui::rgb(0xfca5a5))
-                            .child(err.clone()),
-                    )
+                    .child(self.caption(gpui::rgb(0xfca5a5)).child(err.clone()))
                     .into_any_element(),
             );
         }
@@ -132,17 +137,43 @@ impl AppView {
             .gap_3()
             .items_center()
             .child(
-                super::div()
-                    .text_xs()
-                    .text_color(gpui::rgb(0xa0aab8))
+                self.caption(gpui::rgb(0xa0aab8))
                     .child(format!("置信度: {confidence_text}")),
             )
             .child(
-                super::div()
-                    .text_xs()
-                    .text_color(gpui::rgb(0xa0aab8))
+                self.caption(gpui::rgb(0xa0aab8))
                     .child(format!("帧率: {fps_text}")),
+            )
+            .child(
+                self.caption(gpui::rgb(0xa0aab8))
+                    .child(format!("延迟 {latency_text}")),
+            )
+            .child(
+                self.caption(gpui::rgb(0xa0aab8))
+                    .child(format!("丢帧 {drops_text}")),
             );
+        let provider_text = self
+            .recognizer_backend
+            .provider_status()
+            .get()
+            .map(|provider| provider.label())
+            .unwrap_or("--");
+        let metrics = metrics.child(
+            self.caption(gpui::rgb(0xa0aab8))
+                .child(format!("推理后端: {provider_text}")),
+        );
+        let power_saving = self
+            .latest_result
+            .as_ref()
+            .is_some_and(|result| result.power_saving);
+        let metrics = if power_saving {
+            metrics.child(
+                self.caption(gpui::rgb(0xfbbf24))
+                    .child("节能模式 — 未检测到手"),
+            )
+        } else {
+            metrics
+        };
 
         let mut info_row = h_flex()
             .justify_between()
@@ -150,13 +181,111 @@ impl AppView {
             .gap_2()
             .child(metrics);
 
+        let mut info_buttons = h_flex().gap_2().items_center().child(
+            Button::new(SharedString::from("high-contrast-toggle"))
+                .outline()
+                .label(if self.settings.high_contrast {
+                    "高对比度: 开"
+                } else {
+                    "高对比度: 关"
+                })
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.settings.high_contrast = !this.settings.high_contrast;
+                    this.settings.save();
+                    cx.notify();
+                })),
+        );
+        info_buttons = info_buttons.child(
+            Button::new(SharedString::from("detection-zone-toggle"))
+                .outline()
+                .label(if self.settings.show_detection_zone {
+                    "引导框: 开"
+                } else {
+                    "引导框: 关"
+                })
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.settings.show_detection_zone = !this.settings.show_detection_zone;
+                    this.settings.save();
+                    cx.notify();
+                })),
+        );
+        info_buttons = info_buttons.child(
+            Button::new(SharedString::from("preview-fit"))
+                .outline()
+                .label(format!("画面: {}", self.settings.preview_fit.label()))
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.settings.preview_fit = this.settings.preview_fit.next();
+                    this.settings.save();
+                    cx.notify();
+                })),
+        );
+        info_buttons = info_buttons.child(
+            Button::new(SharedString::from("two-hands-toggle"))
+                .outline()
+                .label(if self.settings.max_hands > 1 {
+                    "双手: 开"
+                } else {
+                    "双手: 关"
+                })
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.settings.max_hands = if this.settings.max_hands > 1 { 1 } else { 2 };
+                    this.settings.save();
+                    this.recognizer_backend
+                        .max_hands()
+                        .set(this.settings.max_hands);
+                    cx.notify();
+                })),
+        );
+        info_buttons = info_buttons.child(
+            Button::new(SharedString::from("power-save-toggle"))
+                .outline()
+                .label(if self.settings.power_save {
+                    "节能: 开"
+                } else {
+                    "节能: 关"
+                })
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.settings.power_save = !this.settings.power_save;
+                    this.settings.save();
+                    this.recognizer_backend
+                        .power_save()
+                        .set(this.settings.power_save.then(PowerSaveConfig::default));
+                    cx.notify();
+                })),
+        );
+        info_buttons = info_buttons.child(
+            Button::new(SharedString::from("finger-calibration"))
+                .outline()
+                .label(if self.settings.finger_config.is_some() {
+                    "手指校准: 已校准"
+                } else {
+                    "手指校准"
+                })
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.start_calibration();
+                    cx.notify();
+                })),
+        );
+        info_buttons = info_buttons.child(
+            Button::new(SharedString::from("recording-toggle"))
+                .outline()
+                .label(if self.recorder.is_some() {
+                    "■ 停止录制"
+                } else {
+                    "● 开始录制"
+                })
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.toggle_recording();
+                    cx.notify();
+                })),
+        );
         if self.available_cameras.len() > 1 {
             let picker_label = if self.camera_picker_open {
                 "◉ 关闭"
             } else {
                 "◉ 切换"
             };
-            info_row = info_row.child(
+            info_buttons = info_buttons.child(
                 Button::new(SharedString::from("camera-picker-toggle"))
                     .outline()
                     .label(picker_label)
@@ -166,6 +295,9 @@ impl AppView {
                     })),
             );
         }
+        info_row = info_row.child(info_buttons);
+
+        let gamma_controls = self.render_gamma_controls(cx);
 
         let mut camera_card = super::div().relative().w(super::px(panel_width)).child(
             v_flex()
@@ -175,15 +307,19 @@ impl AppView {
                 .bg(gpui::rgb(0x0f1419))
                 .child(camera_shell)
                 .child(
-                    v_flex().gap_2().p_3().child(info_row).child(
-                        super::div()
-                            .text_xs()
-                            .text_color(gpui::rgb(0x8b95a5))
-                            .overflow_hidden()
-                            .text_ellipsis()
-                            .whitespace_nowrap()
-                            .child(frame_status.clone()),
-                    ),
+                    v_flex()
+                        .gap_2()
+                        .p_3()
+                        .child(info_row)
+                        .child(gamma_controls)
+                        .child(self.render_threshold_control())
+                        .child(
+                            self.caption(gpui::rgb(0x8b95a5))
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .whitespace_nowrap()
+                                .child(frame_status.clone()),
+                        ),
                 ),
         );
 
@@ -205,20 +341,29 @@ impl AppView {
 
         let theme = cx.theme();
 
-        let (camera_icon, camera_text, camera_color) = if self.latest_frame.is_some() {
+        let camera_stalled = self
+            .camera_stream
+            .as_ref()
+            .is_some_and(|stream| !stream.health().responsive());
+        let (camera_icon, camera_text, camera_color) = if camera_stalled {
+            ("!", "摄像头无响应", theme.danger)
+        } else if self.latest_frame.is_some() {
             ("●", "摄像头就绪", theme.success)
         } else {
             ("○", "等待摄像头", theme.muted_foreground)
         };
 
         let (recognizer_icon, recognizer_text, recognizer_color) =
-            if self.recognizer_handle.is_some() {
+            if self.pipeline.recognizer_running() {
                 ("●", "识别运行中", theme.success)
             } else {
                 ("○", "正在初始化", theme.muted_foreground)
             };
 
         let gesture_panel = self.render_gesture_panel(panel_width, cx);
+        let gesture_legend = self
+            .show_gesture_legend
+            .then(|| self.render_gesture_legend(panel_width));
 
         let panel_handle = super::div()
             .absolute()
@@ -238,7 +383,19 @@ impl AppView {
             .w(super::px(panel_width))
             .h_full()
             .overflow_hidden()
-            .child(v_flex().gap_3().child(camera_card).child(gesture_panel))
+            .child(
+                // Scrolls once the gesture legend no longer fits.
+                super::div()
+                    .id("right-panel-content")
+                    .flex()
+                    .flex_col()
+                    .size_full()
+                    .overflow_y_scroll()
+                    .gap_3()
+                    .child(camera_card)
+                    .child(gesture_panel)
+                    .children(gesture_legend),
+            )
             .child(panel_handle);
 
         let titlebar = self.render_titlebar(
@@ -273,9 +430,263 @@ impl AppView {
             .into_any_element()
     }
 
+    /// Drains the compositor: updates the result, preview and stats, and
+    /// shows every result to `on_result` first, even while locked.
+    pub(super) fn poll_composited_frames(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<'_, Self>,
+        mut on_result: impl FnMut(&GestureResult),
+    ) {
+        self.render_activity.mark();
+        let frames: Vec<_> = self.pipeline.composited_rx.try_iter().collect();
+        for frame in frames {
+            let CompositedFrame { frame, result } = frame;
+            on_result(&result);
+            let primary = result.detail.as_ref().map(|d| d.primary);
+            let smoothed = (self.export_smoothed
+                && (self.openpose_output.is_some() || self.ipc_output.is_some()))
+            .then(|| result.with_smoothed_landmarks());
+            let exported = smoothed.as_ref().unwrap_or(&result);
+            if let Some(output) = &mut self.openpose_output
+                && output.accept(primary)
+                && let Some(json) = exported.openpose_json()
+            {
+                output.send(exported.sequence, json);
+            }
+            if let Some(output) = &mut self.ipc_output
+                && output.accept(primary)
+            {
+                output.send(exported.event_json());
+            }
+
+            if !self.result_locked {
+                self.gesture_stability.push(primary);
+                if let Some(kind) = self.gesture_trigger.update(primary, result.timestamp) {
+                    log::info!("gesture triggered: {}", kind.display_name());
+                    if let Some(output) = &self.emoji_output {
+                        output.send(kind);
+                    }
+                    if kind == GestureKind::TakePicture
+                        && let Some(output) = &self.snapshot_output
+                        && let Some(frame) = self.frame_history.frame_at(SNAPSHOT_LOOKBACK)
+                    {
+                        output.send(frame.clone());
+                    }
+                }
+                if let Some(event) = self.grab_detector.update(primary, result.timestamp) {
+                    log::info!("grab event: {event:?}");
+                }
+                self.update_confidence(&result);
+                self.latest_result = Some(result);
+            }
+
+            self.camera_resolution = Some((frame.width, frame.height));
+            let frame = fit_pixel_budget(frame, self.preview_pixel_budget);
+            if let Some(recorder) = &self.recorder {
+                recorder.push(&frame);
+            }
+            if let Some(image) = frame_to_image(&frame, None) {
+                self.replace_latest_image(image, window, cx);
+            }
+            if self.snapshot_output.is_some() {
+                self.frame_history.push(frame.clone());
+            }
+            self.latest_frame = Some(frame);
+            if let Some(ts) = self.latest_frame.as_ref().map(|f| f.timestamp) {
+                self.update_fps(ts);
+                self.update_latency(ts);
+            }
+        }
+        let now = Instant::now();
+        let recognition_fps = self.recognizer_backend.recognition_rate().fps();
+        self.low_fps_alert.update(recognition_fps, now);
+        self.recognizer_drops.update(now);
+        self.ui_drops.update(now);
+    }
+
+    /// Per-axis scale and offset mapping camera pixels into the preview box
+    /// under the current [`PreviewFit`], or `None` before the first frame.
+    /// The skeleton is drawn into the frame itself and always lines up; the
+    /// overlays placed with this transform follow the same fit, so under
+    /// `Cover` the parts mapped outside the box are clipped with the image.
+    fn preview_transform(&self, box_width: f32, box_height: f32) -> Option<(f32, f32, f32, f32)> {
+        let (frame_w, frame_h) = self.camera_resolution?;
+        if frame_w == 0 || frame_h == 0 {
+            return None;
+        }
+        let fit_x = box_width / frame_w as f32;
+        let fit_y = box_height / frame_h as f32;
+        let (scale_x, scale_y) = match self.settings.preview_fit {
+            PreviewFit::Contain => (fit_x.min(fit_y), fit_x.min(fit_y)),
+            PreviewFit::Cover => (fit_x.max(fit_y), fit_x.max(fit_y)),
+            PreviewFit::Fill => (fit_x, fit_y),
+        };
+        let offset_x = (box_width - frame_w as f32 * scale_x) / 2.0;
+        let offset_y = (box_height - frame_h as f32 * scale_y) / 2.0;
+        Some((scale_x, scale_y, offset_x, offset_y))
+    }
+
+    /// Centered guide rectangle over the preview with everything outside it
+    /// dimmed. Hands near the frame edge are often cut off before the palm
+    /// detector sees them, so this shows new users where to hold them.
+    fn detection_zone_overlay(&self, box_width: f32, box_height: f32) -> Vec<AnyElement> {
+        let Some((_, _, offset_x, offset_y)) = self.preview_transform(box_width, box_height) else {
+            return Vec::new();
+        };
+        let image_w = box_width - 2.0 * offset_x;
+        let image_h = box_height - 2.0 * offset_y;
+        let zone_w = image_w * DETECTION_ZONE_FRACTION.0;
+        let zone_h = image_h * DETECTION_ZONE_FRACTION.1;
+        let left = offset_x + (image_w - zone_w) / 2.0;
+        let top = offset_y + (image_h - zone_h) / 2.0;
+
+        let shade = |x: f32, y: f32, w: f32, h: f32| {
+            super::div()
+                .absolute()
+                .left(super::px(x))
+                .top(super::px(y))
+                .w(super::px(w.max(0.0)))
+                .h(super::px(h.max(0.0)))
+                .bg(gpui::rgba(0x00000073))
+                .into_any_element()
+        };
+        vec![
+            shade(0.0, 0.0, box_width, top),
+            shade(0.0, top + zone_h, box_width, box_height - top - zone_h),
+            shade(0.0, top, left, zone_h),
+            shade(left + zone_w, top, box_width - left - zone_w, zone_h),
+            super::div()
+                .absolute()
+                .left(super::px(left))
+                .top(super::px(top))
+                .w(super::px(zone_w))
+                .h(super::px(zone_h))
+                .rounded_lg()
+                .border_2()
+                .border_color(gpui::rgba(0x38bdf8cc))
+                .child(
+                    super::div()
+                        .absolute()
+                        .left(super::px(8.0))
+                        .top(super::px(6.0))
+                        .text_xs()
+                        .text_color(gpui::rgb(0x7dd3fc))
+                        .child("将手放在框内"),
+                )
+                .into_any_element(),
+        ]
+    }
+
+    /// Index numbers placed next to each landmark of the latest result, in
+    /// the coordinates of the preview box.
+    fn landmark_index_labels(&self, box_width: f32, box_height: f32) -> Vec<AnyElement> {
+        let (Some((scale_x, scale_y, offset_x, offset_y)), Some(landmarks)) = (
+            self.preview_transform(box_width, box_height),
+            self.latest_result
+                .as_ref()
+                .and_then(|result| result.landmarks.as_ref()),
+        ) else {
+            return Vec::new();
+        };
+
+        landmarks
+            .iter()
+            .enumerate()
+            .map(|(idx, (x, y))| {
+                super::div()
+                    .absolute()
+                    .left(super::px(offset_x + x * scale_x + 4.0))
+                    .top(super::px(offset_y + y * scale_y - 6.0))
+                    .text_xs()
+                    .font_semibold()
+                    .text_color(gpui::rgb(0xfde047))
+                    .child(idx.to_string())
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_gamma_controls(&self, cx: &mut Context<'_, Self>) -> AnyElement {
+        let gamma = self.recognizer_backend.gamma();
+        let enabled = gamma.enabled();
+
+        h_flex()
+            .gap_2()
+            .items_center()
+            .child(
+                Button::new(SharedString::from("gamma-toggle"))
+                    .outline()
+                    .label(if enabled {
+                        "低光增强: 开"
+                    } else {
+                        "低光增强: 关"
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let gamma = this.recognizer_backend.gamma();
+                        gamma.set_enabled(!gamma.enabled());
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new(SharedString::from("gamma-decrease"))
+                    .outline()
+                    .label("−")
+                    .disabled(!enabled)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let gamma = this.recognizer_backend.gamma();
+                        gamma.set_gamma(gamma.gamma() - super::GAMMA_STEP);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                self.caption(gpui::rgb(0xa0aab8))
+                    .child(format!("伽马: {:.1}", gamma.gamma())),
+            )
+            .child(
+                Button::new(SharedString::from("gamma-increase"))
+                    .outline()
+                    .label("+")
+                    .disabled(!enabled)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let gamma = this.recognizer_backend.gamma();
+                        gamma.set_gamma(gamma.gamma() + super::GAMMA_STEP);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new(SharedString::from("gamma-preview"))
+                    .outline()
+                    .label(if gamma.apply_to_preview() {
+                        "预览: 校正后"
+                    } else {
+                        "预览: 原始"
+                    })
+                    .disabled(!enabled)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let gamma = this.recognizer_backend.gamma();
+                        gamma.set_apply_to_preview(!gamma.apply_to_preview());
+                        cx.notify();
+                    })),
+            )
+            .into_any_element()
+    }
+
+    fn render_threshold_control(&self) -> AnyElement {
+        h_flex()
+            .gap_2()
+            .items_center()
+            .child(self.caption(gpui::rgb(0xa0aab8)).child("检测阈值"))
+            .child(Slider::new(&self.threshold_slider).flex_1())
+            .child(self.caption(gpui::rgb(0xa0aab8)).child(format!(
+                "{:.2}",
+                self.recognizer_backend.detection_threshold().get()
+            )))
+            .into_any_element()
+    }
+
     fn render_gesture_panel(&self, panel_width: f32, cx: &mut Context<'_, Self>) -> AnyElement {
         let theme = cx.theme();
-        let finger_labels = ["拇指", "食指", "中指", "无名指", "小指"];
 
         let (
             primary_text,
@@ -284,6 +695,7 @@ impl AppView {
             handedness_text,
             motion_state,
             finger_states,
+            probabilities,
         ) = match &self.latest_result {
             Some(result) => {
                 let detail = result.detail.as_ref();
@@ -299,8 +711,20 @@ impl AppView {
                     .map(|d| d.handedness.label().to_string())
                     .unwrap_or_else(|| "--".to_string());
                 let states = detail.map(|d| d.finger_states);
-                let conf = format!("{:.0}%", (result.confidence * 100.0).clamp(0.0, 100.0));
-                (primary, secondary, conf, handedness, motion, states)
+                let probabilities = detail
+                    .map(|d| d.probabilities.iter().take(5).copied().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                let confidence = self.smoothed_confidence.unwrap_or(result.confidence);
+                let conf = format!("{:.0}%", (confidence * 100.0).clamp(0.0, 100.0));
+                (
+                    primary,
+                    secondary,
+                    conf,
+                    handedness,
+                    motion,
+                    states,
+                    probabilities,
+                )
             }
             None => (
                 "等待手部进入画面".to_string(),
@@ -309,9 +733,54 @@ impl AppView {
                 "--".to_string(),
                 GestureMotion::Steady,
                 None,
+                Vec::new(),
             ),
         };
 
+        let index_middle_spread = self
+            .latest_result
+            .as_ref()
+            .and_then(|result| result.detail.as_ref())
+            .map(|detail| format!("{:.0}°", detail.finger_spreads[1]))
+            .unwrap_or_else(|| "--".to_string());
+        let roll_text = self
+            .latest_result
+            .as_ref()
+            .and_then(|result| result.detail.as_ref())
+            .map(|detail| format!("{:+.0}°", detail.roll_degrees))
+            .unwrap_or_else(|| "--".to_string());
+
+        let palm_score_text = self
+            .latest_result
+            .as_ref()
+            .and_then(|result| result.palm_score)
+            .map(|score| format!("{:.0}%", score * 100.0))
+            .unwrap_or_else(|| "--".to_string());
+        let handpose_text = self
+            .latest_result
+            .as_ref()
+            .filter(|result| !result.power_saving)
+            .map(|result| format!("{:.0}%", result.handpose_confidence * 100.0))
+            .unwrap_or_else(|| "--".to_string());
+
+        let stability = self.gesture_stability.ratio();
+        let stability_color = if stability >= 0.8 {
+            gpui::rgb(0x22c55e)
+        } else if stability >= 0.5 {
+            gpui::rgb(0xfbbf24)
+        } else {
+            gpui::rgb(0xf97316)
+        };
+
+        let landmarks_json = self
+            .latest_result
+            .as_ref()
+            .and_then(|result| result.landmarks_json());
+        let openpose_json = self
+            .latest_result
+            .as_ref()
+            .and_then(|result| result.openpose_json());
+
         let status_color = if finger_states.is_some() {
             theme.success
         } else {
@@ -328,7 +797,7 @@ impl AppView {
         let finger_block: AnyElement = if let Some(states) = finger_states {
             let mut first_row = h_flex().gap_2();
             let mut second_row = h_flex().gap_2();
-            for (idx, name) in finger_labels.iter().enumerate() {
+            for (idx, name) in FINGER_LABELS.iter().enumerate() {
                 let chip = self.finger_chip(name, states[idx]);
                 if idx < 3 {
                     first_row = first_row.child(chip);
@@ -336,27 +805,35 @@ impl AppView {
                     second_row = second_row.child(chip);
                 }
             }
-            v_flex()
-                .gap_1()
-                .child(first_row)
-                .child(second_row)
+            h_flex()
+                .gap_3()
+                .items_center()
+                .child(self.finger_diagram(&states))
+                .child(v_flex().gap_1().child(first_row).child(second_row))
                 .into_any_element()
         } else {
-            super::div()
-                .text_xs()
-                .text_color(gpui::rgb(0x6b7280))
+            self.caption(gpui::rgb(0x6b7280))
                 .child("等检测到手势后，这里会展示各手指的状态与动作")
                 .into_any_element()
         };
 
+        let high_contrast = self.settings.high_contrast;
         let mut container = v_flex()
             .w(super::px(panel_width))
             .gap_3()
             .p_4()
             .rounded_lg()
-            .bg(gpui::rgb(0x0f172a))
+            .bg(if high_contrast {
+                gpui::rgb(0x000000)
+            } else {
+                gpui::rgb(0x0f172a)
+            })
             .border_1()
-            .border_color(gpui::rgba(0xffffff1a))
+            .border_color(if high_contrast {
+                gpui::rgba(0xffffffff)
+            } else {
+                gpui::rgba(0xffffff1a)
+            })
             .child(
                 h_flex()
                     .w_full()
@@ -382,12 +859,80 @@ impl AppView {
                             ),
                     )
                     .child(
-                        super::div()
-                            .text_xs()
-                            .text_color(gpui::rgb(0x94a3b8))
-                            .child("实时更新"),
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(self.caption(gpui::rgb(0x94a3b8)).child("实时更新"))
+                            .child(
+                                Button::new(SharedString::from("lock-result"))
+                                    .outline()
+                                    .label(if self.result_locked {
+                                        "🔒 已锁定"
+                                    } else {
+                                        "锁定结果"
+                                    })
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.result_locked = !this.result_locked;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new(SharedString::from("gesture-legend"))
+                                    .outline()
+                                    .label(if self.show_gesture_legend {
+                                        "手势列表: 开"
+                                    } else {
+                                        "手势列表: 关"
+                                    })
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.show_gesture_legend = !this.show_gesture_legend;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new(SharedString::from("landmark-indices"))
+                                    .outline()
+                                    .label(if self.show_landmark_indices {
+                                        "关键点序号: 开"
+                                    } else {
+                                        "关键点序号: 关"
+                                    })
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.show_landmark_indices = !this.show_landmark_indices;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new(SharedString::from("copy-landmarks"))
+                                    .outline()
+                                    .label("复制关键点")
+                                    .disabled(landmarks_json.is_none())
+                                    .on_click(cx.listener(move |_, _, _, cx| {
+                                        if let Some(json) = &landmarks_json {
+                                            cx.write_to_clipboard(ClipboardItem::new_string(
+                                                json.clone(),
+                                            ));
+                                        }
+                                    })),
+                            )
+                            .child(
+                                Button::new(SharedString::from("copy-openpose"))
+                                    .outline()
+                                    .label("复制 OpenPose")
+                                    .disabled(openpose_json.is_none())
+                                    .on_click(cx.listener(move |_, _, _, cx| {
+                                        if let Some(json) = &openpose_json {
+                                            cx.write_to_clipboard(ClipboardItem::new_string(
+                                                json.clone(),
+                                            ));
+                                        }
+                                    })),
+                            ),
                     ),
             )
+            .when_some(self.render_hand_columns(), |this, columns| {
+                this.child(columns)
+            })
             .child(
                 h_flex()
                     .items_center()
@@ -396,7 +941,11 @@ impl AppView {
                         super::div()
                             .text_3xl()
                             .font_bold()
-                            .text_color(gpui::rgb(0xe0f2fe))
+                            .text_color(if high_contrast {
+                                gpui::rgb(0xffffff)
+                            } else {
+                                gpui::rgb(0xe0f2fe)
+                            })
                             .child(primary_text.clone()),
                     )
                     .child(
@@ -404,15 +953,18 @@ impl AppView {
                             .gap_1()
                             .child(
                                 super::div()
-                                    .text_sm()
-                                    .text_color(gpui::rgb(0xa5b4fc))
+                                    .when(high_contrast, |this| this.text_base())
+                                    .when(!high_contrast, |this| this.text_sm())
+                                    .text_color(if high_contrast {
+                                        gpui::rgb(0xffffff)
+                                    } else {
+                                        gpui::rgb(0xa5b4fc)
+                                    })
                                     .child("检测结果"),
                             )
                             .when(secondary_text.is_some(), |this| {
                                 this.child(
-                                    super::div()
-                                        .text_xs()
-                                        .text_color(gpui::rgb(0x94a3b8))
+                                    self.caption(gpui::rgb(0x94a3b8))
                                         .child(secondary_text.clone().unwrap_or_default()),
                                 )
                             }),
@@ -424,20 +976,46 @@ impl AppView {
                     .items_center()
                     .child(self.stat_chip("置信度", &confidence_text, theme.success))
                     .child(self.stat_chip("惯用手", &handedness_text, gpui::rgb(0x38bdf8)))
+                    .child(self.stat_chip("食中指张角", &index_middle_spread, gpui::rgb(0xa78bfa)))
+                    .child(self.stat_chip("手部旋转", &roll_text, gpui::rgb(0x2dd4bf)))
                     .child(motion_chip),
             )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(self.stat_chip("手掌检测", &palm_score_text, gpui::rgb(0xf472b6)))
+                    .child(self.stat_chip("关键点", &handpose_text, gpui::rgb(0xfb923c))),
+            )
             .child(
                 v_flex()
                     .gap_1()
                     .child(
-                        super::div()
-                            .text_xs()
-                            .text_color(gpui::rgb(0x94a3b8))
-                            .child("手指展开度"),
+                        self.caption(gpui::rgb(0x94a3b8))
+                            .child(format!("稳定度 {:.0}%", stability * 100.0)),
                     )
+                    .child(Progress::new().bg(stability_color).value(stability * 100.0)),
+            )
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(self.caption(gpui::rgb(0x94a3b8)).child("手指展开度"))
                     .child(finger_block),
             );
 
+        if !probabilities.is_empty() {
+            let mut rows = v_flex().gap_1();
+            for (kind, prob) in &probabilities {
+                rows = rows.child(self.probability_row(*kind, *prob));
+            }
+            container = container.child(
+                v_flex()
+                    .gap_1()
+                    .child(self.caption(gpui::rgb(0x94a3b8)).child("候选手势"))
+                    .child(rows),
+            );
+        }
+
         if finger_states.is_none() {
             container = container.child(
                 super::div()
@@ -451,7 +1029,117 @@ impl AppView {
         container.into_any_element()
     }
 
-    fn camera_aspect_ratio(&self) -> f32 {
+    /// One column per hand, side by side, when the recognizer reports more
+    /// than one. `None` in single-hand mode, where the panel below already
+    /// describes the only hand.
+    fn render_hand_columns(&self) -> Option<AnyElement> {
+        let result = self.latest_result.as_ref()?;
+        if result.other_hands.is_empty() {
+            return None;
+        }
+        let primary = result.detail.as_ref()?;
+        let primary_x = result
+            .landmarks
+            .as_ref()
+            .and_then(|points| points.first())
+            .map_or(0.0, |point| point.0);
+
+        let mut hands = vec![(primary, result.confidence, primary_x)];
+        hands.extend(result.other_hands.iter().map(|hand| {
+            let wrist_x = hand.landmarks.first().map_or(0.0, |point| point.0);
+            (&hand.detail, hand.confidence, wrist_x)
+        }));
+        let labels = hand_labels(
+            &hands
+                .iter()
+                .map(|(detail, _, x)| (detail.handedness, *x))
+                .collect::<Vec<_>>(),
+        );
+
+        let mut row = h_flex().w_full().gap_2().items_start();
+        for ((detail, confidence, _), label) in hands.iter().zip(labels) {
+            let mut fingers = h_flex().flex_wrap().gap_1();
+            for (name, state) in FINGER_LABELS.iter().zip(detail.finger_states) {
+                fingers = fingers.child(self.finger_chip(name, state));
+            }
+            row = row.child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_2()
+                    .p_3()
+                    .rounded_md()
+                    .bg(gpui::rgba(0xffffff0d))
+                    .child(self.caption(gpui::rgb(0x38bdf8)).child(label))
+                    .child(
+                        super::div()
+                            .text_lg()
+                            .font_semibold()
+                            .text_color(gpui::rgb(0xffffff))
+                            .child(format!(
+                                "{}{}",
+                                detail.primary.emoji(),
+                                detail.primary.display_name()
+                            )),
+                    )
+                    .child(self.caption(gpui::rgb(0x94a3b8)).child(format!(
+                        "置信度 {:.0}%",
+                        (confidence * 100.0).clamp(0.0, 100.0)
+                    )))
+                    .child(fingers),
+            );
+        }
+
+        Some(
+            v_flex()
+                .gap_1()
+                .child(
+                    self.caption(gpui::rgb(0x94a3b8))
+                        .child(format!("多手模式 · {} 只手", hands.len())),
+                )
+                .child(row)
+                .into_any_element(),
+        )
+    }
+
+    /// Every gesture the classifier knows, as wrapped emoji + name chips.
+    fn render_gesture_legend(&self, panel_width: f32) -> AnyElement {
+        let mut chips = h_flex().flex_wrap().gap_2();
+        for kind in GestureKind::all() {
+            chips = chips.child(
+                super::div()
+                    .px(super::px(8.0))
+                    .py(super::px(4.0))
+                    .rounded_md()
+                    .bg(gpui::rgba(0xffffff14))
+                    .child(self.caption(gpui::rgb(0xcbd5e1)).child(format!(
+                        "{}{}",
+                        kind.emoji(),
+                        kind.display_name()
+                    ))),
+            );
+        }
+
+        v_flex()
+            .w(super::px(panel_width))
+            .gap_2()
+            .p_4()
+            .rounded_lg()
+            .bg(gpui::rgb(0x0f172a))
+            .border_1()
+            .border_color(gpui::rgba(0xffffff1a))
+            .child(
+                super::div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(gpui::rgb(0xffffff))
+                    .child(format!("可识别的手势 ({})", GestureKind::all().len())),
+            )
+            .child(chips)
+            .into_any_element()
+    }
+
+    pub(super) fn camera_aspect_ratio(&self) -> f32 {
         if let Some(frame) = &self.latest_frame {
             if frame.height > 0 {
                 return frame.width as f32 / frame.height as f32;
@@ -487,8 +1175,12 @@ impl AppView {
             }
 
             let delta_x = f32::from(event.position.x) - state.start_pointer_x;
-            let target_width = state.start_width - delta_x;
-            let new_width = target_width.clamp(RIGHT_PANEL_MIN_WIDTH, RIGHT_PANEL_MAX_WIDTH);
+            let new_width = resize_width(
+                state.start_width,
+                delta_x,
+                RIGHT_PANEL_MIN_WIDTH,
+                RIGHT_PANEL_MAX_WIDTH,
+            );
             if (new_width - self.right_panel_width).abs() > f32::EPSILON {
                 self.right_panel_width = new_width;
                 cx.notify();
@@ -520,30 +1212,48 @@ impl AppView {
         }
     }
 
+    /// Secondary text: small and in `color` normally, larger and pure white
+    /// in high-contrast mode.
+    pub(super) fn caption<C>(&self, color: C) -> gpui::Div
+    where
+        C: Into<gpui::Hsla>,
+    {
+        if self.settings.high_contrast {
+            super::div().text_sm().text_color(gpui::rgb(0xffffff))
+        } else {
+            super::div().text_xs().text_color(color)
+        }
+    }
+
     fn stat_chip<C>(&self, label: &str, value: &str, color: C) -> AnyElement
     where
         C: Into<gpui::Rgba>,
     {
         let color = color.into();
+        let high_contrast = self.settings.high_contrast;
         super::div()
             .px(super::px(10.0))
             .py(super::px(6.0))
             .rounded_md()
-            .bg(gpui::rgba(0xffffff14))
+            .bg(if high_contrast {
+                gpui::rgba(0x000000ff)
+            } else {
+                gpui::rgba(0xffffff14)
+            })
             .border_1()
-            .border_color(gpui::rgba(0xffffff12))
+            .border_color(if high_contrast {
+                gpui::rgba(0xffffffff)
+            } else {
+                gpui::rgba(0xffffff12)
+            })
             .child(
                 v_flex()
                     .gap_1()
+                    .child(self.caption(gpui::rgb(0x9ca3af)).child(label.to_string()))
                     .child(
                         super::div()
-                            .text_xs()
-                            .text_color(gpui::rgb(0x9ca3af))
-                            .child(label.to_string()),
-                    )
-                    .child(
-                        super::div()
-                            .text_sm()
+                            .when(high_contrast, |this| this.text_lg())
+                            .when(!high_contrast, |this| this.text_sm())
                             .font_semibold()
                             .text_color(color)
                             .child(value.to_string()),
@@ -552,12 +1262,90 @@ impl AppView {
             .into_any_element()
     }
 
+    fn probability_row(&self, kind: GestureKind, prob: f32) -> AnyElement {
+        const BAR_WIDTH: f32 = 160.0;
+        let prob = prob.clamp(0.0, 1.0);
+
+        h_flex()
+            .gap_2()
+            .items_center()
+            .child(
+                self.caption(gpui::rgb(0xcbd5e1))
+                    .w(super::px(120.0))
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .child(format!("{}{}", kind.emoji(), kind.display_name())),
+            )
+            .child(
+                super::div()
+                    .w(super::px(BAR_WIDTH))
+                    .h(super::px(6.0))
+                    .rounded_full()
+                    .bg(gpui::rgba(0xffffff14))
+                    .child(
+                        super::div()
+                            .w(super::px(BAR_WIDTH * prob))
+                            .h_full()
+                            .rounded_full()
+                            .bg(gpui::rgb(0x38bdf8)),
+                    ),
+            )
+            .child(
+                self.caption(gpui::rgb(0x94a3b8))
+                    .child(format!("{:.0}%", prob * 100.0)),
+            )
+            .into_any_element()
+    }
+
+    /// Schematic hand, thumb to pinky, with each finger tinted by its state.
+    /// Only the colors change per frame; the geometry is fixed.
+    fn finger_diagram(&self, states: &[FingerState; 5]) -> AnyElement {
+        // (left, top, width, height) in px within a 92x104 box.
+        const FINGERS: [(f32, f32, f32, f32); 5] = [
+            (4.0, 56.0, 14.0, 30.0),
+            (22.0, 16.0, 11.0, 42.0),
+            (35.0, 6.0, 11.0, 52.0),
+            (48.0, 12.0, 11.0, 46.0),
+            (61.0, 26.0, 11.0, 32.0),
+        ];
+
+        let mut diagram = super::div()
+            .relative()
+            .w(super::px(92.0))
+            .h(super::px(104.0))
+            .child(
+                super::div()
+                    .absolute()
+                    .left(super::px(18.0))
+                    .top(super::px(52.0))
+                    .w(super::px(58.0))
+                    .h(super::px(48.0))
+                    .rounded_lg()
+                    .bg(gpui::rgba(0xffffff14))
+                    .border_1()
+                    .border_color(gpui::rgba(0xffffff1f)),
+            );
+        for ((left, top, width, height), state) in FINGERS.iter().zip(states) {
+            let (bg, fg) = finger_state_colors(*state);
+            diagram = diagram.child(
+                super::div()
+                    .absolute()
+                    .left(super::px(*left))
+                    .top(super::px(*top))
+                    .w(super::px(*width))
+                    .h(super::px(*height))
+                    .rounded_full()
+                    .bg(bg)
+                    .border_1()
+                    .border_color(fg),
+            );
+        }
+        diagram.into_any_element()
+    }
+
     fn finger_chip(&self, label: &str, state: FingerState) -> AnyElement {
-        let (bg, fg) = match state {
-            FingerState::Extended => (gpui::rgba(0x15803d40), gpui::rgb(0x34d399)),
-            FingerState::HalfBent => (gpui::rgba(0x1d4ed840), gpui::rgb(0x93c5fd)),
-            FingerState::Folded => (gpui::rgba(0x7f1d1d40), gpui::rgb(0xfca5a5)),
-        };
+        let (bg, fg) = finger_state_colors(state);
 
         super::div()
             .px(super::px(10.0))
@@ -568,7 +1356,8 @@ impl AppView {
             .border_color(gpui::rgba(0xffffff12))
             .child(
                 super::div()
-                    .text_xs()
+                    .when(self.settings.high_contrast, |this| this.text_sm())
+                    .when(!self.settings.high_contrast, |this| this.text_xs())
                     .font_semibold()
                     .text_color(fg)
                     .child(format!("{label}: {}", state.label())),
@@ -576,3 +1365,130 @@ impl AppView {
             .into_any_element()
     }
 }
+
+/// Panel width after dragging its handle by `delta_x` pixels. The handle
+/// sits on the left edge of the right-docked panel, so dragging left
+/// (negative `delta_x`) widens it.
+fn resize_width(start_width: f32, delta_x: f32, min: f32, max: f32) -> f32 {
+    (start_width - delta_x).clamp(min, max)
+}
+
+/// Preview height that keeps the camera's aspect ratio at `panel_width`.
+fn camera_height_for(panel_width: f32, aspect_ratio: f32) -> f32 {
+    (panel_width / aspect_ratio).clamp(super::CAMERA_MIN_SIZE.1, super::CAMERA_MAX_SIZE.1)
+}
+
+/// 左手/右手 labels for multi-hand columns from each hand's reported
+/// handedness and wrist x in frame pixels. When the model reports the same
+/// side twice, or can't tell, screen position decides instead: the camera
+/// faces the user, so the hand further left in the frame is their right
+/// hand. Beyond two hands they are simply numbered left to right.
+fn hand_labels(hands: &[(Handedness, f32)]) -> Vec<String> {
+    let reported_distinct = hands.len() <= 2
+        && hands
+            .iter()
+            .all(|(handedness, _)| *handedness != Handedness::Unknown)
+        && hands.first().map(|first| first.0) != hands.get(1).map(|second| second.0);
+    if reported_distinct {
+        return hands
+            .iter()
+            .map(|(handedness, _)| handedness.label().to_string())
+            .collect();
+    }
+
+    let mut order: Vec<usize> = (0..hands.len()).collect();
+    order.sort_by(|a, b| hands[*a].1.total_cmp(&hands[*b].1));
+    let mut labels = vec![String::new(); hands.len()];
+    for (rank, idx) in order.into_iter().enumerate() {
+        labels[idx] = match (hands.len(), rank) {
+            (2, 0) => Handedness::Right.label().to_string(),
+            (2, _) => Handedness::Left.label().to_string(),
+            _ => format!("手 {}", rank + 1),
+        };
+    }
+    labels
+}
+
+/// Background and foreground colors for a finger state: green extended,
+/// blue half-bent, red folded.
+fn finger_state_colors(state: FingerState) -> (gpui::Rgba, gpui::Rgba) {
+    match state {
+        FingerState::Extended => (gpui::rgba(0x15803d40), gpui::rgb(0x34d399)),
+        FingerState::HalfBent => (gpui::rgba(0x1d4ed840), gpui::rgb(0x93c5fd)),
+        FingerState::Folded => (gpui::rgba(0x7f1d1d40), gpui::rgb(0xfca5a5)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dragging_left_widens_the_right_docked_panel() {
+        assert_eq!(resize_width(400.0, -50.0, 320.0, 720.0), 450.0);
+        assert_eq!(resize_width(400.0, 50.0, 320.0, 720.0), 350.0);
+    }
+
+    #[test]
+    fn panel_width_is_clamped_to_its_limits() {
+        assert_eq!(resize_width(400.0, 200.0, 320.0, 720.0), 320.0);
+        assert_eq!(resize_width(400.0, -500.0, 320.0, 720.0), 720.0);
+    }
+
+    #[test]
+    fn preview_height_follows_the_aspect_ratio_within_limits() {
+        assert_eq!(camera_height_for(480.0, 4.0 / 3.0), 360.0);
+        assert_eq!(camera_height_for(640.0, 16.0 / 9.0), 360.0);
+        // Too wide a frame hits the minimum height, too tall one the maximum.
+        assert_eq!(
+            camera_height_for(320.0, 4.0),
+            super::super::CAMERA_MIN_SIZE.1
+        );
+        assert_eq!(
+            camera_height_for(720.0, 0.5),
+            super::super::CAMERA_MAX_SIZE.1
+        );
+    }
+
+    #[test]
+    fn distinct_reported_handedness_is_kept_whatever_the_position() {
+        let labels = hand_labels(&[(Handedness::Left, 500.0), (Handedness::Right, 100.0)]);
+        assert_eq!(
+            labels,
+            [Handedness::Left.label(), Handedness::Right.label()]
+        );
+    }
+
+    #[test]
+    fn duplicate_handedness_falls_back_to_screen_position() {
+        let labels = hand_labels(&[(Handedness::Right, 500.0), (Handedness::Right, 100.0)]);
+        assert_eq!(
+            labels,
+            [Handedness::Left.label(), Handedness::Right.label()]
+        );
+    }
+
+    #[test]
+    fn unknown_handedness_falls_back_to_screen_position() {
+        let labels = hand_labels(&[(Handedness::Unknown, 100.0), (Handedness::Left, 500.0)]);
+        assert_eq!(
+            labels,
+            [Handedness::Right.label(), Handedness::Left.label()]
+        );
+        assert_eq!(hand_labels(&[(Handedness::Unknown, 100.0)]), ["手 1"]);
+        assert_eq!(
+            hand_labels(&[(Handedness::Left, 100.0)]),
+            [Handedness::Left.label()]
+        );
+    }
+
+    #[test]
+    fn more_than_two_hands_are_numbered_left_to_right() {
+        let labels = hand_labels(&[
+            (Handedness::Left, 300.0),
+            (Handedness::Right, 100.0),
+            (Handedness::Left, 200.0),
+        ]);
+        assert_eq!(labels, ["手 3", "手 1", "手 2"]);
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     mem,
+    path::PathBuf,
     sync::Arc,
     thread,
     time::{Duration, Instant},
@@ -8,27 +9,45 @@ use std::{
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    AnyElement, App, AppContext, Context, Hsla, InteractiveElement, IntoElement, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ObjectFit, ParentElement, Render, RenderImage,
-    SharedString, Styled, StyledImage, TitlebarOptions, Window, WindowControlArea,
-    WindowDecorations, WindowOptions, div, img, px,
+    AnyElement, App, AppContext, Context, Div, Entity, Hsla, InteractiveElement, IntoElement,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ObjectFit, ParentElement, Render,
+    RenderImage, SharedString, Styled, StyledImage, Subscription, TitlebarOptions, Window,
+    WindowControlArea, WindowDecorations, WindowOptions, div, img, px,
+};
+use gpui_component::{
+    ActiveTheme, Root, StyledExt,
+    button::Button,
+    h_flex,
+    slider::{SliderEvent, SliderState},
+    v_flex,
 };
-use gpui_component::{ActiveTheme, Root, StyledExt, button::Button, h_flex, v_flex};
 use image::{Frame as ImageFrame, ImageBuffer, Rgba};
 
+use self::{calibration::Calibration, settings::UiSettings};
 use crate::{
-    model_download::{ModelDownloadEvent, ModelKind},
+    emoji_output::EmojiFileOutput,
+    gesture_events::{GestureStability, GestureTrigger, GrabDetector, OutputMode},
+    ipc_output::IpcOutput,
+    model_download::{ModelDownloadEvent, ModelKind, models_available},
+    openpose_output::OpenPoseOutput,
     pipeline::{
-        CameraDevice, CameraStream, CompositedFrame, RecognizerBackend, start_frame_compositor,
-        start_recognizer,
+        CameraDevice, CameraStream, FrameHistory, RecognizerBackend, RenderActivity,
+        builder::Pipeline,
+        camera::CaptureConfig,
+        channel::DropCounter,
+        recognizer::{MAX_DETECTION_THRESHOLD, MIN_DETECTION_THRESHOLD, PowerSaveConfig},
     },
-    types::{Frame, GestureResult, RecognizedFrame},
+    snapshot_output::SnapshotOutput,
+    types::{Frame, GestureResult},
+    video_recorder::{RecordingConfig, VideoRecorder},
 };
 
+mod calibration;
 mod camera_view;
 mod download;
 mod main_view;
 mod render_util;
+mod settings;
 mod titlebar;
 
 const CAMERA_MIN_SIZE: (f32, f32) = (240.0, 180.0);
@@ -38,12 +57,59 @@ const RIGHT_PANEL_MIN_WIDTH: f32 = 320.0;
 const RIGHT_PANEL_MAX_WIDTH: f32 = 720.0;
 const RIGHT_PANEL_INITIAL_WIDTH: f32 = 480.0;
 const STARTUP_CARD_WIDTH: f32 = 420.0;
+const FRAME_HISTORY_WINDOW: Duration = Duration::from_millis(1_000);
+const FRAME_HISTORY_MAX_BYTES: usize = 64 * 1024 * 1024;
+const GAMMA_STEP: f32 = 0.1;
+const DETECTION_THRESHOLD_STEP: f32 = 0.01;
+const GESTURE_TRIGGER_COOLDOWN: Duration = Duration::from_millis(1_500);
+const GESTURE_STABILITY_WINDOW: usize = 15;
+/// Width and height of the preview guide rectangle, as fractions of the
+/// camera image.
+const DETECTION_ZONE_FRACTION: (f32, f32) = (0.6, 0.75);
+/// Maximum gap between the open and closed hand (and back) for a grab.
+const GRAB_WINDOW: Duration = Duration::from_millis(600);
+const CAMERA_REFRESH_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Preview frames above this many pixels are downscaled before the UI keeps
+/// them, so 4K cameras don't multiply memory use in the preview and history.
+const PREVIEW_PIXEL_BUDGET: u64 = 1920 * 1080;
+/// How long the recognition rate has to stay on the other side of the
+/// threshold before the low-fps warning appears or clears.
+const LOW_FPS_DEBOUNCE: Duration = Duration::from_secs(3);
+/// Interval over which dropped frames are turned into a per-second rate.
+const DROP_RATE_SAMPLE: Duration = Duration::from_secs(1);
+/// Weight of the newest sample in the displayed confidence EMA.
+const CONFIDENCE_SMOOTHING_ALPHA: f32 = 0.2;
+/// Minimum spacing between download speed samples; progress events arrive
+/// per chunk, far too often for a stable rate.
+const DOWNLOAD_SPEED_SAMPLE: Duration = Duration::from_millis(250);
+/// EMA weight of the newest download speed sample.
+const DOWNLOAD_SPEED_ALPHA: f64 = 0.3;
+
+/// Files and pipes that recognition results are mirrored to besides the
+/// window, from the command line.
+#[derive(Clone, Debug, Default)]
+pub struct OutputOptions {
+    /// Receives the emoji of every triggered gesture.
+    pub emoji_file: Option<PathBuf>,
+    /// Receives one OpenPose JSON file per frame with a hand.
+    pub openpose_dir: Option<PathBuf>,
+    pub openpose_mode: OutputMode,
+    /// Unix socket that every result is streamed to as JSON lines.
+    pub ipc_socket: Option<PathBuf>,
+    pub ipc_mode: OutputMode,
+    /// Receives a PNG of the preview on every "take picture" gesture.
+    pub snapshot_dir: Option<PathBuf>,
+    /// Export smoothed instead of raw landmarks when smoothing is on.
+    pub export_smoothed: bool,
+    /// Where the "开始录制" button saves the preview.
+    pub recording: RecordingConfig,
+}
 
 pub fn launch_ui(
     app: &mut App,
-    camera_frame_rx: Receiver<Frame>,
-    camera_frame_tx: Sender<Frame>,
     recognizer_backend: RecognizerBackend,
+    camera_arg: Option<String>,
+    outputs: OutputOptions,
 ) -> gpui::Result<()> {
     let window_options = WindowOptions {
         titlebar: Some(TitlebarOptions {
@@ -63,7 +129,7 @@ pub fn launch_ui(
     };
 
     app.open_window(window_options, move |window, app| {
-        let view = app.new(|_| AppView::new(camera_frame_rx, camera_frame_tx, recognizer_backend));
+        let view = app.new(|cx| AppView::new(recognizer_backend, camera_arg, outputs, cx));
         app.new(|cx| {
             let root = Root::new(view, window, cx);
             #[cfg(target_os = "macos")]
@@ -80,34 +146,90 @@ pub fn launch_ui(
 
 struct AppView {
     screen: Screen,
-    composited_rx: Option<Receiver<CompositedFrame>>,
-    camera_frame_rx: Option<Receiver<Frame>>,
-    camera_frame_tx: Sender<Frame>,
-    recognized_tx: Sender<RecognizedFrame>,
+    /// Recognizer and compositor; the recognizer starts once the models
+    /// are downloaded.
+    pipeline: Pipeline,
     recognizer_backend: RecognizerBackend,
-    _frame_compositor_handle: thread::JoinHandle<()>,
-    recognizer_handle: Option<thread::JoinHandle<()>>,
+    /// Marked on every render so the compositor can pause overlays while
+    /// the window is hidden.
+    render_activity: RenderActivity,
     camera_stream: Option<CameraStream>,
     available_cameras: Vec<CameraDevice>,
     selected_camera_idx: Option<usize>,
     camera_error: Option<String>,
     latest_frame: Option<Frame>,
+    /// Camera resolution before the preview pixel budget was applied.
+    camera_resolution: Option<(u32, u32)>,
+    preview_pixel_budget: u64,
+    /// Warm-up and MJPEG scaling for camera streams started from the UI.
+    camera_capture: CaptureConfig,
+    frame_history: FrameHistory,
     latest_result: Option<GestureResult>,
+    gesture_trigger: GestureTrigger,
+    grab_detector: GrabDetector,
+    gesture_stability: GestureStability,
     latest_image: Option<Arc<RenderImage>>,
     latest_fps: Option<f32>,
+    /// Display-only EMA of the hand confidence; `None` while no hand is seen.
+    smoothed_confidence: Option<f32>,
+    confidence_smoothing: f32,
+    latest_latency_ms: Option<f32>,
     last_frame_ts: Option<Instant>,
+    low_fps_alert: LowFpsAlert,
+    /// Camera frames dropped because the recognizer was still busy.
+    recognizer_drops: DropMeter,
+    /// Composited frames dropped because the UI hadn't taken the last one.
+    ui_drops: DropMeter,
+    download: DownloadState,
     download_rx: Receiver<DownloadMessage>,
     _download_handle: thread::JoinHandle<()>,
     camera_picker_open: bool,
+    /// Debug overlay numbering each landmark 0-20 on the preview.
+    show_landmark_indices: bool,
+    /// Holds the displayed result for demos and screenshots; the preview
+    /// and recognition keep running.
+    result_locked: bool,
+    /// Cheat-sheet of every recognizable gesture below the gesture panel.
+    show_gesture_legend: bool,
     right_panel_width: f32,
     panel_resize_state: Option<PanelResizeState>,
     is_refreshing_cameras: bool,
+    camera_refresh_rx: Option<Receiver<anyhow::Result<Vec<CameraDevice>>>>,
+    last_camera_refresh: Option<Instant>,
+    /// Set by `--snapshot-dir`; the only reader of `frame_history`, which
+    /// stays empty without it.
+    snapshot_output: Option<SnapshotOutput>,
+    /// Camera requested with `--camera`, consumed on the first selection screen.
+    camera_arg: Option<String>,
+    /// Remembered camera from the settings, consumed with `camera_arg`.
+    auto_start_camera: Option<String>,
+    /// Whether the camera started next is saved for auto-start.
+    remember_camera: bool,
+    /// Set by `--emoji-file`; receives every triggered gesture.
+    emoji_output: Option<EmojiFileOutput>,
+    /// Set by `--openpose-dir`; receives every frame with a hand.
+    openpose_output: Option<OpenPoseOutput>,
+    /// Set by `--ipc`; receives every result.
+    ipc_output: Option<IpcOutput>,
+    /// Whether the outputs above get smoothed landmarks.
+    export_smoothed: bool,
+    recording: RecordingConfig,
+    /// Records the annotated preview while set; toggled from the UI.
+    recorder: Option<VideoRecorder>,
+    settings: UiSettings,
+    /// Live palm detection threshold; changes go straight to the worker.
+    threshold_slider: Entity<SliderState>,
+    _threshold_subscription: Subscription,
 }
 
 enum Screen {
     Camera(CameraState),
-    Download(DownloadState),
+    /// Model download progress or failure; shown when a camera is picked
+    /// before the models are ready.
+    Download,
     Main,
+    /// Guided open-palm and fist capture for personal finger thresholds.
+    Calibration(Calibration),
 }
 
 enum CameraState {
@@ -133,6 +255,10 @@ struct DownloadState {
     gesture_classifier_ready: bool,
     current_model: Option<ModelKind>,
     start_time: Instant,
+    /// Time and byte count of the last speed sample for the current model.
+    speed_sample: Option<(Instant, u64)>,
+    /// Smoothed throughput in bytes per second.
+    bytes_per_sec: Option<f64>,
 }
 
 impl DownloadState {
@@ -148,9 +274,37 @@ impl DownloadState {
             gesture_classifier_ready: false,
             current_model: None,
             start_time: Instant::now(),
+            speed_sample: None,
+            bytes_per_sec: None,
         }
     }
 
+    /// Seconds left for the current model at the smoothed speed.
+    fn eta_secs(&self) -> Option<f64> {
+        let total = self.total?;
+        let speed = self.bytes_per_sec.filter(|speed| *speed > 0.0)?;
+        Some(total.saturating_sub(self.downloaded) as f64 / speed)
+    }
+
+    fn sample_speed(&mut self, downloaded: u64) {
+        let now = Instant::now();
+        let Some((last_time, last_bytes)) = self.speed_sample else {
+            self.speed_sample = Some((now, downloaded));
+            return;
+        };
+        let elapsed = now.duration_since(last_time);
+        if elapsed < DOWNLOAD_SPEED_SAMPLE {
+            return;
+        }
+
+        let current = downloaded.saturating_sub(last_bytes) as f64 / elapsed.as_secs_f64();
+        self.bytes_per_sec = Some(match self.bytes_per_sec {
+            Some(prev) => prev + DOWNLOAD_SPEED_ALPHA * (current - prev),
+            None => current,
+        });
+        self.speed_sample = Some((now, downloaded));
+    }
+
     fn update_from_event(&mut self, event: ModelDownloadEvent) {
         match event {
             ModelDownloadEvent::AlreadyPresent { model } => {
@@ -166,6 +320,8 @@ impl DownloadState {
                 self.current_model = Some(model);
                 self.downloaded = 0;
                 self.total = total;
+                self.speed_sample = None;
+                self.bytes_per_sec = None;
                 self.message = format!("Downloading {} model...", model_label(model));
             }
             ModelDownloadEvent::Progress {
@@ -176,6 +332,7 @@ impl DownloadState {
                 self.current_model = Some(model);
                 self.downloaded = downloaded;
                 self.total = total;
+                self.sample_speed(downloaded);
                 self.message = format!("Downloading {} model...", model_label(model));
             }
             ModelDownloadEvent::Finished { model } => {
@@ -208,6 +365,71 @@ enum DownloadMessage {
     Error(String),
 }
 
+/// Titlebar warning for a recognition rate below `min_fps`. It only flips
+/// once the rate has stayed on the other side for [`LOW_FPS_DEBOUNCE`], so
+/// brief dips don't make it flash.
+struct LowFpsAlert {
+    min_fps: Option<f32>,
+    shown: bool,
+    /// Since when the rate has disagreed with `shown`.
+    pending_since: Option<Instant>,
+}
+
+impl LowFpsAlert {
+    fn new(min_fps: Option<f32>) -> Self {
+        Self {
+            min_fps,
+            shown: false,
+            pending_since: None,
+        }
+    }
+
+    fn update(&mut self, fps: Option<f32>, now: Instant) {
+        let low = self.min_fps.zip(fps).is_some_and(|(min, fps)| fps < min);
+        if low == self.shown {
+            self.pending_since = None;
+            return;
+        }
+        let since = *self.pending_since.get_or_insert(now);
+        if now.saturating_duration_since(since) >= LOW_FPS_DEBOUNCE {
+            self.shown = low;
+            self.pending_since = None;
+        }
+    }
+}
+
+/// Per-second rate of a [`DropCounter`], resampled every
+/// [`DROP_RATE_SAMPLE`].
+struct DropMeter {
+    counter: DropCounter,
+    sample: Option<(Instant, u64)>,
+    per_sec: Option<f32>,
+}
+
+impl DropMeter {
+    fn new(counter: DropCounter) -> Self {
+        Self {
+            counter,
+            sample: None,
+            per_sec: None,
+        }
+    }
+
+    fn update(&mut self, now: Instant) {
+        let total = self.counter.total();
+        let Some((sampled_at, sampled_total)) = self.sample else {
+            self.sample = Some((now, total));
+            return;
+        };
+        let elapsed = now.saturating_duration_since(sampled_at);
+        if elapsed < DROP_RATE_SAMPLE {
+            return;
+        }
+        self.per_sec = Some(total.saturating_sub(sampled_total) as f32 / elapsed.as_secs_f32());
+        self.sample = Some((now, total));
+    }
+}
+
 struct PanelResizeState {
     start_pointer_x: f32,
     start_width: f32,
@@ -215,62 +437,139 @@ struct PanelResizeState {
 
 impl AppView {
     fn new(
-        camera_frame_rx: Receiver<Frame>,
-        camera_frame_tx: Sender<Frame>,
         recognizer_backend: RecognizerBackend,
+        camera_arg: Option<String>,
+        outputs: OutputOptions,
+        cx: &mut Context<'_, Self>,
     ) -> Self {
-        let (recognized_tx, recognized_rx) = crossbeam_channel::bounded(1);
-        let (composited_rx, compositor_handle) = start_frame_compositor(recognized_rx);
+        let render_activity = RenderActivity::default();
+        let ui_drops = DropCounter::default();
+        let pipeline = Pipeline::builder()
+            .with_backend(recognizer_backend.clone())
+            .with_render_activity(render_activity.clone())
+            .with_drop_counter(ui_drops.clone())
+            .defer_recognizer()
+            .build();
+        let recognizer_drops = DropMeter::new(pipeline.frame_tx.drops());
         let (download_tx, download_rx) = unbounded();
         let download_handle =
             download::spawn_model_download(recognizer_backend.clone(), download_tx);
-        let (_initial_camera_state, available_cameras) = Self::initial_camera_state();
+        let (initial_camera_state, available_cameras) = Self::initial_camera_state();
         let selected_camera_idx = if available_cameras.is_empty() {
             None
         } else {
             Some(0)
         };
 
+        // Camera selection never waits for the models: the download runs in
+        // the background and only gates the switch to recognition.
+        let availability = models_available();
+        if !availability.all_present() && !recognizer_backend.prefer_embedded_models() {
+            log::info!("models missing, downloading during camera selection: {availability:?}");
+        }
+        let screen = Screen::Camera(initial_camera_state);
+
+        let settings = UiSettings::load();
+        recognizer_backend.max_hands().set(settings.max_hands);
+        recognizer_backend
+            .power_save()
+            .set(settings.power_save.then(PowerSaveConfig::default));
+        if let Some(config) = settings.finger_config {
+            recognizer_backend.finger_control().set(config);
+        }
+
+        let threshold_slider = cx.new(|_| {
+            SliderState::new()
+                .min(MIN_DETECTION_THRESHOLD)
+                .max(MAX_DETECTION_THRESHOLD)
+                .step(DETECTION_THRESHOLD_STEP)
+                .default_value(recognizer_backend.detection_threshold().get())
+        });
+        let threshold_subscription = cx.subscribe(
+            &threshold_slider,
+            |this: &mut Self, _, event: &SliderEvent, cx| {
+                let SliderEvent::Change(value) = event;
+                this.recognizer_backend
+                    .detection_threshold()
+                    .set(value.start());
+                cx.notify();
+            },
+        );
+
         Self {
-            screen: Screen::Download(DownloadState::new()),
-            composited_rx: Some(composited_rx),
-            camera_frame_rx: Some(camera_frame_rx),
-            camera_frame_tx,
-            recognized_tx,
+            screen,
+            pipeline,
             recognizer_backend,
-            _frame_compositor_handle: compositor_handle,
-            recognizer_handle: None,
+            render_activity,
             camera_stream: None,
             available_cameras,
             selected_camera_idx,
             camera_error: None,
             latest_frame: None,
+            camera_resolution: None,
+            preview_pixel_budget: PREVIEW_PIXEL_BUDGET,
+            camera_capture: CaptureConfig::default(),
+            frame_history: FrameHistory::new(FRAME_HISTORY_WINDOW, FRAME_HISTORY_MAX_BYTES),
             latest_result: None,
+            gesture_trigger: GestureTrigger::new(GESTURE_TRIGGER_COOLDOWN),
+            grab_detector: GrabDetector::new(GRAB_WINDOW),
+            gesture_stability: GestureStability::new(GESTURE_STABILITY_WINDOW),
             latest_image: None,
             latest_fps: None,
+            smoothed_confidence: None,
+            confidence_smoothing: CONFIDENCE_SMOOTHING_ALPHA,
+            latest_latency_ms: None,
             last_frame_ts: None,
+            low_fps_alert: LowFpsAlert::new(settings.min_recognition_fps),
+            recognizer_drops,
+            ui_drops: DropMeter::new(ui_drops),
+            download: DownloadState::new(),
             download_rx,
             _download_handle: download_handle,
             camera_picker_open: false,
+            show_landmark_indices: false,
+            result_locked: false,
+            show_gesture_legend: false,
             right_panel_width: RIGHT_PANEL_INITIAL_WIDTH,
             panel_resize_state: None,
             is_refreshing_cameras: false,
+            camera_refresh_rx: None,
+            last_camera_refresh: None,
+            camera_arg,
+            auto_start_camera: settings.auto_start_camera.clone(),
+            remember_camera: settings.auto_start_camera.is_some(),
+            emoji_output: outputs.emoji_file.map(EmojiFileOutput::spawn),
+            openpose_output: outputs
+                .openpose_dir
+                .map(|dir| OpenPoseOutput::spawn(dir, outputs.openpose_mode)),
+            ipc_output: outputs
+                .ipc_socket
+                .and_then(|path| IpcOutput::spawn(path, outputs.ipc_mode)),
+            snapshot_output: outputs.snapshot_dir.map(SnapshotOutput::spawn),
+            export_smoothed: outputs.export_smoothed,
+            recording: outputs.recording,
+            recorder: None,
+            settings,
+            threshold_slider,
+            _threshold_subscription: threshold_subscription,
         }
     }
 
-    fn start_recognizer_if_needed(&mut self) {
-        if self.recognizer_handle.is_some() {
-            return;
+    /// Starts a new recording, or stops the running one, which finishes its
+    /// file in the background.
+    fn toggle_recording(&mut self) {
+        match self.recorder.take() {
+            Some(recorder) => log::info!("stopping recording {}", recorder.path().display()),
+            None => self.recorder = Some(VideoRecorder::start(&self.recording)),
         }
+    }
 
-        let Some(frame_rx) = self.camera_frame_rx.take() else {
-            log::warn!("missing frame receiver for recognizer");
-            return;
-        };
+    fn models_ready(&self) -> bool {
+        self.download.finished && self.download.error.is_none()
+    }
 
-        let backend = self.recognizer_backend.clone();
-        let handle = start_recognizer(backend, frame_rx, self.recognized_tx.clone());
-        self.recognizer_handle = Some(handle);
+    fn start_recognizer_if_needed(&mut self) {
+        self.pipeline.start_recognizer();
     }
 
     fn update_fps(&mut self, ts: Instant) {
@@ -288,6 +587,34 @@ impl AppView {
             }
         }
     }
+
+    fn update_confidence(&mut self, result: &GestureResult) {
+        // Restart from the raw value whenever a hand reappears so the readout
+        // doesn't climb up from the previous hand's value or from zero.
+        self.smoothed_confidence = if result.landmarks.is_some() {
+            let alpha = self.confidence_smoothing.clamp(0.0, 1.0);
+            Some(match self.smoothed_confidence {
+                Some(prev) => prev * (1.0 - alpha) + result.confidence * alpha,
+                None => result.confidence,
+            })
+        } else {
+            None
+        };
+    }
+
+    fn update_latency(&mut self, captured_at: Instant) {
+        // Capture → display delay, covering camera decode, inference and compositing.
+        let current = Instant::now()
+            .saturating_duration_since(captured_at)
+            .as_secs_f32()
+            * 1000.0;
+        let smoothed = if let Some(prev) = self.latest_latency_ms {
+            prev * 0.8 + current * 0.2
+        } else {
+            current
+        };
+        self.latest_latency_ms = Some(smoothed);
+    }
 }
 
 impl Render for AppView {
@@ -300,31 +627,44 @@ impl Render for AppView {
             cx.notify();
         });
 
+        self.poll_download_events();
+
         let mut screen = mem::replace(&mut self.screen, Screen::Main);
         let view = match screen {
             Screen::Camera(mut state) => {
+                if let Some(refreshed) = self.poll_camera_refresh() {
+                    state = refreshed;
+                }
+                self.apply_camera_arg(&mut state);
                 let view = self.render_camera_view(&mut state, window, cx);
                 match state {
-                    CameraState::Ready => {
+                    CameraState::Ready if self.models_ready() => {
                         self.start_recognizer_if_needed();
                         screen = Screen::Main;
                     }
+                    // The camera keeps streaming; recognition starts once the
+                    // download finishes.
+                    CameraState::Ready => screen = Screen::Download,
+                    _ if self.download.error.is_some() => screen = Screen::Download,
                     _ => {
                         screen = Screen::Camera(state);
                     }
                 }
                 view
             }
-            Screen::Download(mut state) => {
-                self.poll_download_events(&mut state);
-                let min_time_passed = state.start_time.elapsed() >= Duration::from_millis(1200);
-                let should_switch = state.finished && state.error.is_none() && min_time_passed;
-                let view = self.render_download_view(&state, cx);
-                if should_switch {
+            Screen::Download => {
+                let min_time_passed =
+                    self.download.start_time.elapsed() >= Duration::from_millis(1200);
+                let should_switch = self.models_ready() && min_time_passed;
+                let view = self.render_download_view(cx);
+                if !should_switch {
+                    screen = Screen::Download;
+                } else if self.camera_stream.is_some() {
+                    self.start_recognizer_if_needed();
+                    screen = Screen::Main;
+                } else {
                     let (initial_camera_state, _) = Self::initial_camera_state();
                     screen = Screen::Camera(initial_camera_state);
-                } else {
-                    screen = Screen::Download(state);
                 }
                 view
             }
@@ -332,6 +672,11 @@ impl Render for AppView {
                 screen = Screen::Main;
                 self.render_main(window, cx)
             }
+            Screen::Calibration(mut calibration) => {
+                let view = self.render_calibration(&mut calibration, window, cx);
+                screen = Screen::Calibration(calibration);
+                view
+            }
         };
         self.screen = screen;
         view
@@ -1,5 +1,27 @@
 use super::{Arc, ImageBuffer, ImageFrame, RenderImage, Rgba};
-use crate::{pipeline::skeleton, types::Frame};
+use crate::{
+    pipeline::{recognizer::common::downscale_frame, skeleton},
+    types::Frame,
+};
+
+/// Shrinks `frame` to at most `max_pixels` pixels, keeping its aspect ratio.
+/// Frames within budget, or ones that fail to resize, are returned as is.
+pub(super) fn fit_pixel_budget(frame: Frame, max_pixels: u64) -> Frame {
+    let pixels = frame.width as u64 * frame.height as u64;
+    if max_pixels == 0 || pixels <= max_pixels {
+        return frame;
+    }
+    let scale = (max_pixels as f64 / pixels as f64).sqrt();
+    let max_side = (frame.width.max(frame.height) as f64 * scale).floor() as u32;
+    match downscale_frame(&frame, max_side) {
+        Ok(Some((smaller, _))) => smaller,
+        Ok(None) => frame,
+        Err(err) => {
+            log::warn!("preview downscale failed: {err:?}");
+            frame
+        }
+    }
+}
 
 pub(super) fn frame_to_image(
     frame: &Frame,
@@ -10,12 +32,40 @@ pub(super) fn frame_to_image(
         skeleton::draw_skeleton(&mut rgba, frame.width, frame.height, points);
     }
 
-    for px in rgba.chunks_exact_mut(4) {
-        px.swap(0, 2);
-    }
+    rgba_to_bgra_in_place(&mut rgba);
 
     let buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(frame.width, frame.height, rgba)?;
     let frame = ImageFrame::new(buffer);
 
     Some(Arc::new(RenderImage::new(vec![frame])))
 }
+
+/// Swaps red and blue in an RGBA8 buffer, giving the BGRA order gpui's
+/// `RenderImage` expects. A trailing partial pixel is left alone.
+pub(super) fn rgba_to_bgra_in_place(buf: &mut [u8]) {
+    for px in buf.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swaps_red_and_blue_of_each_pixel() {
+        let mut buf = [1, 2, 3, 4, 10, 20, 30, 40];
+        rgba_to_bgra_in_place(&mut buf);
+        assert_eq!(buf, [3, 2, 1, 4, 30, 20, 10, 40]);
+        // Swapping again restores RGBA.
+        rgba_to_bgra_in_place(&mut buf);
+        assert_eq!(buf, [1, 2, 3, 4, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn leaves_a_trailing_partial_pixel_alone() {
+        let mut buf = [1, 2, 3, 4, 5, 6];
+        rgba_to_bgra_in_place(&mut buf);
+        assert_eq!(buf, [3, 2, 1, 4, 5, 6]);
+    }
+}
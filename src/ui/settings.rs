@@ -0,0 +1,243 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use gpui::ObjectFit;
+
+use crate::gesture::FingerClassifierConfig;
+
+const SETTINGS_FILENAME: &str = "settings.ini";
+
+/// UI preferences kept between launches as `key=value` lines next to the
+/// `models` directory. Unknown keys and malformed values are ignored so an
+/// older or hand-edited file never blocks startup.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct UiSettings {
+    /// Larger status text and maximum-contrast colors.
+    pub high_contrast: bool,
+    /// Guide rectangle marking where hands detect best. On until the user
+    /// turns it off, so first runs always show it.
+    pub show_detection_zone: bool,
+    /// Finger thresholds from the last calibration; `None` keeps the
+    /// built-in defaults.
+    pub finger_config: Option<FingerClassifierConfig>,
+    /// How the camera image fills the preview box.
+    pub preview_fit: PreviewFit,
+    /// Label of the camera to start without showing the picker, set by
+    /// "记住选择并自动启动"; `None` always shows the picker.
+    pub auto_start_camera: Option<String>,
+    /// Recognition rate below which the titlebar warns that the machine
+    /// can't keep up; `None` (stored as `0`) turns the warning off.
+    pub min_recognition_fps: Option<f32>,
+    /// Hands recognized per frame: 1, or 2 for two-handed use at the cost
+    /// of a second handpose pass whenever both are visible.
+    pub max_hands: usize,
+    /// Drop to a palm check per second after 10s without a hand, to save
+    /// battery when the app is left running.
+    pub power_save: bool,
+}
+
+/// Low enough that a healthy CPU-only run stays clear of it.
+const DEFAULT_MIN_RECOGNITION_FPS: f32 = 5.0;
+
+/// The preview's `ObjectFit` choices, kept separately because gpui's enum
+/// is neither `Copy` nor comparable.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum PreviewFit {
+    /// Whole frame visible, letterboxed.
+    #[default]
+    Contain,
+    /// Fills the box, cropping the overflowing sides.
+    Cover,
+    /// Fills the box, stretching the frame.
+    Fill,
+}
+
+impl PreviewFit {
+    pub fn object_fit(self) -> ObjectFit {
+        match self {
+            PreviewFit::Contain => ObjectFit::Contain,
+            PreviewFit::Cover => ObjectFit::Cover,
+            PreviewFit::Fill => ObjectFit::Fill,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PreviewFit::Contain => "适应",
+            PreviewFit::Cover => "裁切填充",
+            PreviewFit::Fill => "拉伸",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            PreviewFit::Contain => PreviewFit::Cover,
+            PreviewFit::Cover => PreviewFit::Fill,
+            PreviewFit::Fill => PreviewFit::Contain,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            PreviewFit::Contain => "contain",
+            PreviewFit::Cover => "cover",
+            PreviewFit::Fill => "fill",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "contain" => Some(PreviewFit::Contain),
+            "cover" => Some(PreviewFit::Cover),
+            "fill" => Some(PreviewFit::Fill),
+            _ => None,
+        }
+    }
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            show_detection_zone: true,
+            finger_config: None,
+            preview_fit: PreviewFit::default(),
+            auto_start_camera: None,
+            min_recognition_fps: Some(DEFAULT_MIN_RECOGNITION_FPS),
+            max_hands: 1,
+            power_save: false,
+        }
+    }
+}
+
+impl UiSettings {
+    pub fn load() -> Self {
+        let path = settings_path();
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                log::warn!("failed to read {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes the settings, logging instead of failing: losing a preference
+    /// is not worth interrupting the UI for.
+    pub fn save(&self) {
+        let path = settings_path();
+        if let Err(err) =
+            fs::write(&path, self.serialize()).with_context(|| format!("{}", path.display()))
+        {
+            log::warn!("failed to save settings: {err:?}");
+        }
+    }
+
+    fn parse(text: &str) -> Self {
+        let mut settings = Self::default();
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "high_contrast" => {
+                    settings.high_contrast = value.parse().unwrap_or(settings.high_contrast)
+                }
+                "show_detection_zone" => {
+                    settings.show_detection_zone =
+                        value.parse().unwrap_or(settings.show_detection_zone)
+                }
+                "preview_fit" => {
+                    settings.preview_fit =
+                        PreviewFit::from_key(value).unwrap_or(settings.preview_fit)
+                }
+                "auto_start_camera" if !value.is_empty() => {
+                    settings.auto_start_camera = Some(value.to_string())
+                }
+                "min_recognition_fps" => {
+                    if let Some(fps) = value
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|fps| fps.is_finite() && *fps >= 0.0)
+                    {
+                        settings.min_recognition_fps = (fps > 0.0).then_some(fps);
+                    }
+                }
+                "max_hands" => {
+                    if let Some(count) = value.parse::<usize>().ok().filter(|count| *count >= 1) {
+                        settings.max_hands = count;
+                    }
+                }
+                "power_save" => settings.power_save = value.parse().unwrap_or(settings.power_save),
+                "finger_thresholds" => {
+                    settings.finger_config =
+                        parse_finger_thresholds(value).or(settings.finger_config)
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    fn serialize(&self) -> String {
+        let mut text = format!(
+            "high_contrast={}\nshow_detection_zone={}\npreview_fit={}\nmin_recognition_fps={}\nmax_hands={}\npower_save={}\n",
+            self.high_contrast,
+            self.show_detection_zone,
+            self.preview_fit.key(),
+            self.min_recognition_fps.unwrap_or(0.0),
+            self.max_hands,
+            self.power_save
+        );
+        if let Some(label) = &self.auto_start_camera {
+            text.push_str(&format!("auto_start_camera={label}\n"));
+        }
+        if let Some(config) = &self.finger_config {
+            text.push_str(&format!(
+                "finger_thresholds={},{},{},{},{},{}\n",
+                config.extended_extension,
+                config.extended_straightness,
+                config.extended_reach,
+                config.folded_extension,
+                config.folded_straightness,
+                config.folded_reach
+            ));
+        }
+        text
+    }
+}
+
+/// The six calibrated non-thumb thresholds, comma separated in field order;
+/// the thumb keeps its defaults.
+fn parse_finger_thresholds(value: &str) -> Option<FingerClassifierConfig> {
+    let values = value
+        .split(',')
+        .map(|part| part.trim().parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let [
+        extended_extension,
+        extended_straightness,
+        extended_reach,
+        folded_extension,
+        folded_straightness,
+        folded_reach,
+    ] = values[..]
+    else {
+        return None;
+    };
+    Some(FingerClassifierConfig {
+        extended_extension,
+        extended_straightness,
+        extended_reach,
+        folded_extension,
+        folded_straightness,
+        folded_reach,
+        ..FingerClassifierConfig::default()
+    })
+}
+
+fn settings_path() -> PathBuf {
+    PathBuf::from(SETTINGS_FILENAME)
+}
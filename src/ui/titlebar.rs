@@ -1,6 +1,6 @@
 use super::{
-    AnyElement, AppView, Context, Hsla, InteractiveElement, IntoElement, ParentElement, Styled,
-    Window, WindowControlArea, div, h_flex, px,
+    AnyElement, AppView, Context, Div, FluentBuilder, Hsla, InteractiveElement, IntoElement,
+    ParentElement, Styled, Window, WindowControlArea, div, h_flex, px,
 };
 
 #[cfg(target_os = "windows")]
@@ -51,31 +51,74 @@ impl AppView {
                     .pr_3()
                     .h_full()
                     .items_center()
-                    .child(
-                        div()
-                            .px_2()
-                            .py_0p5()
-                            .rounded_md()
-                            .bg(gpui::rgba(0x00000033))
-                            .text_xs()
-                            .text_color(recognizer_color)
-                            .child(format!("{} {}", recognizer_icon, recognizer_text)),
-                    )
-                    .child(
-                        div()
-                            .px_2()
-                            .py_0p5()
-                            .rounded_md()
-                            .bg(gpui::rgba(0x00000033))
-                            .text_xs()
-                            .text_color(camera_color)
-                            .child(format!("{} {}", camera_icon, camera_text)),
-                    ),
+                    .child(self.status_chip(
+                        recognizer_color,
+                        gpui::rgba(0x00000033),
+                        format!("{} {}", recognizer_icon, recognizer_text),
+                    ))
+                    .child(self.status_chip(
+                        camera_color,
+                        gpui::rgba(0x00000033),
+                        format!("{} {}", camera_icon, camera_text),
+                    ))
+                    .when(self.result_locked, |this| {
+                        this.child(self.status_chip(
+                            gpui::rgb(0xfbbf24).into(),
+                            gpui::rgba(0xfbbf2433),
+                            "🔒 结果已锁定".to_string(),
+                        ))
+                    })
+                    .when(self.recognizer_backend.heartbeat().is_stalled(), |this| {
+                        this.child(self.status_chip(
+                            gpui::rgb(0xf87171).into(),
+                            gpui::rgba(0xf8717133),
+                            "⚠ 识别线程无响应".to_string(),
+                        ))
+                    })
+                    .when_some(self.recorder.as_ref(), |this, recorder| {
+                        let text = if recorder.has_failed() {
+                            "⚠ 录制失败".to_string()
+                        } else {
+                            let secs = recorder.elapsed().as_secs();
+                            format!("● 录制中 {:02}:{:02}", secs / 60, secs % 60)
+                        };
+                        this.child(self.status_chip(
+                            gpui::rgb(0xf87171).into(),
+                            gpui::rgba(0xf8717133),
+                            text,
+                        ))
+                    })
+                    .when(self.low_fps_alert.shown, |this| {
+                        this.child(self.status_chip(
+                            gpui::rgb(0xf87171).into(),
+                            gpui::rgba(0xf8717133),
+                            "⚠ 识别速度过低".to_string(),
+                        ))
+                    }),
             )
             .child(controls)
             .into_any_element()
     }
 
+    /// Titlebar status pill. High-contrast mode keeps the status color but
+    /// puts it on solid black at a larger size.
+    fn status_chip(&self, color: Hsla, bg: gpui::Rgba, text: String) -> Div {
+        let high_contrast = self.settings.high_contrast;
+        div()
+            .px_2()
+            .py_0p5()
+            .rounded_md()
+            .when(high_contrast, |this| {
+                this.text_sm()
+                    .bg(gpui::rgb(0x000000))
+                    .border_1()
+                    .border_color(color)
+            })
+            .when(!high_contrast, |this| this.text_xs().bg(bg))
+            .text_color(color)
+            .child(text)
+    }
+
     #[cfg(target_os = "windows")]
     fn render_windows_controls(
         &self,
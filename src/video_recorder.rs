@@ -0,0 +1,223 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{Sender, bounded};
+
+use crate::types::Frame;
+
+/// Frames queued for the encoder before new ones are dropped. Recording
+/// loses frames rather than holding up the UI, which would in turn hold up
+/// recognition.
+const ENCODER_BACKLOG: usize = 8;
+
+/// Where and how the recorder writes, from the command line.
+#[derive(Clone, Debug)]
+pub struct RecordingConfig {
+    pub dir: PathBuf,
+    pub format: VideoFormat,
+    pub fps: u32,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("recordings"),
+            format: VideoFormat::Mp4,
+            fps: 30,
+        }
+    }
+}
+
+impl RecordingConfig {
+    /// A new file name in `dir`, stamped with the current time.
+    fn next_path(&self) -> PathBuf {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.dir
+            .join(format!("session-{secs}.{}", self.format.extension()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VideoFormat {
+    /// H.264 in MP4, for playing back anywhere.
+    #[default]
+    Mp4,
+    /// VP9 in WebM, for browsers.
+    WebM,
+}
+
+impl VideoFormat {
+    pub fn from_flag(value: &str) -> Option<Self> {
+        match value {
+            "mp4" => Some(VideoFormat::Mp4),
+            "webm" => Some(VideoFormat::WebM),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            VideoFormat::Mp4 => "mp4",
+            VideoFormat::WebM => "webm",
+        }
+    }
+
+    fn codec(self) -> &'static str {
+        match self {
+            VideoFormat::Mp4 => "libx264",
+            VideoFormat::WebM => "libvpx-vp9",
+        }
+    }
+}
+
+/// Encodes the annotated preview into a video file while it lives.
+///
+/// Frames are piped as raw RGBA into an `ffmpeg` process, which has to be
+/// on the `PATH`; it is started with the first frame, once the size is
+/// known. The output runs at a constant `fps`: frames are repeated or
+/// skipped by their capture time, so the video plays back in real time
+/// whatever rate the preview ran at. Frames of a different size, e.g.
+/// after switching cameras, are skipped. Dropping the recorder finishes
+/// the file.
+pub struct VideoRecorder {
+    tx: Sender<Frame>,
+    path: PathBuf,
+    started: Instant,
+    failed: Arc<AtomicBool>,
+}
+
+impl VideoRecorder {
+    pub fn start(config: &RecordingConfig) -> Self {
+        let path = config.next_path();
+        let fps = config.fps.max(1);
+        let format = config.format;
+        let (tx, rx) = bounded::<Frame>(ENCODER_BACKLOG);
+        let failed = Arc::new(AtomicBool::new(false));
+        let failed_flag = failed.clone();
+        let output = path.clone();
+
+        thread::spawn(move || {
+            let mut encoder: Option<Encoder> = None;
+            let mut first_timestamp = None;
+            let mut written = 0u64;
+            for frame in rx {
+                let encoder = match &mut encoder {
+                    Some(encoder) => encoder,
+                    None => match Encoder::spawn(&output, format, &frame, fps) {
+                        Ok(spawned) => encoder.insert(spawned),
+                        Err(err) => {
+                            log::warn!("failed to start recording: {err:#}");
+                            failed_flag.store(true, Ordering::Relaxed);
+                            return;
+                        }
+                    },
+                };
+                if (frame.width, frame.height) != encoder.size {
+                    continue;
+                }
+
+                let first = *first_timestamp.get_or_insert(frame.timestamp);
+                let offset = frame.timestamp.saturating_duration_since(first);
+                let due = (offset.as_secs_f64() * fps as f64) as u64 + 1;
+                while written < due {
+                    if let Err(err) = encoder.stdin.write_all(&frame.rgba) {
+                        log::warn!("recording to {} stopped: {err}", output.display());
+                        failed_flag.store(true, Ordering::Relaxed);
+                        return;
+                    }
+                    written += 1;
+                }
+            }
+            if let Some(encoder) = encoder {
+                encoder.finish(&output);
+            }
+        });
+
+        log::info!("recording to {}", path.display());
+        Self {
+            tx,
+            path,
+            started: Instant::now(),
+            failed,
+        }
+    }
+
+    /// Queues a copy of `frame`, or drops it while the encoder is behind.
+    pub fn push(&self, frame: &Frame) {
+        if !self.tx.is_full() {
+            let _ = self.tx.try_send(frame.clone());
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Set once `ffmpeg` could not be started or stopped accepting frames;
+    /// nothing more is recorded after that.
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+struct Encoder {
+    child: Child,
+    stdin: ChildStdin,
+    size: (u32, u32),
+}
+
+impl Encoder {
+    fn spawn(path: &Path, format: VideoFormat, frame: &Frame, fps: u32) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let mut child = Command::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-y"])
+            .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+            .args(["-s", &format!("{}x{}", frame.width, frame.height)])
+            .args(["-framerate", &fps.to_string(), "-i", "-"])
+            // yuv420p needs even sides; pad odd ones by a pixel.
+            .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2"])
+            .args(["-c:v", format.codec(), "-pix_fmt", "yuv420p"])
+            .arg(path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .context("failed to run ffmpeg; is it installed and on the PATH?")?;
+        let stdin = child.stdin.take().context("ffmpeg has no stdin")?;
+        Ok(Self {
+            child,
+            stdin,
+            size: (frame.width, frame.height),
+        })
+    }
+
+    /// Closes the input and waits for `ffmpeg` to finalize the file.
+    fn finish(self, path: &Path) {
+        let Self {
+            mut child, stdin, ..
+        } = self;
+        drop(stdin);
+        match child.wait() {
+            Ok(status) if status.success() => log::info!("saved recording {}", path.display()),
+            Ok(status) => log::warn!("ffmpeg exited with {status} for {}", path.display()),
+            Err(err) => log::warn!("failed to wait for ffmpeg: {err}"),
+        }
+    }
+}